@@ -0,0 +1,744 @@
+/*!
+This module reads and writes fumen, the page-based board encoding used by many tetromino tools and
+websites (e.g. `v115@vhAAgH`, an empty board).
+
+Only the current version (`v115`) of the format is supported. The lowest 23 rows of the board
+can be represented, and a page's "rising garbage" line is discarded when decoding.
+*/
+
+use std::fmt;
+
+use crate::{ActivePiece, Board, Game, GameState, Line, Orientation, Tetromino, TileTypeID};
+
+/// One page of a fumen.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct FumenPage {
+    /// The board shown on the page.
+    pub board: Board,
+    /// The piece shown on the page, if any.
+    pub piece: Option<ActivePiece>,
+    /// Whether the piece is locked into the board (and full lines are cleared) for the next page.
+    pub lock: bool,
+    /// The comment of the page, which stays the same on the following pages unless changed.
+    pub comment: String,
+}
+
+/// An error that can be returned by [`decode`].
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum FumenError {
+    /// The data is not prefixed by a supported version (`v115@`).
+    UnsupportedVersion,
+    /// The data contains a character not used by the encoding.
+    InvalidCharacter(char),
+    /// The data ends in the middle of a page.
+    UnexpectedEnd,
+    /// The data decodes to nonsensical values, such as a piece outside of the board.
+    InvalidData,
+}
+
+impl fmt::Display for FumenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FumenError::UnsupportedVersion => write!(f, "unsupported fumen version"),
+            FumenError::InvalidCharacter(ch) => write!(f, "invalid character {ch:?} in fumen"),
+            FumenError::UnexpectedEnd => write!(f, "fumen ends unexpectedly"),
+            FumenError::InvalidData => write!(f, "invalid fumen data"),
+        }
+    }
+}
+
+impl std::error::Error for FumenError {}
+
+const VERSION: &str = "115@";
+const ENCODE_TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+/// Printable ASCII, in which comments are encoded.
+const COMMENT_TABLE_LEN: u32 = 96;
+const FIELD_TOP: usize = 23;
+const FIELD_BLOCKS: usize = (FIELD_TOP + 1) * Game::WIDTH;
+
+/// The cells of a fumen field, from the top left to the bottom right, followed by the garbage line.
+type Field = [u8; FIELD_BLOCKS];
+
+impl Default for FumenPage {
+    fn default() -> Self {
+        Self {
+            board: vec![Line::default(); Game::HEIGHT],
+            piece: None,
+            lock: true,
+            comment: String::new(),
+        }
+    }
+}
+
+impl FumenPage {
+    /// Creates a page showing the board, the active piece and, as quiz comment, the upcoming pieces
+    /// of a game.
+    pub fn from_state(state: &GameState) -> Self {
+        let piece = state.active_piece_data.map(|(piece, _)| piece);
+        let queue = piece
+            .iter()
+            .map(|piece| piece.shape)
+            .chain(state.next_pieces.iter().copied())
+            .collect::<Vec<_>>();
+        Self {
+            board: state.board.clone(),
+            piece,
+            lock: true,
+            comment: quiz_comment(state.hold_piece.map(|(held, _)| held), &queue),
+        }
+    }
+}
+
+/// Formats a quiz comment (`#Q=[hold](current)next`) listing the pieces available on a page.
+///
+/// The first piece of the queue is the current piece.
+pub fn quiz_comment(hold: Option<Tetromino>, queue: &[Tetromino]) -> String {
+    let hold = hold.map_or(String::new(), |piece| piece_char(piece).to_string());
+    let (current, next) = match queue.split_first() {
+        Some((current, next)) => (piece_char(*current).to_string(), next),
+        None => (String::new(), queue),
+    };
+    let next = next
+        .iter()
+        .map(|piece| piece_char(*piece))
+        .collect::<String>();
+    format!("#Q=[{hold}]({current}){next}")
+}
+
+/// Reads the hold piece and the queue (current piece first) from a quiz comment.
+///
+/// Returns `None` if the comment is not a quiz comment.
+pub fn parse_quiz(comment: &str) -> Option<(Option<Tetromino>, Vec<Tetromino>)> {
+    let quiz = comment.strip_prefix("#Q=[")?;
+    let (hold, quiz) = quiz.split_once("](")?;
+    let (current, next) = quiz.split_once(')')?;
+    let pieces = |text: &str| text.chars().map(char_piece).collect::<Option<Vec<_>>>();
+    let hold = pieces(hold)?.first().copied();
+    let mut queue = pieces(current)?;
+    queue.extend(pieces(next.split(';').next().unwrap_or(""))?);
+    Some((hold, queue))
+}
+
+/// Decodes all pages of a fumen, given as data (`v115@...`) or as a link containing it.
+///
+/// # Examples
+///
+/// ```
+/// use tetrs_engine::fumen;
+///
+/// let pages = fumen::decode("v115@vhAAgH").unwrap();
+/// assert_eq!(pages.len(), 1);
+/// assert!(pages[0].board.iter().flatten().all(Option::is_none));
+/// assert_eq!(fumen::encode(&pages), "v115@vhAAgH");
+/// ```
+pub fn decode(fumen: &str) -> Result<Vec<FumenPage>, FumenError> {
+    let Some((_, data)) = fumen.trim().split_once(VERSION) else {
+        return Err(FumenError::UnsupportedVersion);
+    };
+    let mut values = Values::parse(data)?;
+    let mut pages = Vec::new();
+    let mut prev_field: Field = [0; FIELD_BLOCKS];
+    let mut prev_comment = String::new();
+    let mut repeat_count = 0;
+    while !values.is_empty() {
+        // Field.
+        let mut field = prev_field;
+        if repeat_count > 0 {
+            repeat_count -= 1;
+        } else {
+            let mut index = 0;
+            while index < FIELD_BLOCKS {
+                let value = values.poll(2)? as usize;
+                let (diff, count) = (value / FIELD_BLOCKS, value % FIELD_BLOCKS + 1);
+                if index + count > FIELD_BLOCKS || diff > 16 {
+                    return Err(FumenError::InvalidData);
+                }
+                if diff == 8 && count == FIELD_BLOCKS {
+                    repeat_count = values.poll(1)?;
+                }
+                for cell in &mut field[index..index + count] {
+                    let block = i64::from(*cell) + diff as i64 - 8;
+                    *cell = u8::try_from(block)
+                        .ok()
+                        .filter(|block| *block <= 8)
+                        .ok_or(FumenError::InvalidData)?;
+                }
+                index += count;
+            }
+        }
+        // Action.
+        let mut action = values.poll(3)?;
+        let block = (action % 8) as u8;
+        action /= 8;
+        let rotation = action % 4;
+        action /= 4;
+        let coordinate = (action % FIELD_BLOCKS as u32) as usize;
+        action /= FIELD_BLOCKS as u32;
+        let [rise, mirror, _colorize, has_comment, no_lock] =
+            [0, 1, 2, 3, 4].map(|bit| action >> bit & 1 == 1);
+        // Comment.
+        if has_comment {
+            let length = values.poll(2)? as usize;
+            let mut escaped = String::new();
+            for _ in 0..length.div_ceil(4) {
+                let mut chunk = values.poll(5)?;
+                for _ in 0..4 {
+                    escaped.push(char::from(b' ' + (chunk % COMMENT_TABLE_LEN) as u8));
+                    chunk /= COMMENT_TABLE_LEN;
+                }
+            }
+            escaped.truncate(length);
+            prev_comment = unescape(&escaped);
+        }
+        let piece = match block {
+            0 => None,
+            block => {
+                let shape = block_piece(block).ok_or(FumenError::InvalidData)?;
+                let y = (FIELD_TOP - 1).checked_sub(coordinate / Game::WIDTH);
+                let piece =
+                    y.and_then(|y| fumen_piece(shape, rotation, (coordinate % Game::WIDTH, y)));
+                Some(piece.ok_or(FumenError::InvalidData)?)
+            }
+        };
+        let lock = !no_lock;
+        pages.push(FumenPage {
+            board: field_to_board(&field),
+            piece,
+            lock,
+            comment: prev_comment.clone(),
+        });
+        // Prepare field of next page.
+        if lock {
+            if let Some(piece) = piece {
+                for ((x, y), tile_type_id) in piece.tiles() {
+                    field[field_index(x, y)] = tile_block(tile_type_id);
+                }
+            }
+            clear_lines(&mut field);
+            if rise {
+                field.copy_within(Game::WIDTH.., 0);
+                field[FIELD_BLOCKS - Game::WIDTH..].fill(0);
+            }
+            if mirror {
+                for row in field[..FIELD_BLOCKS - Game::WIDTH].chunks_mut(Game::WIDTH) {
+                    row.reverse();
+                }
+            }
+        }
+        prev_field = field;
+    }
+    if pages.is_empty() {
+        return Err(FumenError::UnexpectedEnd);
+    }
+    Ok(pages)
+}
+
+/// Encodes pages as a fumen (`v115@...`).
+///
+/// Rows of the board above the 23 representable ones are ignored, as are pieces whose center would
+/// lie outside of the representable board.
+pub fn encode(pages: &[FumenPage]) -> String {
+    let mut values = Vec::new();
+    let mut prev_field: Field = [0; FIELD_BLOCKS];
+    let mut prev_comment = String::new();
+    // Where the number of repeated unchanged fields is stored, if the last field was unchanged.
+    let mut repeat_index: Option<usize> = None;
+    for (page_index, page) in pages.iter().enumerate() {
+        // Field.
+        let mut field = board_to_field(&page.board);
+        let mut runs: Vec<(usize, usize)> = Vec::new();
+        for (cell, prev_cell) in field.iter().zip(&prev_field) {
+            let diff = usize::from(*cell) + 8 - usize::from(*prev_cell);
+            match runs.last_mut() {
+                Some((last_diff, count)) if *last_diff == diff && *count < FIELD_BLOCKS => {
+                    *count += 1;
+                }
+                _ => runs.push((diff, 1)),
+            }
+        }
+        let unchanged = runs == [(8, FIELD_BLOCKS)];
+        match repeat_index {
+            Some(index) if unchanged && values[index] < ENCODE_TABLE.len() as u32 - 1 => {
+                values[index] += 1;
+            }
+            _ => {
+                for (diff, count) in runs {
+                    push_values(&mut values, (diff * FIELD_BLOCKS + count - 1) as u32, 2);
+                }
+                repeat_index = unchanged.then(|| {
+                    values.push(0);
+                    values.len() - 1
+                });
+            }
+        }
+        // Action.
+        let (block, rotation, coordinate) = page.piece.and_then(piece_action).unwrap_or((0, 0, 0));
+        let comment = escape(&page.comment);
+        let comment = &comment[..comment.len().min(4095)];
+        let has_comment = comment != prev_comment;
+        let flags = [false, false, page_index == 0, has_comment, !page.lock];
+        let flags = flags
+            .iter()
+            .enumerate()
+            .map(|(bit, flag)| u32::from(*flag) << bit)
+            .sum::<u32>();
+        let action = u32::from(block)
+            + 8 * (rotation + 4 * (coordinate as u32 + FIELD_BLOCKS as u32 * flags));
+        push_values(&mut values, action, 3);
+        // Comment.
+        if has_comment {
+            push_values(&mut values, comment.len() as u32, 2);
+            for chunk in comment.as_bytes().chunks(4) {
+                let chunk = chunk.iter().rev().fold(0, |value, byte| {
+                    value * COMMENT_TABLE_LEN + u32::from(byte - b' ')
+                });
+                push_values(&mut values, chunk, 5);
+            }
+            prev_comment = comment.to_string();
+        }
+        // Prepare field of next page.
+        if page.lock {
+            if let Some(piece) = page.piece.filter(|piece| piece_action(*piece).is_some()) {
+                for ((x, y), tile_type_id) in piece.tiles() {
+                    if y < FIELD_TOP {
+                        field[field_index(x, y)] = tile_block(tile_type_id);
+                    }
+                }
+            }
+            clear_lines(&mut field);
+        }
+        prev_field = field;
+    }
+    let data = values
+        .into_iter()
+        .map(|value| char::from(ENCODE_TABLE[value as usize]))
+        .collect::<String>();
+    // Long fumen are broken up by question marks, as done by the original encoder.
+    let mut fumen = format!("v{VERSION}");
+    for (i, ch) in data.chars().enumerate() {
+        if i >= 42 && (i - 42) % 47 == 0 {
+            fumen.push('?');
+        }
+        fumen.push(ch);
+    }
+    fumen
+}
+
+/// The encoded values of a fumen, read from the front.
+struct Values(std::vec::IntoIter<u32>);
+
+impl Values {
+    fn parse(data: &str) -> Result<Self, FumenError> {
+        let values = data
+            .chars()
+            .filter(|ch| *ch != '?' && !ch.is_whitespace())
+            .map(|ch| {
+                ENCODE_TABLE
+                    .iter()
+                    .position(|c| char::from(*c) == ch)
+                    .map(|value| value as u32)
+                    .ok_or(FumenError::InvalidCharacter(ch))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self(values.into_iter()))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.len() == 0
+    }
+
+    /// Reads a number spread over `n` values, least significant first.
+    fn poll(&mut self, n: u32) -> Result<u32, FumenError> {
+        (0..n).try_fold(0, |value, i| {
+            let digit = self.0.next().ok_or(FumenError::UnexpectedEnd)?;
+            Ok(value + digit * 64u32.pow(i))
+        })
+    }
+}
+
+fn push_values(values: &mut Vec<u32>, mut value: u32, n: u32) {
+    for _ in 0..n {
+        values.push(value % 64);
+        value /= 64;
+    }
+}
+
+fn field_index(x: usize, y: usize) -> usize {
+    (FIELD_TOP - 1 - y) * Game::WIDTH + x
+}
+
+fn field_to_board(field: &Field) -> Board {
+    let mut board = vec![Line::default(); Game::HEIGHT];
+    for (y, line) in board.iter_mut().enumerate().take(FIELD_TOP) {
+        for (x, cell) in line.iter_mut().enumerate() {
+            *cell = block_tile(field[field_index(x, y)]);
+        }
+    }
+    board
+}
+
+fn board_to_field(board: &Board) -> Field {
+    let mut field = [0; FIELD_BLOCKS];
+    for (y, line) in board.iter().enumerate().take(FIELD_TOP) {
+        for (x, cell) in line.iter().enumerate() {
+            field[field_index(x, y)] = cell.map_or(0, tile_block);
+        }
+    }
+    field
+}
+
+/// Removes the full lines of a field (not including the garbage line).
+fn clear_lines(field: &mut Field) {
+    let (rows, _garbage) = field.split_at_mut(FIELD_BLOCKS - Game::WIDTH);
+    let mut kept = rows
+        .chunks(Game::WIDTH)
+        .filter(|row| row.contains(&0))
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+    kept.splice(0..0, vec![0; rows.len() - kept.len()]);
+    rows.copy_from_slice(&kept);
+}
+
+fn block_piece(block: u8) -> Option<Tetromino> {
+    use Tetromino::*;
+    Some(match block {
+        1 => I,
+        2 => L,
+        3 => O,
+        4 => Z,
+        5 => T,
+        6 => J,
+        7 => S,
+        _ => return None,
+    })
+}
+
+fn piece_block(piece: Tetromino) -> u8 {
+    (1..=7)
+        .find(|block| block_piece(*block) == Some(piece))
+        .unwrap()
+}
+
+fn block_tile(block: u8) -> Option<TileTypeID> {
+    match block {
+        0 => None,
+        block => Some(block_piece(block).map_or(Game::GARBAGE_TILE, |piece| piece.tiletypeid())),
+    }
+}
+
+fn tile_block(tile_type_id: TileTypeID) -> u8 {
+    Tetromino::SHAPES
+        .into_iter()
+        .find(|piece| piece.tiletypeid() == tile_type_id)
+        .map_or(8, piece_block)
+}
+
+fn piece_char(piece: Tetromino) -> char {
+    match piece {
+        Tetromino::O => 'O',
+        Tetromino::I => 'I',
+        Tetromino::S => 'S',
+        Tetromino::Z => 'Z',
+        Tetromino::T => 'T',
+        Tetromino::L => 'L',
+        Tetromino::J => 'J',
+    }
+}
+
+fn char_piece(ch: char) -> Option<Tetromino> {
+    Tetromino::SHAPES
+        .into_iter()
+        .find(|piece| piece_char(*piece) == ch.to_ascii_uppercase())
+}
+
+/// Fumen's rotation values, in the order 'reverse', 'right', 'spawn', 'left'.
+fn rotation_orientation(rotation: u32) -> Orientation {
+    [
+        Orientation::S,
+        Orientation::E,
+        Orientation::N,
+        Orientation::W,
+    ][rotation as usize % 4]
+}
+
+/// The mino offsets around the center of a piece, as used by fumen (in spawn rotation).
+fn fumen_minos(shape: Tetromino) -> [(isize, isize); 4] {
+    match shape {
+        Tetromino::I => [(0, 0), (-1, 0), (1, 0), (2, 0)],
+        Tetromino::T => [(0, 0), (-1, 0), (1, 0), (0, 1)],
+        Tetromino::O => [(0, 0), (1, 0), (0, 1), (1, 1)],
+        Tetromino::L => [(0, 0), (-1, 0), (1, 0), (1, 1)],
+        Tetromino::J => [(0, 0), (-1, 0), (1, 0), (-1, 1)],
+        Tetromino::S => [(0, 0), (-1, 0), (0, 1), (1, 1)],
+        Tetromino::Z => [(0, 0), (1, 0), (0, 1), (-1, 1)],
+    }
+}
+
+/// Where fumen places pieces of a rotation relative to the center used by [`fumen_minos`].
+fn center_offset(shape: Tetromino, orientation: Orientation) -> (isize, isize) {
+    use Orientation::*;
+    match (shape, orientation) {
+        (Tetromino::O, W) => (1, -1),
+        (Tetromino::O, S) | (Tetromino::I, S) | (Tetromino::Z, W) => (1, 0),
+        (Tetromino::O, N) | (Tetromino::I, W) | (Tetromino::S, N) | (Tetromino::Z, N) => (0, -1),
+        (Tetromino::S, E) => (-1, 0),
+        _ => (0, 0),
+    }
+}
+
+/// The lowest, leftmost offset of a piece's minos relative to its fumen center.
+fn min_offset(shape: Tetromino, orientation: Orientation) -> (isize, isize) {
+    let minos = fumen_minos(shape).map(|(x, y)| match orientation {
+        Orientation::N => (x, y),
+        Orientation::E => (y, -x),
+        Orientation::S => (-x, -y),
+        Orientation::W => (-y, x),
+    });
+    let min_x = minos.iter().map(|(x, _)| *x).min().unwrap();
+    let min_y = minos.iter().map(|(_, y)| *y).min().unwrap();
+    (min_x, min_y)
+}
+
+fn fumen_piece(shape: Tetromino, rotation: u32, (x, y): (usize, usize)) -> Option<ActivePiece> {
+    let orientation = rotation_orientation(rotation);
+    let (dx, dy) = center_offset(shape, orientation);
+    let (min_x, min_y) = min_offset(shape, orientation);
+    let position = (
+        x.checked_add_signed(min_x - dx)?,
+        y.checked_add_signed(min_y - dy)?,
+    );
+    let piece = ActivePiece {
+        shape,
+        orientation,
+        position,
+    };
+    piece
+        .tiles()
+        .iter()
+        .all(|((x, y), _)| *x < Game::WIDTH && *y < FIELD_TOP)
+        .then_some(piece)
+}
+
+/// The block, rotation and coordinate of a piece, if it can be shown by fumen.
+fn piece_action(piece: ActivePiece) -> Option<(u8, u32, usize)> {
+    let ActivePiece {
+        shape,
+        orientation,
+        position: (x, y),
+    } = piece;
+    let rotation = (0..4).find(|r| rotation_orientation(*r) == orientation)?;
+    let (dx, dy) = center_offset(shape, orientation);
+    let (min_x, min_y) = min_offset(shape, orientation);
+    let x = x.checked_add_signed(dx - min_x)?;
+    let y = y.checked_add_signed(dy - min_y)?;
+    (x < Game::WIDTH && y < FIELD_TOP && fumen_piece(shape, rotation, (x, y)) == Some(piece))
+        .then_some((piece_block(shape), rotation, field_index(x, y)))
+}
+
+/// Escapes text like JavaScript's `escape`, as fumen does for comments.
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for ch in text.chars() {
+        if ch.is_ascii_alphanumeric() || "@*_+-./".contains(ch) {
+            escaped.push(ch);
+        } else {
+            let mut utf16 = [0; 2];
+            for unit in ch.encode_utf16(&mut utf16) {
+                if *unit < 256 {
+                    escaped.push_str(&format!("%{unit:02X}"));
+                } else {
+                    escaped.push_str(&format!("%u{unit:04X}"));
+                }
+            }
+        }
+    }
+    escaped
+}
+
+/// Reverses [`escape`]; malformed escapes are kept as they are.
+fn unescape(text: &str) -> String {
+    let mut units = Vec::new();
+    let mut rest = text;
+    while let Some(ch) = rest.chars().next() {
+        let (hex, len) = if let Some(hex) = rest.strip_prefix("%u") {
+            (hex.get(..4), 6)
+        } else if let Some(hex) = rest.strip_prefix('%') {
+            (hex.get(..2), 3)
+        } else {
+            (None, 0)
+        };
+        let hex = hex.filter(|hex| hex.chars().all(|ch| ch.is_ascii_hexdigit()));
+        match hex.and_then(|hex| u16::from_str_radix(hex, 16).ok()) {
+            Some(unit) => {
+                units.push(unit);
+                rest = &rest[len..];
+            }
+            None => {
+                units.extend(ch.encode_utf16(&mut [0; 2]).iter());
+                rest = &rest[ch.len_utf8()..];
+            }
+        }
+    }
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_board() -> Board {
+        vec![Line::default(); Game::HEIGHT]
+    }
+
+    /// A board using every kind of tile, with full and nearly full lines.
+    fn stacked_board() -> Board {
+        let mut board = empty_board();
+        let tiles = Tetromino::SHAPES
+            .map(|piece| Some(piece.tiletypeid()))
+            .into_iter()
+            .chain([Some(Game::GARBAGE_TILE), None])
+            .collect::<Vec<_>>();
+        for (y, line) in board.iter_mut().enumerate().take(12) {
+            for (x, cell) in line.iter_mut().enumerate() {
+                *cell = tiles[(3 * y + x) % tiles.len()];
+            }
+        }
+        board[0] = [Some(Game::GARBAGE_TILE); Game::WIDTH];
+        board[FIELD_TOP - 1][0] = Some(Tetromino::I.tiletypeid());
+        board
+    }
+
+    fn page(board: Board, piece: Option<ActivePiece>, lock: bool, comment: &str) -> FumenPage {
+        FumenPage {
+            board,
+            piece,
+            lock,
+            comment: comment.to_string(),
+        }
+    }
+
+    fn round_trip(pages: &[FumenPage]) {
+        let fumen = encode(pages);
+        assert_eq!(decode(&fumen).as_deref(), Ok(pages), "{fumen}");
+    }
+
+    #[test]
+    fn decodes_hand_encoded_pages() {
+        // An empty board with a comment.
+        let pages = decode("v115@vhAAgWCAouBAA").unwrap();
+        assert_eq!(pages, [page(empty_board(), None, true, "hi")]);
+        // An I piece lying flat on the bottom row, centered on the fifth column.
+        let pages = decode("v115@vhARQJ").unwrap();
+        let piece = ActivePiece {
+            shape: Tetromino::I,
+            orientation: Orientation::N,
+            position: (3, 0),
+        };
+        assert_eq!(pages, [page(empty_board(), Some(piece), true, "")]);
+    }
+
+    #[test]
+    fn decodes_links_and_broken_up_data() {
+        let pages = decode("https://fumen.zui.jp/?v115@vhAAgH").unwrap();
+        assert_eq!(pages, [page(empty_board(), None, true, "")]);
+        assert_eq!(decode("v115@vhA?AgH"), decode("v115@vhAAgH"));
+    }
+
+    #[test]
+    fn rejects_invalid_data() {
+        assert_eq!(decode("v110@vhAAgH"), Err(FumenError::UnsupportedVersion));
+        assert_eq!(
+            decode("v115@vh!AgH"),
+            Err(FumenError::InvalidCharacter('!'))
+        );
+        assert_eq!(decode("v115@vhAAg"), Err(FumenError::UnexpectedEnd));
+        assert_eq!(decode("v115@"), Err(FumenError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn round_trips_every_piece_and_rotation() {
+        for shape in Tetromino::SHAPES {
+            for orientation in [
+                Orientation::N,
+                Orientation::E,
+                Orientation::S,
+                Orientation::W,
+            ] {
+                let piece = ActivePiece {
+                    shape,
+                    orientation,
+                    position: (4, 13),
+                };
+                round_trip(&[page(stacked_board(), Some(piece), false, "")]);
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips_pages_and_comments() {
+        let board = stacked_board();
+        let mut cleared_board = board.clone();
+        cleared_board.remove(0);
+        cleared_board.push(Line::default());
+        let piece = ActivePiece {
+            shape: Tetromino::T,
+            orientation: Orientation::S,
+            position: (2, 12),
+        };
+        let pages = [
+            page(
+                board.clone(),
+                Some(piece),
+                false,
+                "Ünïcode, spaces & 100% ✓",
+            ),
+            // Unchanged boards and comments are carried over from the previous page.
+            page(board.clone(), None, true, "Ünïcode, spaces & 100% ✓"),
+            page(board.clone(), None, true, "Ünïcode, spaces & 100% ✓"),
+            page(board, Some(piece), true, ""),
+            page(
+                cleared_board,
+                None,
+                true,
+                &quiz_comment(None, &[Tetromino::L]),
+            ),
+            page(stacked_board(), None, true, "a\nnew line"),
+            page(empty_board(), None, true, "a\nnew line"),
+        ];
+        round_trip(&pages);
+    }
+
+    #[test]
+    fn locks_pieces_for_the_next_page() {
+        let mut board = empty_board();
+        board[0] = [Some(Game::GARBAGE_TILE); Game::WIDTH];
+        board[0][..4].fill(None);
+        board[1][5] = Some(Game::GARBAGE_TILE);
+        let piece = ActivePiece {
+            shape: Tetromino::I,
+            orientation: Orientation::N,
+            position: (0, 0),
+        };
+        let fumen = encode(&[page(board, Some(piece), true, "")]);
+        // A page leaving the field as it is after the piece locked and cleared the bottom line.
+        let fumen = format!("{fumen}vhAAAA");
+        let pages = decode(&fumen).unwrap();
+        let mut locked_board = empty_board();
+        locked_board[0][5] = Some(Game::GARBAGE_TILE);
+        assert_eq!(pages[1], page(locked_board, None, true, ""));
+    }
+
+    #[test]
+    fn round_trips_quiz_comments() {
+        use Tetromino::*;
+        let comment = quiz_comment(Some(T), &[I, O, S, Z, L, J]);
+        assert_eq!(comment, "#Q=[T](I)OSZLJ");
+        assert_eq!(
+            parse_quiz(&comment),
+            Some((Some(T), vec![I, O, S, Z, L, J]))
+        );
+        assert_eq!(parse_quiz("#Q=[](o)ts;#Q=..."), Some((None, vec![O, T, S])));
+        assert_eq!(parse_quiz("just a comment"), None);
+    }
+}
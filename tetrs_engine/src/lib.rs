@@ -37,10 +37,13 @@ TASK: Document all features (including IRS, etc. - cargo feature `serde`).
 
 #![warn(missing_docs)]
 
+pub mod fumen;
 pub mod piece_generation;
 pub mod piece_rotation;
+pub mod rollback;
 
 use std::{
+    borrow::Cow,
     collections::{HashMap, VecDeque},
     fmt,
     num::{NonZeroU32, NonZeroU8},
@@ -50,10 +53,10 @@ use std::{
 
 pub use piece_generation::TetrominoSource;
 pub use piece_rotation::RotationSystem;
-use rand::rngs::ThreadRng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-/// A mapping for which buttons are pressed, usable through `impl Index<Button> for [T; 8]`.
-pub type ButtonsPressed = [bool; 9];
+/// A mapping for which buttons are pressed, usable through `impl Index<Button> for [T; 10]`.
+pub type ButtonsPressed = [bool; 10];
 /// Abstract identifier for which type of tile occupies a cell in the grid.
 pub type TileTypeID = NonZeroU8;
 /// The type of horizontal lines of the playing grid.
@@ -104,6 +107,8 @@ pub enum Button {
     DropSonic,
     /// Holding and swapping in a held piece.
     Hold,
+    /// Activating the zone, if the game mode has one (see [`GameMode::zone`]).
+    ActivateZone,
 }
 
 /// Represents the orientation an active piece can be in.
@@ -172,12 +177,29 @@ pub struct LockingData {
     pub ground_time_left: Duration,
     /// The lowest recorded vertical position of the main piece.
     pub lowest_y: usize,
+    /// How many times the lock timer was reset by moving or rotating since the main piece last
+    /// reached its lowest position.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lock_resets: u32,
+}
+
+/// How several limits of the same kind (positive or negative) combine to end a game.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LimitCombination {
+    /// Reaching any one limit ends the game (e.g. "40 lines OR 2 minutes").
+    #[default]
+    Any,
+    /// All positive (resp. negative) limits must have been reached for the game to end
+    /// (e.g. "level 10 AND 50,000 points").
+    All,
 }
 
 /// Stores the ways in which a round of the game should be limited.
 ///
 /// Each limitation may be either of positive ('game completed') or negative ('game over'), as
 /// designated by the `bool` stored with it.
+/// A limitation counts as reached once the corresponding value is at least the one given.
 ///
 /// No limitations may allow for endless games.
 #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, Default, Debug)]
@@ -189,10 +211,28 @@ pub struct Limits {
     pub pieces: Option<(bool, u32)>,
     /// The total number of full lines that may be cleared.
     pub lines: Option<(bool, usize)>,
-    /// The number of levels to reach.
+    /// The level to complete, i.e. the limit is reached once the level after it is reached.
     pub level: Option<(bool, NonZeroU32)>,
     /// The number of game points to earn.
     pub score: Option<(bool, u64)>,
+    /// The number of holes (empty tiles covered by a filled one) in the board.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub holes: Option<(bool, usize)>,
+    /// The height of the stack on the board.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stack_height: Option<(bool, usize)>,
+    /// The number of pieces locked in a row without clearing a line.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pieces_without_clear: Option<(bool, u32)>,
+    /// The time a single piece may be in play.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub piece_time: Option<(bool, Duration)>,
+    /// The number of garbage lines (see [`Game::GARBAGE_TILE`]) to be cleared.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub garbage_lines: Option<(bool, usize)>,
+    /// How the limitations above combine.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub combination: LimitCombination,
 }
 
 /// The playing configuration specific to the single, current round of play.
@@ -208,10 +248,120 @@ pub struct GameMode {
     pub start_level: NonZeroU32,
     /// Whether the level should be automatically incremented while the game plays.
     pub increment_level: bool,
+    /// How the level is incremented, if it is.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub level_progression: LevelProgression,
+    /// Whether pieces are twice as large, effectively halving the width and height of the board
+    /// (TGM-style 'Big' mode).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub big: bool,
+    /// Whether blocks fall after a line clear in connected groups until they land, possibly
+    /// causing chain clears (cascade gravity).
+    ///
+    /// Blocks are only connected to adjacent blocks of the same locked piece.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cascade: bool,
+    /// Whether line clears fill a zone meter, which can be activated to suspend gravity for a while
+    /// (Tetris Effect-style 'Zone').
+    ///
+    /// Lines cleared during the zone are pushed to the bottom of the board instead of being removed,
+    /// and are cleared all at once when it ends.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub zone: bool,
     /// The limitations under which a game may end (un)successfully.
     pub limits: Limits,
 }
 
+/// The ways in which the level may advance while a game plays.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LevelProgression {
+    /// A level is advanced every fixed number of lines cleared.
+    Lines(NonZeroU32),
+    /// A level is advanced once the lines awarded during it reach five times the level
+    /// (Guideline 'variable goal').
+    ///
+    /// Single to quadruple clears award 1, 3, 5 and 8 lines, T-spins without or with line clears
+    /// award 4, 8, 12 and 16 lines (mini T-spins 1, 2 and 4), and back-to-back clears award half
+    /// again as many.
+    VariableGoal,
+    /// A level is advanced every fixed number of section points, earned one per piece spawned and
+    /// one per line cleared, where spawning pieces alone never completes a section (TGM-style).
+    Sections(NonZeroU32),
+    /// A level is advanced every fixed amount of time played.
+    Time(Duration),
+}
+
+/// The curve by which pieces fall faster as the level increases.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gravity {
+    /// Drop delay as per guideline, from 1s at level 1 down to 20G at level 20.
+    #[default]
+    Standard,
+    /// The frame-based drop delays of NES Tetris, reaching one row per frame at level 29 (the
+    /// 'kill screen').
+    Classic,
+}
+
+/// The way line clears are turned into score.
+///
+/// Chain clears of [`GameMode::cascade`] are scored as further combos of the piece that started
+/// them, and the lines collected during a zone (see [`GameMode::zone`]) as a single clear without a
+/// combo; neither ever counts as a spin. Clears of more than four lines score like four lines where
+/// no higher award exists.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Scoring {
+    /// Score grows quadratically with lines cleared and combo, multiplied by back-to-backs, spins
+    /// and perfect clears.
+    #[default]
+    Standard,
+    /// NES scoring: 40, 100, 300 and 1200 points for one to four lines, times the level plus one.
+    Classic,
+    /// Guideline scoring: points for line clears and T-spins (minis included) times the level,
+    /// half again as many for back-to-backs, plus combo and perfect clear bonuses.
+    Guideline,
+}
+
+/// A line clear to be scored by [`Scoring::score_bonus`].
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+struct ScoredClear {
+    lines: u32,
+    spin: bool,
+    mini: bool,
+    perfect_clear: bool,
+    /// The number of consecutive line clears, including this one.
+    combo: u32,
+    /// The number of consecutive special clears, including this one if it is special.
+    back_to_back: u32,
+}
+
+/// The way it is decided whether a piece was spun into place upon locking.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpinDetection {
+    /// Any piece that cannot move up anymore when it locks counts as spun.
+    #[default]
+    Immobile,
+    /// Only T pieces that were rotated last and have three of their four diagonal corners occupied
+    /// count as spun; unless both corners on the pointing side are occupied (or the rotation
+    /// kicked the piece one column over and two rows up or down), this is a mini spin.
+    TCorners,
+}
+
+/// Presets of [`GameConfig`] modelled after well-known rulesets.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Ruleset {
+    /// The rules of NES Tetris: no hold, hard drop or ghost piece, NES randomizer, gravity and
+    /// scoring, and frame-based timings.
+    Classic,
+    /// The rules of the modern guideline: Super Rotation System, 7-bag, guideline scoring,
+    /// move reset lock and T-spins by the 3-corner rule.
+    Guideline,
+}
+
 /// User-focused configuration options that mainly influence time-sensitive or cosmetic mechanics.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -239,8 +389,43 @@ pub struct GameConfig {
     pub line_clear_delay: Duration,
     /// How long the game should wait *additionally* before spawning a new piece.
     pub appearance_delay: Duration,
+    /// How much longer the appearance delay is for every four rows higher up a piece was locked
+    /// (starting with the third row, and up to four times).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub appearance_delay_per_height: Duration,
     /// Whether to disable a 'soft drop' button press to explicitly and immediately lock down a piece.
     pub no_soft_drop_lock: bool,
+    /// How fast pieces fall depending on the level.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gravity: Gravity,
+    /// How line clears are scored.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub scoring: Scoring,
+    /// Whether to disable holding pieces.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub no_hold: bool,
+    /// Whether to disable the 'hard drop' and 'sonic drop' buttons.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub no_hard_drop: bool,
+    /// Whether the landing position of the active piece should not be shown.
+    ///
+    /// This is not used by the engine itself, but is a hint to frontends.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub no_ghost_piece: bool,
+    /// How spins are detected when a piece locks.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub spin_detection: SpinDetection,
+    /// How many times moving or rotating a piece on the ground may reset its lock timer before
+    /// it reaches a new lowest position ('move reset'), if limited.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lock_resets_max: Option<u32>,
+    /// How many frames per second the game advances by, if it runs in discrete frames.
+    ///
+    /// In this case button changes only take effect at the start of the frame they happen in, and
+    /// all delays are rounded to a whole number of frames, making games depend only on the frames
+    /// in which inputs happened.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tick_rate: Option<NonZeroU32>,
 }
 
 /// An event that is scheduled by the game engine to execute some action.
@@ -273,6 +458,10 @@ pub enum InternalEvent {
     Rotate(i32),
     /// Event of attempted piece lock down.
     LockTimer,
+    /// Event of the zone being activated.
+    ZoneStart,
+    /// Event of the zone running out, clearing all lines collected during it.
+    ZoneEnd,
 }
 
 /// Represents how a game can end.
@@ -305,9 +494,18 @@ pub struct GameState {
     /// Upcoming game events.
     pub events: EventMap,
     /// The current state of buttons being pressed in the game.
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "deserialize_buttons_pressed")
+    )]
     pub buttons_pressed: ButtonsPressed,
     /// The main playing grid storing empty (`None`) and filled, fixed tiles (`Some(nz_u32)`).
     pub board: Board,
+    /// The times at which the tiles of the board were locked, stored like the board itself.
+    ///
+    /// Tiles not placed by locking a piece (e.g. garbage added by game mods) have no lock time.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub board_lock_times: Vec<[Option<GameTime>; Game::WIDTH]>,
     /// All relevant data of the current piece in play.
     pub active_piece_data: Option<(ActivePiece, LockingData)>,
     /// Data about the piece being held. `true` denotes that the held piece can be swapped back in.
@@ -328,6 +526,84 @@ pub struct GameState {
     pub consecutive_line_clears: u32,
     /// The number of line clears that were either a quadruple, spin or perfect clear.
     pub back_to_back_special_clears: u32,
+    /// Values which game mods keep about the round of play by name, such as a grade awarded.
+    ///
+    /// The engine itself does not read or change these.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mod_stats: HashMap<String, i64>,
+    /// The number of pieces locked since the last line clear.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pieces_since_line_clear: u32,
+    /// The time at which the current (or last) piece spawned.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub piece_spawn_time: GameTime,
+    /// The number of cleared lines that contained garbage (see [`Game::GARBAGE_TILE`]).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub garbage_lines_cleared: usize,
+    /// Progress made towards the next level, as counted by the [`LevelProgression`] in use
+    /// (lines awarded, section points or time intervals passed).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub level_progress: u32,
+    /// Whether the active piece is kept from falling on its own and from locking by touching the
+    /// ground; it still locks when hard or soft dropped.
+    ///
+    /// Once this is unset, gravity resumes the next time the piece moves.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gravity_suspended: bool,
+    /// The number of lines cleared towards the zone meter, up to [`Game::ZONE_METER_MAX`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub zone_meter: u32,
+    /// The time at which the currently active zone ends.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub zone_end: Option<GameTime>,
+    /// The number of lines cleared during the currently active zone, kept at the bottom of the board.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub zone_lines: u32,
+    /// The active piece before and after it was last rotated, unless it was moved otherwise since.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub last_rotation: Option<(ActivePiece, ActivePiece)>,
+}
+
+impl GameState {
+    /// The number of empty tiles on the board which have a filled tile somewhere above them.
+    pub fn holes(&self) -> usize {
+        (0..Game::WIDTH)
+            .map(|x| {
+                self.board
+                    .iter()
+                    .rev()
+                    .skip_while(|line| line[x].is_none())
+                    .filter(|line| line[x].is_none())
+                    .count()
+            })
+            .sum()
+    }
+
+    /// The number of lines from the bottom of the board up to and including its highest filled tile.
+    pub fn stack_height(&self) -> usize {
+        self.board
+            .iter()
+            .rposition(|line| line.iter().any(|tile| tile.is_some()))
+            .map_or(0, |y| y + 1)
+    }
+
+    /// Inserts a line into the board at some row (0 being the bottom), moving the rows above up.
+    ///
+    /// Unlike editing `board` directly, this keeps `board_lock_times` aligned with it.
+    pub fn insert_row(&mut self, y: usize, line: Line) {
+        self.board_lock_times
+            .resize(self.board.len(), Default::default());
+        self.board.insert(y, line);
+        self.board_lock_times.insert(y, Default::default());
+    }
+
+    /// Removes the highest row of the board, keeping `board_lock_times` aligned with it.
+    pub fn pop_row(&mut self) -> Option<Line> {
+        self.board_lock_times
+            .resize(self.board.len(), Default::default());
+        self.board_lock_times.pop();
+        self.board.pop()
+    }
 }
 
 /// An error that can be thrown by [`Game::update`].
@@ -344,10 +620,38 @@ pub struct Game {
     config: GameConfig,
     mode: GameMode,
     state: GameState,
-    rng: ThreadRng,
+    seed: u64,
+    rng: StdRng,
     modifiers: Vec<FnGameMod>,
 }
 
+/// A copy of everything that determines how a [`Game`] continues, see [`Game::save_state`].
+#[derive(Debug)]
+pub struct GameSnapshot {
+    config: GameConfig,
+    mode: GameMode,
+    state: GameState,
+    rng: StdRng,
+}
+
+impl Clone for GameSnapshot {
+    fn clone(&self) -> Self {
+        Self {
+            config: config_with_state(&self.config),
+            mode: self.mode.clone(),
+            state: self.state.clone(),
+            rng: self.rng.clone(),
+        }
+    }
+}
+
+impl GameSnapshot {
+    /// Immutable accessor for the saved game state.
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+}
+
 /// A number of feedback events that can be returned by the game.
 ///
 /// These can be used to more easily render visual feedback to the player.
@@ -373,6 +677,8 @@ pub enum Feedback {
         shape: Tetromino,
         /// Whether the piece was spun into place.
         spin: bool,
+        /// Whether the spin only counted as a mini spin.
+        mini: bool,
         /// How many lines were cleared by the piece simultaneously
         lineclears: u32,
         /// Whether the entire board was cleared empty by this action.
@@ -382,12 +688,21 @@ pub enum Feedback {
         /// The number of consecutive lineclears where a spin, quadruple or perfect clear occurred.
         back_to_back: u32,
     },
+    /// The level was advanced to the one given.
+    LevelUp(NonZeroU32),
     /// Generic text feedback message.
     ///
     /// This is currently unused in base game modes.
     Message(String),
 }
 
+impl Default for LevelProgression {
+    fn default() -> Self {
+        // SAFETY: 10 > 0.
+        Self::Lines(unsafe { NonZeroU32::new_unchecked(10) })
+    }
+}
+
 /// The points at which a [`FnGameMod`] will be applied.
 #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, Debug)]
 pub enum ModifierPoint {
@@ -533,6 +848,22 @@ impl ActivePiece {
             .map(|(dx, dy)| ((x + dx, y + dy), tile_type_id))
     }
 
+    /// Returns the coordinates and tile types of the cells the piece covers on the actual board.
+    ///
+    /// If the piece is big, each of its tiles covers 2×2 cells (see [`GameMode::big`]).
+    pub fn board_tiles(&self, big: bool) -> Vec<(Coord, TileTypeID)> {
+        if !big {
+            return self.tiles().to_vec();
+        }
+        self.tiles()
+            .into_iter()
+            .flat_map(|((x, y), tile_type_id)| {
+                [(0, 0), (1, 0), (0, 1), (1, 1)]
+                    .map(|(dx, dy)| ((2 * x + dx, 2 * y + dy), tile_type_id))
+            })
+            .collect()
+    }
+
     /// Checks whether the piece fits at its current location onto the board.
     pub fn fits(&self, board: &Board) -> bool {
         self.tiles()
@@ -590,6 +921,51 @@ impl ActivePiece {
     }
 }
 
+impl Limits {
+    /// Checks each limitation which is set, in the order of declaration, returning
+    /// `(win, reached)` for every one of them.
+    pub fn check(&self, state: &GameState) -> Vec<(bool, bool)> {
+        let piece_time = state.time.saturating_sub(state.piece_spawn_time);
+        [
+            self.time.map(|(win, dur)| (win, dur <= state.time)),
+            self.pieces
+                .map(|(win, pcs)| (win, pcs <= state.pieces_played.iter().sum())),
+            self.lines
+                .map(|(win, lns)| (win, lns <= state.lines_cleared)),
+            self.level.map(|(win, lvl)| (win, lvl < state.level)),
+            self.score.map(|(win, pts)| (win, pts <= state.score)),
+            self.holes.map(|(win, holes)| (win, holes <= state.holes())),
+            self.stack_height
+                .map(|(win, height)| (win, height <= state.stack_height())),
+            self.pieces_without_clear
+                .map(|(win, pcs)| (win, pcs <= state.pieces_since_line_clear)),
+            self.piece_time
+                .map(|(win, dur)| (win, state.active_piece_data.is_some() && dur <= piece_time)),
+            self.garbage_lines
+                .map(|(win, lns)| (win, lns <= state.garbage_lines_cleared)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Whether the limitations have ended a game in the given state, and if so, successfully.
+    ///
+    /// With [`LimitCombination::All`], negative limitations are considered before positive ones.
+    pub fn outcome(&self, state: &GameState) -> Option<bool> {
+        let checks = self.check(state);
+        match self.combination {
+            LimitCombination::Any => checks
+                .into_iter()
+                .find_map(|(win, reached)| reached.then_some(win)),
+            LimitCombination::All => [false, true].into_iter().find(|outcome| {
+                let mut limits = checks.iter().filter(|(win, _)| win == outcome).peekable();
+                limits.peek().is_some() && limits.all(|(_, reached)| *reached)
+            }),
+        }
+    }
+}
+
 impl GameMode {
     /// Produce a game mode template for "Marathon" mode.
     ///
@@ -603,6 +979,10 @@ impl GameMode {
             name: String::from("Marathon"),
             start_level: NonZeroU32::MIN,
             increment_level: true,
+            level_progression: Default::default(),
+            big: false,
+            cascade: false,
+            zone: false,
             limits: Limits {
                 level: Some((true, NonZeroU32::try_from(15).unwrap())),
                 ..Default::default()
@@ -622,6 +1002,10 @@ impl GameMode {
             name: String::from("40-Lines"),
             start_level,
             increment_level: false,
+            level_progression: Default::default(),
+            big: false,
+            cascade: false,
+            zone: false,
             limits: Limits {
                 lines: Some((true, 40)),
                 ..Default::default()
@@ -641,6 +1025,10 @@ impl GameMode {
             name: String::from("Time Trial"),
             start_level,
             increment_level: false,
+            level_progression: Default::default(),
+            big: false,
+            cascade: false,
+            zone: false,
             limits: Limits {
                 time: Some((true, Duration::from_secs(3 * 60))),
                 ..Default::default()
@@ -660,6 +1048,10 @@ impl GameMode {
             name: String::from("Master"),
             start_level: Game::LEVEL_20G.saturating_add(1),
             increment_level: true,
+            level_progression: Default::default(),
+            big: false,
+            cascade: false,
+            zone: false,
             limits: Limits {
                 lines: Some((true, 100)),
                 ..Default::default()
@@ -679,12 +1071,16 @@ impl GameMode {
             name: String::from("Endless"),
             start_level: NonZeroU32::MIN,
             increment_level: false,
+            level_progression: Default::default(),
+            big: false,
+            cascade: false,
+            zone: false,
             limits: Default::default(),
         }
     }
 }
 
-impl<T> ops::Index<Button> for [T; 9] {
+impl<T> ops::Index<Button> for [T; 10] {
     type Output = T;
 
     fn index(&self, idx: Button) -> &Self::Output {
@@ -698,11 +1094,12 @@ impl<T> ops::Index<Button> for [T; 9] {
             Button::DropHard => &self[6],
             Button::DropSonic => &self[7],
             Button::Hold => &self[8],
+            Button::ActivateZone => &self[9],
         }
     }
 }
 
-impl<T> ops::IndexMut<Button> for [T; 9] {
+impl<T> ops::IndexMut<Button> for [T; 10] {
     fn index_mut(&mut self, idx: Button) -> &mut Self::Output {
         match idx {
             Button::MoveLeft => &mut self[0],
@@ -714,6 +1111,7 @@ impl<T> ops::IndexMut<Button> for [T; 9] {
             Button::DropHard => &mut self[6],
             Button::DropSonic => &mut self[7],
             Button::Hold => &mut self[8],
+            Button::ActivateZone => &mut self[9],
         }
     }
 }
@@ -731,7 +1129,150 @@ impl Default for GameConfig {
             ground_time_max: Duration::from_millis(3000),
             line_clear_delay: Duration::from_millis(200),
             appearance_delay: Duration::from_millis(50),
+            appearance_delay_per_height: Duration::ZERO,
             no_soft_drop_lock: false,
+            gravity: Gravity::Standard,
+            scoring: Scoring::Standard,
+            no_hold: false,
+            no_hard_drop: false,
+            no_ghost_piece: false,
+            spin_detection: SpinDetection::Immobile,
+            lock_resets_max: None,
+            tick_rate: None,
+        }
+    }
+}
+
+impl Ruleset {
+    /// All available rulesets.
+    pub const ALL: [Self; 2] = [Self::Classic, Self::Guideline];
+
+    /// The game configuration prescribed by the ruleset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tetrs_engine::*;
+    /// let config = Ruleset::Classic.game_config();
+    ///
+    /// assert_eq!(config.rotation_system, RotationSystem::Classic);
+    /// assert!(config.no_hold && config.no_hard_drop && config.no_ghost_piece);
+    /// ```
+    pub fn game_config(self) -> GameConfig {
+        match self {
+            Ruleset::Classic => GameConfig {
+                rotation_system: RotationSystem::Classic,
+                tetromino_generator: TetrominoSource::classic(),
+                preview_count: 1,
+                delayed_auto_shift: 16 * Game::NES_FRAME,
+                auto_repeat_rate: 6 * Game::NES_FRAME,
+                line_clear_delay: 18 * Game::NES_FRAME,
+                appearance_delay: 10 * Game::NES_FRAME,
+                appearance_delay_per_height: 2 * Game::NES_FRAME,
+                gravity: Gravity::Classic,
+                scoring: Scoring::Classic,
+                no_hold: true,
+                no_hard_drop: true,
+                no_ghost_piece: true,
+                // SAFETY: 60 > 0.
+                tick_rate: Some(unsafe { NonZeroU32::new_unchecked(60) }),
+                ..GameConfig::default()
+            },
+            Ruleset::Guideline => GameConfig {
+                rotation_system: RotationSystem::Super,
+                tetromino_generator: TetrominoSource::bag(),
+                preview_count: 5,
+                scoring: Scoring::Guideline,
+                spin_detection: SpinDetection::TCorners,
+                lock_resets_max: Some(15),
+                ..GameConfig::default()
+            },
+        }
+    }
+}
+
+impl Scoring {
+    /// The points awarded for a line clear at some level.
+    fn score_bonus(self, clear: ScoredClear, level: NonZeroU32) -> u32 {
+        match self {
+            Scoring::Standard => {
+                10 * (clear.lines + clear.combo - 1).pow(2)
+                    * clear.back_to_back.max(1)
+                    * if clear.spin { 4 } else { 1 }
+                    * if clear.perfect_clear { 100 } else { 1 }
+            }
+            Scoring::Classic => {
+                [0, 40, 100, 300, 1200][clear.lines.min(4) as usize] * (level.get() + 1)
+            }
+            Scoring::Guideline => {
+                let clear_bonus = match (clear.spin, clear.mini) {
+                    (false, _) => [0, 100, 300, 500, 800][clear.lines.min(4) as usize],
+                    (true, true) => [100, 200, 400][clear.lines.min(2) as usize],
+                    (true, false) => [400, 800, 1200, 1600][clear.lines.min(3) as usize],
+                };
+                let clear_bonus = if clear.back_to_back > 1 {
+                    clear_bonus * 3 / 2
+                } else {
+                    clear_bonus
+                };
+                let perfect_clear_bonus = if clear.perfect_clear {
+                    [0, 800, 1200, 1800, 2000][clear.lines.min(4) as usize]
+                } else {
+                    0
+                };
+                let combo_bonus = 50 * (clear.combo - 1);
+                (clear_bonus + perfect_clear_bonus + combo_bonus) * level.get()
+            }
+        }
+    }
+}
+
+impl SpinDetection {
+    /// Decides whether a piece about to lock was spun into place, and whether only as a mini spin.
+    fn detect(
+        self,
+        piece: &ActivePiece,
+        board: &Board,
+        last_rotation: Option<(ActivePiece, ActivePiece)>,
+    ) -> (bool, bool) {
+        match self {
+            SpinDetection::Immobile => (piece.fits_at(board, (0, 1)).is_none(), false),
+            SpinDetection::TCorners => {
+                let Some((piece_before_rotation, _)) = last_rotation else {
+                    return (false, false);
+                };
+                if piece.shape != Tetromino::T {
+                    return (false, false);
+                }
+                let center = t_center(piece);
+                // Walls and floor count as occupied.
+                let occupied = |offset: Offset| {
+                    add(center, offset).map_or(true, |(x, y)| {
+                        x >= Game::WIDTH || board.get(y).is_some_and(|line| line[x].is_some())
+                    })
+                };
+                let corners = [(-1, -1), (1, -1), (-1, 1), (1, 1)];
+                if corners
+                    .into_iter()
+                    .filter(|&corner| occupied(corner))
+                    .count()
+                    < 3
+                {
+                    return (false, false);
+                }
+                // The T points away from the one side of its center without a mino.
+                let tiles = piece.tiles();
+                let (back_x, back_y) = [(0, 1), (1, 0), (0, -1), (-1, 0)]
+                    .into_iter()
+                    .find(|&side| tiles.iter().all(|&(pos, _)| add(center, side) != Some(pos)))
+                    .unwrap();
+                let (front_x, front_y) = (-back_x, -back_y);
+                let front_corners_occupied = occupied((front_x + front_y, front_y + front_x))
+                    && occupied((front_x - front_y, front_y - front_x));
+                let (x0, y0) = t_center(&piece_before_rotation);
+                let kicked_far = center.0.abs_diff(x0) == 1 && center.1.abs_diff(y0) == 2;
+                (true, !(front_corners_occupied || kicked_far))
+            }
         }
     }
 }
@@ -741,6 +1282,7 @@ impl fmt::Debug for Game {
         fmt.debug_struct("Game")
             .field("config", &self.config)
             .field("state", &self.state)
+            .field("seed", &self.seed)
             .field("rng", &std::any::type_name_of_val(&self.rng))
             .field("modifiers", &std::any::type_name_of_val(&self.modifiers))
             .finish()
@@ -754,8 +1296,18 @@ impl Game {
     pub const WIDTH: usize = 10;
     /// The maximal height of the (conventionally visible) playing grid that can be played in.
     pub const SKYLINE: usize = 20;
+    /// The type of tile used for garbage, i.e. lines not built from pieces by the player.
+    // SAFETY: 254 > 0.
+    pub const GARBAGE_TILE: TileTypeID = unsafe { TileTypeID::new_unchecked(254) };
     // SAFETY: 19 > 0, and this is the level at which blocks start falling with 20G.
     const LEVEL_20G: NonZeroU32 = unsafe { NonZeroU32::new_unchecked(19) };
+    /// The duration of a frame on the NTSC NES, which ran at about 60.0988 frames per second.
+    const NES_FRAME: Duration = Duration::from_nanos(16_639_267);
+    /// The number of lines it takes to fill the zone meter (see [`GameMode::zone`]).
+    pub const ZONE_METER_MAX: u32 = 32;
+    /// How long the zone lasts when activated with a full meter; with a partially filled meter it
+    /// lasts proportionally shorter, but the meter must be at least a quarter full.
+    pub const ZONE_DURATION_MAX: Duration = Duration::from_secs(20);
 
     /// Start a new game given some game mode.
     pub fn new(game_mode: GameMode) -> Self {
@@ -764,6 +1316,14 @@ impl Game {
 
     /// Start a new game given a gamemode and some advanced configuration options.
     pub fn with_config(game_mode: GameMode, config: GameConfig) -> Self {
+        Self::with_seed(game_mode, config, rand::thread_rng().gen())
+    }
+
+    /// Start a new game whose randomness is entirely determined by the given seed.
+    ///
+    /// Two games started with the same seed, mode and configuration behave identically when given
+    /// the same updates.
+    pub fn with_seed(game_mode: GameMode, config: GameConfig, seed: u64) -> Self {
         let state = GameState {
             time: Duration::ZERO,
             end: None,
@@ -772,6 +1332,7 @@ impl Game {
             board: std::iter::repeat(Line::default())
                 .take(Self::HEIGHT)
                 .collect(),
+            board_lock_times: vec![Default::default(); Self::HEIGHT],
             active_piece_data: None,
             hold_piece: None,
             next_pieces: VecDeque::new(),
@@ -781,12 +1342,23 @@ impl Game {
             score: 0,
             consecutive_line_clears: 0,
             back_to_back_special_clears: 0,
+            mod_stats: HashMap::new(),
+            pieces_since_line_clear: 0,
+            piece_spawn_time: Duration::ZERO,
+            garbage_lines_cleared: 0,
+            level_progress: 0,
+            gravity_suspended: false,
+            zone_meter: 0,
+            zone_end: None,
+            zone_lines: 0,
+            last_rotation: None,
         };
         Game {
             config,
             mode: game_mode,
             state,
-            rng: rand::thread_rng(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
             modifiers: Vec::new(),
         }
     }
@@ -814,6 +1386,50 @@ impl Game {
         &mut self.config
     }
 
+    /// The number of the frame a point in game time lies in, if the game runs in frames.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{num::NonZeroU32, time::Duration};
+    /// # use tetrs_engine::*;
+    /// let mut game = Game::new(GameMode::marathon());
+    /// assert_eq!(game.frame_at(Duration::from_millis(100)), None);
+    ///
+    /// game.config_mut().tick_rate = NonZeroU32::new(60);
+    /// assert_eq!(game.frame_at(Duration::from_millis(100)), Some(6));
+    /// assert_eq!(game.frame_start(6), Some(Duration::from_nanos(100_000_000)));
+    /// ```
+    pub fn frame_at(&self, time: GameTime) -> Option<u64> {
+        let tick_rate = u128::from(self.config.tick_rate?.get());
+        u64::try_from(time.as_nanos() * tick_rate / 1_000_000_000).ok()
+    }
+
+    /// The game time at which a frame starts, if the game runs in frames.
+    pub fn frame_start(&self, frame: u64) -> Option<GameTime> {
+        let tick_rate = u128::from(self.config.tick_rate?.get());
+        let nanos = (u128::from(frame) * 1_000_000_000).div_ceil(tick_rate);
+        u64::try_from(nanos).ok().map(Duration::from_nanos)
+    }
+
+    /// Moves all scheduled events to the nearest start of a frame, if the game runs in frames.
+    fn align_events_to_frames(&mut self) {
+        let Some(tick_rate) = self.config.tick_rate else {
+            return;
+        };
+        let tick_rate = u128::from(tick_rate.get());
+        for event_time in self.state.events.values_mut() {
+            let frame = (event_time.as_nanos() * tick_rate + 500_000_000) / 1_000_000_000;
+            let nanos = (frame * 1_000_000_000).div_ceil(tick_rate);
+            *event_time = Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX));
+        }
+    }
+
+    /// The seed the game's randomness was started from (see [`Game::with_seed`]).
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
     /// Immutable accessor for the current game mode.
     pub fn mode(&self) -> &GameMode {
         &self.mode
@@ -848,6 +1464,71 @@ impl Game {
         &mut self.state
     }
 
+    /// Saves a copy of the current game, which can be returned to with [`Game::load_state`].
+    ///
+    /// Re-simulating the same updates from a loaded state yields an identical [`GameState`].
+    /// Note that 'game mods' (see [`Game::add_modifier`]) are not saved, so any internal state they
+    /// keep themselves is not restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use tetrs_engine::*;
+    ///
+    /// let mut game = Game::with_seed(GameMode::marathon(), GameConfig::default(), 42);
+    /// let mut left = ButtonsPressed::default();
+    /// left[Button::MoveLeft] = true;
+    /// let updates = [
+    ///     (Some(left), Duration::from_millis(500)),
+    ///     (Some(ButtonsPressed::default()), Duration::from_millis(600)),
+    ///     (None, Duration::from_secs(30)),
+    /// ];
+    ///
+    /// game.update(None, Duration::from_millis(200)).ok();
+    /// let saved = game.save_state();
+    /// for (buttons, time) in updates {
+    ///     game.update(buttons, time).ok();
+    /// }
+    /// let first_run = game.state().clone();
+    ///
+    /// game.load_state(&saved);
+    /// for (buttons, time) in updates {
+    ///     game.update(buttons, time).ok();
+    /// }
+    /// assert_eq!(game.state(), &first_run);
+    ///
+    /// // Same seed, same game.
+    /// let mut other_game = Game::with_seed(GameMode::marathon(), GameConfig::default(), 42);
+    /// other_game.update(None, Duration::from_millis(200)).ok();
+    /// for (buttons, time) in updates {
+    ///     other_game.update(buttons, time).ok();
+    /// }
+    /// assert_eq!(other_game.state(), &first_run);
+    /// ```
+    pub fn save_state(&self) -> GameSnapshot {
+        GameSnapshot {
+            config: config_with_state(&self.config),
+            mode: self.mode.clone(),
+            state: self.state.clone(),
+            rng: self.rng.clone(),
+        }
+    }
+
+    /// Returns the game to a state saved with [`Game::save_state`], keeping the current 'game mods'.
+    pub fn load_state(&mut self, snapshot: &GameSnapshot) {
+        let GameSnapshot {
+            config,
+            mode,
+            state,
+            rng,
+        } = snapshot.clone();
+        self.config = config;
+        self.mode = mode;
+        self.state = state;
+        self.rng = rng;
+    }
+
     /// Adds a 'game mod' that will get executed regularly before and after each [`InternalEvent`].
     ///
     /// # Safety
@@ -861,39 +1542,167 @@ impl Game {
         self.modifiers.push(game_mod)
     }
 
+    /// Advances the level by one, reporting it.
+    fn level_up(&mut self, feedback_events: &mut FeedbackEvents, event_time: GameTime) {
+        self.state.level = self.state.level.saturating_add(1);
+        feedback_events.push((event_time, Feedback::LevelUp(self.state.level)));
+    }
+
+    /// Counts a line removed from the board as cleared, advancing the level if it is due.
+    fn count_line_clear(
+        &mut self,
+        line: &Line,
+        feedback_events: &mut FeedbackEvents,
+        event_time: GameTime,
+    ) {
+        self.state.lines_cleared += 1;
+        if line.contains(&Some(Self::GARBAGE_TILE)) {
+            self.state.garbage_lines_cleared += 1;
+        }
+        if self.mode.increment_level {
+            match self.mode.level_progression {
+                // Increment level if enough lines cleared.
+                LevelProgression::Lines(lines) => {
+                    if self.state.lines_cleared % lines.get() as usize == 0 {
+                        self.level_up(feedback_events, event_time);
+                    }
+                }
+                LevelProgression::Sections(section) => {
+                    self.state.level_progress += 1;
+                    if self.state.level_progress >= section.get() {
+                        self.state.level_progress -= section.get();
+                        self.level_up(feedback_events, event_time);
+                    }
+                }
+                LevelProgression::VariableGoal | LevelProgression::Time(_) => {}
+            }
+        }
+    }
+
+    /// The full lines of the board from row `bottom` upwards, top to bottom.
+    ///
+    /// Big pieces fill rows in pairs, so in Big mode only pairs of full rows count, which keeps the
+    /// stack aligned to the doubled grid.
+    fn full_lines(&self, bottom: usize) -> Vec<usize> {
+        let is_full = |y: usize| {
+            self.state
+                .board
+                .get(y)
+                .is_some_and(|line| line.iter().all(|mino| mino.is_some()))
+        };
+        (bottom..Self::HEIGHT)
+            .rev()
+            .filter(|&y| !self.mode.big || is_full(y ^ 1))
+            .filter(|&y| is_full(y))
+            .collect()
+    }
+
+    /// Whether clearing the given lines would leave the board empty.
+    fn is_perfect_clear(&self, lines_cleared: &[usize]) -> bool {
+        self.state
+            .board
+            .iter()
+            .enumerate()
+            .all(|(y, line)| lines_cleared.contains(&y) || line.iter().all(|tile| tile.is_none()))
+    }
+
+    /// Lets connected groups of blocks fall until all of them have landed, returning whether any
+    /// block moved.
+    ///
+    /// Blocks form a group if they are adjacent and were locked as part of the same piece; blocks
+    /// without a lock time (e.g. garbage) form groups among themselves.
+    fn cascade(&mut self) -> bool {
+        self.state
+            .board_lock_times
+            .resize(self.state.board.len(), Default::default());
+        let mut moved = false;
+        loop {
+            // Label connected groups of blocks.
+            let mut groups = vec![[None::<usize>; Self::WIDTH]; self.state.board.len()];
+            let mut n_groups = 0;
+            for y in 0..self.state.board.len() {
+                for x in 0..Self::WIDTH {
+                    if self.state.board[y][x].is_none() || groups[y][x].is_some() {
+                        continue;
+                    }
+                    let lock_time = self.state.board_lock_times[y][x];
+                    groups[y][x] = Some(n_groups);
+                    let mut stack = vec![(x, y)];
+                    while let Some((x, y)) = stack.pop() {
+                        let neighbors = [
+                            (x.wrapping_sub(1), y),
+                            (x + 1, y),
+                            (x, y.wrapping_sub(1)),
+                            (x, y + 1),
+                        ];
+                        for (nx, ny) in neighbors {
+                            if nx < Self::WIDTH
+                                && ny < self.state.board.len()
+                                && self.state.board[ny][nx].is_some()
+                                && groups[ny][nx].is_none()
+                                && self.state.board_lock_times[ny][nx] == lock_time
+                            {
+                                groups[ny][nx] = Some(n_groups);
+                                stack.push((nx, ny));
+                            }
+                        }
+                    }
+                    n_groups += 1;
+                }
+            }
+            // A group falls if every one of its blocks is above an empty cell or a block of itself.
+            let mut falls = vec![true; n_groups];
+            for (y, row) in groups.iter().enumerate() {
+                for (x, group) in row.iter().enumerate() {
+                    let Some(group) = *group else {
+                        continue;
+                    };
+                    if y == 0 || groups[y - 1][x].is_some_and(|below| below != group) {
+                        falls[group] = false;
+                    }
+                }
+            }
+            if !falls.contains(&true) {
+                return moved;
+            }
+            moved = true;
+            // Move all falling groups down by one, bottom rows first.
+            for (y, row) in groups.iter().enumerate().skip(1) {
+                for (x, group) in row.iter().enumerate() {
+                    if group.is_some_and(|group| falls[group]) {
+                        self.state.board[y - 1][x] = self.state.board[y][x].take();
+                        self.state.board_lock_times[y - 1][x] =
+                            self.state.board_lock_times[y][x].take();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Advances the level for every interval passed, if the level progresses with time.
+    fn update_time_level(&mut self, feedback_events: &mut FeedbackEvents) {
+        let LevelProgression::Time(interval) = self.mode.level_progression else {
+            return;
+        };
+        if !self.mode.increment_level || interval.is_zero() {
+            return;
+        }
+        let intervals_passed = self.state.time.as_nanos() / interval.as_nanos();
+        while u128::from(self.state.level_progress) < intervals_passed {
+            self.state.level_progress += 1;
+            self.level_up(feedback_events, interval * self.state.level_progress);
+        }
+    }
+
     /// Updates the internal `self.state.end` state, checking whether any [`Limits`] have been reached.
     fn update_game_end(&mut self) {
         self.state.end = self.state.end.or_else(|| {
-            [
-                self.mode
-                    .limits
-                    .time
-                    .and_then(|(win, dur)| (dur <= self.state.time).then_some(win)),
-                self.mode.limits.pieces.and_then(|(win, pcs)| {
-                    (pcs <= self.state.pieces_played.iter().sum()).then_some(win)
-                }),
-                self.mode
-                    .limits
-                    .lines
-                    .and_then(|(win, lns)| (lns <= self.state.lines_cleared).then_some(win)),
-                self.mode
-                    .limits
-                    .level
-                    .and_then(|(win, lvl)| (lvl < self.state.level).then_some(win)),
-                self.mode
-                    .limits
-                    .score
-                    .and_then(|(win, pts)| (pts <= self.state.score).then_some(win)),
-            ]
-            .into_iter()
-            .find_map(|limit_reached| {
-                limit_reached.map(|win| {
-                    if win {
-                        Ok(())
-                    } else {
-                        Err(GameOver::ModeLimit)
-                    }
-                })
+            self.mode.limits.outcome(&self.state).map(|win| {
+                if win {
+                    Ok(())
+                } else {
+                    Err(GameOver::ModeLimit)
+                }
             })
         });
     }
@@ -933,6 +1742,9 @@ impl Game {
     ///   can change the game state, or
     /// - [`GameUpdateError::DurationPassed`] if `update_time < game.state().time`, indicating that
     ///   the requested update lies in the past.
+    ///
+    /// If the game runs in frames (see [`GameConfig::tick_rate`]), `update_time` is first rounded
+    /// down to the start of its frame.
     pub fn update(
         &mut self,
         mut new_button_state: Option<ButtonsPressed>,
@@ -952,6 +1764,10 @@ impl Game {
             - try adding input events, goto *
             - else return immediately
          */
+        let update_time = self
+            .frame_at(update_time)
+            .and_then(|frame| self.frame_start(frame))
+            .unwrap_or(update_time);
         // Invalid call: return immediately.
         if update_time < self.state.time {
             return Err(GameUpdateError::DurationPassed);
@@ -962,6 +1778,7 @@ impl Game {
         // NOTE: Returning an empty Vec is efficient because it won't even allocate (as by Rust API).
         let mut feedback_events = Vec::new();
         self.apply_modifiers(&mut feedback_events, &ModifierPoint::UpdateStart);
+        self.align_events_to_frames();
         // We linearly process all events until we reach the update time.
         'event_simulation: loop {
             // Peek the next closest event.
@@ -981,6 +1798,8 @@ impl Game {
                     self.state.time = event_time;
                     feedback_events.extend(new_feedback_events);
                     self.apply_modifiers(&mut feedback_events, &ModifierPoint::AfterEvent(event));
+                    self.align_events_to_frames();
+                    self.update_time_level(&mut feedback_events);
                     // Stop simulation early if event or modifier ended game.
                     self.update_game_end();
                     if self.ended() {
@@ -1006,9 +1825,11 @@ impl Game {
                                 &mut feedback_events,
                                 &ModifierPoint::AfterButtonChange,
                             );
+                            self.align_events_to_frames();
                         }
                         self.state.buttons_pressed = buttons_pressed;
                     } else {
+                        self.update_time_level(&mut feedback_events);
                         self.update_game_end();
                         break 'event_simulation;
                     }
@@ -1022,9 +1843,9 @@ impl Game {
     /// player in form of a change of button states.
     fn add_input_events(&mut self, next_buttons_pressed: ButtonsPressed, update_time: GameTime) {
         #[allow(non_snake_case)]
-        let [mL0, mR0, rL0, rR0, rA0, dS0, dH0, dC0, h0] = self.state.buttons_pressed;
+        let [mL0, mR0, rL0, rR0, rA0, dS0, dH0, dC0, h0, z0] = self.state.buttons_pressed;
         #[allow(non_snake_case)]
-        let [mL1, mR1, rL1, rR1, rA1, dS1, dH1, dC1, h1] = next_buttons_pressed;
+        let [mL1, mR1, rL1, rR1, rA1, dS1, dH1, dC1, h1, z1] = next_buttons_pressed;
         /*
         Table:                                 Karnaugh map:
         | mL0 mR0 mL1 mR1                      |           !mL1 !mL1  mL1  mL1
@@ -1104,37 +1925,43 @@ impl Game {
                 .insert(InternalEvent::SoftDrop, update_time);
         // Soft drop button released: Reset fall timer.
         } else if dS0 && !dS1 {
-            self.state.events.insert(
-                InternalEvent::Fall,
-                update_time + Self::drop_delay(self.state.level, None),
-            );
+            self.state
+                .events
+                .insert(InternalEvent::Fall, update_time + self.drop_delay(None));
         }
         // Hard drop button pressed.
-        if !dH0 && dH1 {
+        if !dH0 && dH1 && !self.config.no_hard_drop {
             self.state
                 .events
                 .insert(InternalEvent::HardDrop, update_time);
         }
         // Sonic drop button pressed
-        if !dC0 && dC1 {
+        if !dC0 && dC1 && !self.config.no_hard_drop {
             self.state
                 .events
                 .insert(InternalEvent::SonicDrop, update_time);
         }
         // Hold button pressed
-        if !h0 && h1 {
+        if !h0 && h1 && !self.config.no_hold {
             self.state
                 .events
                 .insert(InternalEvent::HoldPiece, update_time);
         }
+        // Zone button pressed.
+        if !z0 && z1 {
+            self.state
+                .events
+                .insert(InternalEvent::ZoneStart, update_time);
+        }
     }
 
     /// Given a tetromino variant to be spawned onto the board, returns the correct initial state of
     /// [`ActivePiece`].
-    fn position_tetromino(shape: Tetromino) -> ActivePiece {
-        let pos = match shape {
-            Tetromino::O => (4, 20),
-            _ => (3, 20),
+    pub fn position_tetromino(shape: Tetromino, big: bool) -> ActivePiece {
+        let pos = match (shape, big) {
+            (Tetromino::O, false) => (4, Self::SKYLINE),
+            (_, false) => (3, Self::SKYLINE),
+            (_, true) => (1, Self::SKYLINE / 2),
         };
         let orientation = Orientation::N;
         /* NOTE: Unused spawn positions/orientations. While nice and symmetrical :): also unusual.
@@ -1154,6 +1981,31 @@ impl Game {
         }
     }
 
+    /// Returns the board as seen by the pieces in play.
+    ///
+    /// For big pieces, each cell stands for 2×2 cells of the actual board, and all cells beyond
+    /// its halved width and height are walled off (see [`GameMode::big`]).
+    pub fn piece_board(board: &Board, big: bool) -> Cow<'_, Board> {
+        if !big {
+            return Cow::Borrowed(board);
+        }
+        let wall = Some(Self::GARBAGE_TILE);
+        let scaled_board = (0..Self::HEIGHT)
+            .map(|y| {
+                let mut line = [wall; Self::WIDTH];
+                if 2 * y + 1 < board.len() {
+                    for (x, cell) in line.iter_mut().enumerate().take(Self::WIDTH / 2) {
+                        *cell = [(0, 0), (1, 0), (0, 1), (1, 1)]
+                            .iter()
+                            .find_map(|(dx, dy)| board[2 * y + dy][2 * x + dx]);
+                    }
+                }
+                line
+            })
+            .collect();
+        Cow::Owned(scaled_board)
+    }
+
     /// Given an event, update the internal game state, possibly adding new future events.
     ///
     /// This function is likely the most important part of a game update as it handles the logic of
@@ -1164,6 +2016,7 @@ impl Game {
         let mut feedback_events = Vec::new();
         let prev_piece_data = self.state.active_piece_data;
         let prev_piece = prev_piece_data.unzip().0;
+        let board = Self::piece_board(&self.state.board, self.mode.big);
         let next_piece = match event {
             // We generate a new piece above the skyline, and immediately queue a fall event for it.
             InternalEvent::Spawn => {
@@ -1188,10 +2041,16 @@ impl Game {
                                 .saturating_sub(self.state.next_pieces.len()),
                         ),
                 );
-                let next_piece = Self::position_tetromino(tetromino);
+                let next_piece = Self::position_tetromino(tetromino, self.mode.big);
                 feedback_events.push((event_time, Feedback::PieceSpawned(next_piece)));
+                self.state.piece_spawn_time = event_time;
+                if let LevelProgression::Sections(section) = self.mode.level_progression {
+                    if self.mode.increment_level && self.state.level_progress + 1 < section.get() {
+                        self.state.level_progress += 1;
+                    }
+                }
                 // Newly spawned piece conflicts with board - Game over.
-                if !next_piece.fits(&self.state.board) {
+                if !next_piece.fits(&board) {
                     self.state.end = Some(Err(GameOver::BlockOut));
                     return feedback_events;
                 }
@@ -1228,7 +2087,9 @@ impl Game {
                             );
                         }
                         self.state.hold_piece = Some((prev_piece.shape, false));
-                        self.state.events.clear();
+                        self.state
+                            .events
+                            .retain(|event, _| *event == InternalEvent::ZoneEnd);
                         self.state.events.insert(InternalEvent::Spawn, event_time);
                         None
                     }
@@ -1239,7 +2100,7 @@ impl Game {
                 let prev_piece = prev_piece.expect("rotate event but no active piece");
                 self.config
                     .rotation_system
-                    .rotate(&prev_piece, &self.state.board, turns)
+                    .rotate(&prev_piece, &board, turns)
                     .or(Some(prev_piece))
             }
             InternalEvent::MoveSlow | InternalEvent::MoveFast => {
@@ -1254,7 +2115,7 @@ impl Game {
                     dx += 1;
                 }
                 Some(
-                    if let Some(next_piece) = prev_piece.fits_at(&self.state.board, (dx, 0)) {
+                    if let Some(next_piece) = prev_piece.fits_at(&board, (dx, 0)) {
                         let move_delay = if event == InternalEvent::MoveSlow {
                             self.config.delayed_auto_shift
                         } else {
@@ -1275,13 +2136,16 @@ impl Game {
             }
             InternalEvent::Fall => {
                 let prev_piece = prev_piece.expect("falling event but no active piece");
+                if self.state.gravity_suspended {
+                    return feedback_events;
+                }
                 // Try to drop active piece down by one, and queue next fall event.
                 Some(
-                    if let Some(dropped_piece) = prev_piece.fits_at(&self.state.board, (0, -1)) {
+                    if let Some(dropped_piece) = prev_piece.fits_at(&board, (0, -1)) {
                         // Drop delay is possibly faster due to soft drop button pressed.
                         let soft_drop = self.state.buttons_pressed[Button::DropSoft]
                             .then_some(self.config.soft_drop_factor);
-                        let drop_delay = Self::drop_delay(self.state.level, soft_drop);
+                        let drop_delay = self.drop_delay(soft_drop);
                         self.state
                             .events
                             .insert(InternalEvent::Fall, event_time + drop_delay);
@@ -1296,10 +2160,10 @@ impl Game {
                 let prev_piece = prev_piece.expect("softdrop event but no active piece");
                 // Try to drop active piece down by one, and queue next fall event.
                 Some(
-                    if let Some(dropped_piece) = prev_piece.fits_at(&self.state.board, (0, -1)) {
+                    if let Some(dropped_piece) = prev_piece.fits_at(&board, (0, -1)) {
                         let soft_drop = self.state.buttons_pressed[Button::DropSoft]
                             .then_some(self.config.soft_drop_factor);
-                        let drop_delay = Self::drop_delay(self.state.level, soft_drop);
+                        let drop_delay = self.drop_delay(soft_drop);
                         self.state
                             .events
                             .insert(InternalEvent::Fall, event_time + drop_delay);
@@ -1319,12 +2183,12 @@ impl Game {
             InternalEvent::SonicDrop => {
                 let prev_piece = prev_piece.expect("sonicdrop event but no active piece");
                 // Move piece all the way down and nothing more.
-                Some(prev_piece.well_piece(&self.state.board))
+                Some(prev_piece.well_piece(&board))
             }
             InternalEvent::HardDrop => {
                 let prev_piece = prev_piece.expect("harddrop event but no active piece");
                 // Move piece all the way down.
-                let dropped_piece = prev_piece.well_piece(&self.state.board);
+                let dropped_piece = prev_piece.well_piece(&board);
                 feedback_events.push((event_time, Feedback::HardDrop(prev_piece, dropped_piece)));
                 self.state.events.insert(
                     InternalEvent::LockTimer,
@@ -1340,35 +2204,54 @@ impl Game {
                 let prev_piece = prev_piece.expect("lock event but no active piece");
                 feedback_events.push((event_time, Feedback::PieceLocked(prev_piece)));
                 // Attempt to lock active piece fully above skyline - Game over.
-                if prev_piece
-                    .tiles()
-                    .iter()
-                    .all(|((_, y), _)| *y >= Self::SKYLINE)
-                {
+                let skyline = if self.mode.big {
+                    Self::SKYLINE / 2
+                } else {
+                    Self::SKYLINE
+                };
+                if prev_piece.tiles().iter().all(|((_, y), _)| *y >= skyline) {
                     self.state.end = Some(Err(GameOver::LockOut));
                     return feedback_events;
                 }
                 self.state.pieces_played[prev_piece.shape] += 1;
                 // Pre-save whether piece was spun into lock position.
-                let spin = prev_piece.fits_at(&self.state.board, (0, 1)).is_none();
+                let (spin, mini) = self.config.spin_detection.detect(
+                    &prev_piece,
+                    &board,
+                    self.state.last_rotation,
+                );
                 // Locking.
-                for ((x, y), tile_type_id) in prev_piece.tiles() {
+                self.state
+                    .board_lock_times
+                    .resize(self.state.board.len(), Default::default());
+                for ((x, y), tile_type_id) in prev_piece.board_tiles(self.mode.big) {
                     self.state.board[y][x] = Some(tile_type_id);
+                    self.state.board_lock_times[y][x] = Some(event_time);
                 }
                 // Handle line clear counting for score (only do actual clearing in LineClear).
-                let mut lines_cleared = Vec::<usize>::with_capacity(4);
-                for y in (0..Self::HEIGHT).rev() {
-                    if self.state.board[y].iter().all(|mino| mino.is_some()) {
-                        lines_cleared.push(y);
-                    }
+                // Lines collected during the zone are not cleared again.
+                let zone_rows = self.state.zone_lines as usize;
+                let lines_cleared = self.full_lines(zone_rows);
+                let mut n_lines_cleared = u32::try_from(lines_cleared.len()).unwrap();
+                // Big pieces clear lines in pairs, which count as one.
+                if self.mode.big {
+                    n_lines_cleared /= 2;
                 }
-                let n_lines_cleared = u32::try_from(lines_cleared.len()).unwrap();
-                if n_lines_cleared > 0 {
+                if self.state.zone_end.is_some() && n_lines_cleared > 0 {
+                    // During the zone, full lines sink to the bottom of the board instead.
+                    // Each line moved pushes the (higher) lines yet to be moved up by one.
+                    for (k, y) in lines_cleared.iter().enumerate() {
+                        let line = self.state.board.remove(y + k);
+                        let lock_times = self.state.board_lock_times.remove(y + k);
+                        self.state.board.insert(0, line);
+                        self.state.board_lock_times.insert(0, lock_times);
+                    }
+                    self.state.zone_lines += u32::try_from(lines_cleared.len()).unwrap();
+                    self.state.pieces_since_line_clear = 0;
+                    n_lines_cleared = 0;
+                } else if n_lines_cleared > 0 {
                     // Add score bonus.
-                    let perfect_clear = self.state.board.iter().all(|line| {
-                        line.iter().all(|tile| tile.is_none())
-                            || line.iter().all(|tile| tile.is_some())
-                    });
+                    let perfect_clear = self.is_perfect_clear(&lines_cleared);
                     self.state.consecutive_line_clears += 1;
                     let special_clear = n_lines_cleared >= 4 || spin || perfect_clear;
                     if special_clear {
@@ -1376,16 +2259,23 @@ impl Game {
                     } else {
                         self.state.back_to_back_special_clears = 0;
                     }
-                    let score_bonus = 10
-                        * (n_lines_cleared + self.state.consecutive_line_clears - 1).pow(2)
-                        * self.state.back_to_back_special_clears.max(1)
-                        * if spin { 4 } else { 1 }
-                        * if perfect_clear { 100 } else { 1 };
+                    let score_bonus = self.config.scoring.score_bonus(
+                        ScoredClear {
+                            lines: n_lines_cleared,
+                            spin,
+                            mini,
+                            perfect_clear,
+                            combo: self.state.consecutive_line_clears,
+                            back_to_back: self.state.back_to_back_special_clears,
+                        },
+                        self.state.level,
+                    );
                     self.state.score += u64::from(score_bonus);
                     let yippie = Feedback::Accolade {
                         score_bonus,
                         shape: prev_piece.shape,
                         spin,
+                        mini,
                         lineclears: n_lines_cleared,
                         perfect_clear,
                         combo: self.state.consecutive_line_clears,
@@ -1396,20 +2286,73 @@ impl Game {
                         event_time,
                         Feedback::LineClears(lines_cleared, self.config.line_clear_delay),
                     ));
+                    self.state.pieces_since_line_clear = 0;
+                    if self.mode.zone && self.state.zone_end.is_none() {
+                        self.state.zone_meter =
+                            (self.state.zone_meter + n_lines_cleared).min(Self::ZONE_METER_MAX);
+                    }
                 } else {
                     self.state.consecutive_line_clears = 0;
+                    self.state.pieces_since_line_clear += 1;
+                    // Spins are worth points even without clearing lines.
+                    if spin && self.config.scoring == Scoring::Guideline {
+                        let score_bonus = if mini { 100 } else { 400 } * self.state.level.get();
+                        self.state.score += u64::from(score_bonus);
+                        feedback_events.push((
+                            event_time,
+                            Feedback::Message(format!(
+                                "{}{:?}-Spin +{score_bonus}",
+                                if mini { "Mini " } else { "" },
+                                prev_piece.shape
+                            )),
+                        ));
+                    }
+                }
+                if self.mode.increment_level
+                    && self.mode.level_progression == LevelProgression::VariableGoal
+                {
+                    let t_spin = spin && prev_piece.shape == Tetromino::T;
+                    let awarded_lines = match (t_spin, mini, n_lines_cleared) {
+                        (false, _, n) => [0, 1, 3, 5, 8][n.min(4) as usize],
+                        (true, true, n) => [1, 2, 4][n.min(2) as usize],
+                        (true, false, n) => 4 * (n.min(3) + 1),
+                    };
+                    let back_to_back = n_lines_cleared > 0
+                        && (n_lines_cleared >= 4 || t_spin)
+                        && self.state.back_to_back_special_clears > 1;
+                    self.state.level_progress += if back_to_back {
+                        awarded_lines * 3 / 2
+                    } else {
+                        awarded_lines
+                    };
+                    while self.state.level_progress >= 5 * self.state.level.get() {
+                        self.state.level_progress -= 5 * self.state.level.get();
+                        self.level_up(&mut feedback_events, event_time);
+                    }
                 }
-                // Clear all events and only put in line clear / appearance delay.
-                self.state.events.clear();
+                // Clear all events (except for the zone running out) and only put in line clear /
+                // appearance delay.
+                self.state
+                    .events
+                    .retain(|event, _| *event == InternalEvent::ZoneEnd);
+                // Pieces locked higher up may take longer until the next one appears.
+                let lock_height = prev_piece
+                    .tiles()
+                    .iter()
+                    .map(|((_, y), _)| *y)
+                    .min()
+                    .unwrap();
+                let height_delay = self.config.appearance_delay_per_height
+                    * u32::try_from((lock_height + 2) / 4).unwrap().min(4);
                 if n_lines_cleared > 0 {
                     self.state.events.insert(
                         InternalEvent::LineClear,
-                        event_time + self.config.line_clear_delay,
+                        event_time + self.config.line_clear_delay + height_delay,
                     );
                 } else {
                     self.state.events.insert(
                         InternalEvent::Spawn,
-                        event_time + self.config.appearance_delay,
+                        event_time + self.config.appearance_delay + height_delay,
                     );
                 }
                 self.state.hold_piece = self
@@ -1419,29 +2362,171 @@ impl Game {
                 None
             }
             InternalEvent::LineClear => {
-                for y in (0..Self::HEIGHT).rev() {
-                    // Full line: move it to the cleared lines storage and push an empty line to the board.
-                    if self.state.board[y].iter().all(|mino| mino.is_some()) {
-                        self.state.board.remove(y);
-                        self.state.lines_cleared += 1;
-                        // Increment level if 10 lines cleared.
-                        if self.mode.increment_level && self.state.lines_cleared % 10 == 0 {
-                            self.state.level = self.state.level.saturating_add(1);
-                        }
+                // Full lines: remove them from the board and push empty lines to it.
+                for (rows_cleared, y) in self.full_lines(0).into_iter().enumerate() {
+                    let line = self.state.board.remove(y);
+                    if y < self.state.board_lock_times.len() {
+                        self.state.board_lock_times.remove(y);
+                    }
+                    // Big pieces clear lines in pairs, which count as one.
+                    if self.mode.big && rows_cleared % 2 == 1 {
+                        continue;
                     }
+                    self.count_line_clear(&line, &mut feedback_events, event_time);
                 }
                 while self.state.board.len() < Self::HEIGHT {
                     self.state.board.push(Default::default());
                 }
-                self.state.events.insert(
-                    InternalEvent::Spawn,
-                    event_time + self.config.appearance_delay,
-                );
+                self.state
+                    .board_lock_times
+                    .resize(self.state.board.len(), Default::default());
+                // Cascade gravity: let blocks fall and clear any lines they complete as a chain.
+                let mut chain_clear = false;
+                if self.mode.cascade && self.cascade() {
+                    let lines_cleared = self.full_lines(0);
+                    let mut n_lines_cleared = u32::try_from(lines_cleared.len()).unwrap();
+                    if self.mode.big {
+                        n_lines_cleared /= 2;
+                    }
+                    if n_lines_cleared > 0 {
+                        // Chain clears are scored like combos of the piece that started them.
+                        self.state.consecutive_line_clears += 1;
+                        let perfect_clear = self.is_perfect_clear(&lines_cleared);
+                        let score_bonus = self.config.scoring.score_bonus(
+                            ScoredClear {
+                                lines: n_lines_cleared,
+                                spin: false,
+                                mini: false,
+                                perfect_clear,
+                                combo: self.state.consecutive_line_clears,
+                                back_to_back: self.state.back_to_back_special_clears,
+                            },
+                            self.state.level,
+                        );
+                        self.state.score += u64::from(score_bonus);
+                        feedback_events.push((
+                            event_time,
+                            Feedback::Message(format!(
+                                "{}-chain! +{score_bonus}",
+                                self.state.consecutive_line_clears
+                            )),
+                        ));
+                        feedback_events.push((
+                            event_time,
+                            Feedback::LineClears(lines_cleared, self.config.line_clear_delay),
+                        ));
+                        chain_clear = true;
+                    }
+                }
+                if chain_clear {
+                    self.state.events.insert(
+                        InternalEvent::LineClear,
+                        event_time + self.config.line_clear_delay,
+                    );
+                } else {
+                    self.state.events.insert(
+                        InternalEvent::Spawn,
+                        event_time + self.config.appearance_delay,
+                    );
+                }
                 None
             }
+            InternalEvent::ZoneStart => {
+                if self.mode.zone
+                    && self.state.zone_end.is_none()
+                    && self.state.zone_meter >= Self::ZONE_METER_MAX / 4
+                {
+                    let zone_end = event_time
+                        + Self::ZONE_DURATION_MAX * self.state.zone_meter / Self::ZONE_METER_MAX;
+                    self.state.zone_meter = 0;
+                    self.state.zone_end = Some(zone_end);
+                    self.state.gravity_suspended = true;
+                    self.state.events.remove(&InternalEvent::Fall);
+                    self.state.events.remove(&InternalEvent::LockTimer);
+                    self.state.events.insert(InternalEvent::ZoneEnd, zone_end);
+                    feedback_events.push((event_time, Feedback::Message("Zone!".to_string())));
+                }
+                prev_piece
+            }
+            InternalEvent::ZoneEnd => {
+                self.state.zone_end = None;
+                self.state.gravity_suspended = false;
+                let zone_rows = self.state.zone_lines as usize;
+                self.state.zone_lines = 0;
+                for row in 0..zone_rows {
+                    let line = self.state.board.remove(0);
+                    if !self.state.board_lock_times.is_empty() {
+                        self.state.board_lock_times.remove(0);
+                    }
+                    // Big pieces clear lines in pairs, which count as one.
+                    if self.mode.big && row % 2 == 1 {
+                        continue;
+                    }
+                    self.count_line_clear(&line, &mut feedback_events, event_time);
+                }
+                while self.state.board.len() < Self::HEIGHT {
+                    self.state.board.push(Default::default());
+                }
+                self.state
+                    .board_lock_times
+                    .resize(self.state.board.len(), Default::default());
+                let mut n_lines_cleared = u32::try_from(zone_rows).unwrap();
+                if self.mode.big {
+                    n_lines_cleared = n_lines_cleared.div_ceil(2);
+                }
+                if n_lines_cleared > 0 {
+                    // All lines of the zone are scored as a single clear.
+                    let score_bonus = self.config.scoring.score_bonus(
+                        ScoredClear {
+                            lines: n_lines_cleared,
+                            spin: false,
+                            mini: false,
+                            perfect_clear: false,
+                            combo: 1,
+                            back_to_back: 0,
+                        },
+                        self.state.level,
+                    );
+                    self.state.score += u64::from(score_bonus);
+                    feedback_events.push((
+                        event_time,
+                        Feedback::Message(format!("{n_lines_cleared}-line Zone! +{score_bonus}")),
+                    ));
+                    feedback_events.push((
+                        event_time,
+                        Feedback::LineClears(
+                            (0..zone_rows).rev().collect(),
+                            self.config.line_clear_delay,
+                        ),
+                    ));
+                }
+                // Gravity resumes right away.
+                if prev_piece.is_some() {
+                    self.state.events.insert(InternalEvent::Fall, event_time);
+                }
+                // The active piece drops down with the rest of the board, which always leaves it in
+                // free space.
+                let piece_rows = if self.mode.big {
+                    zone_rows / 2
+                } else {
+                    zone_rows
+                };
+                let board = Self::piece_board(&self.state.board, self.mode.big);
+                prev_piece.map(|prev_piece| {
+                    prev_piece
+                        .fits_at(&board, (0, -isize::try_from(piece_rows).unwrap()))
+                        .unwrap_or(prev_piece)
+                })
+            }
         };
         // Piece changed.
         if next_piece.is_some() && prev_piece != next_piece {
+            self.state.last_rotation = match (event, prev_piece, next_piece) {
+                (InternalEvent::Rotate(_), Some(prev_piece), Some(next_piece)) => {
+                    Some((prev_piece, next_piece))
+                }
+                _ => None,
+            };
             // No move event scheduled but user wants to move to one side, add a move event.
             if !(self.state.events.contains_key(&InternalEvent::MoveSlow)
                 || self.state.events.contains_key(&InternalEvent::MoveFast))
@@ -1454,15 +2539,20 @@ impl Game {
             }
             // No fall event scheduled but piece might be able to, schedule fall event.
             #[allow(clippy::map_entry)]
-            if !self.state.events.contains_key(&InternalEvent::Fall) {
+            if !self.state.events.contains_key(&InternalEvent::Fall)
+                && !self.state.gravity_suspended
+            {
                 let soft_drop = self.state.buttons_pressed[Button::DropSoft]
                     .then_some(self.config.soft_drop_factor);
-                let drop_delay = Self::drop_delay(self.state.level, soft_drop);
+                let drop_delay = self.drop_delay(soft_drop);
                 self.state
                     .events
                     .insert(InternalEvent::Fall, event_time + drop_delay);
             }
         }
+        let board = Self::piece_board(&self.state.board, self.mode.big);
+        let touches_ground =
+            next_piece.is_some_and(|next_piece| next_piece.fits_at(&board, (0, -1)).is_none());
         self.state.active_piece_data = next_piece.map(|next_piece| {
             (
                 next_piece,
@@ -1471,7 +2561,7 @@ impl Game {
                     event_time,
                     prev_piece_data,
                     next_piece,
-                    next_piece.fits_at(&self.state.board, (0, -1)).is_none(),
+                    touches_ground,
                 ),
             )
         });
@@ -1505,6 +2595,7 @@ impl Game {
                 last_liftoff: Some(event_time),
                 ground_time_left: self.config.ground_time_max,
                 lowest_y: next_piece.position.1,
+                lock_resets: 0,
             },
             // [2] Active piece lifted off the ground.
             (Some((_prev_piece, prev_locking_data)), false) if prev_locking_data.touches_ground => {
@@ -1517,7 +2608,7 @@ impl Game {
             }
             // [3] A piece is on the ground. Complex update to locking values.
             (prev_piece_data, true) => {
-                let next_locking_data = match prev_piece_data {
+                let mut next_locking_data = match prev_piece_data {
                     // If previous piece exists and next piece hasn't reached newest low (i.e. not a reset situation).
                     Some((_prev_piece, prev_locking_data))
                         if next_piece.position.1 >= prev_locking_data.lowest_y =>
@@ -1543,7 +2634,7 @@ impl Game {
                                 Some(last_touchdown) => {
                                     let (last_touchdown, ground_time_left) = if event_time
                                         .saturating_sub(last_liftoff)
-                                        <= 2 * Self::drop_delay(self.state.level, None)
+                                        <= 2 * self.drop_delay(None)
                                     {
                                         (
                                             prev_locking_data.last_touchdown,
@@ -1565,6 +2656,7 @@ impl Game {
                                         last_liftoff: None,
                                         ground_time_left,
                                         lowest_y: prev_locking_data.lowest_y,
+                                        lock_resets: prev_locking_data.lock_resets,
                                     }
                                 }
                                 // Piece existed, was not touching ground, is touching ground now, but does not have a last touchdown. Just set touchdown.
@@ -1583,6 +2675,7 @@ impl Game {
                         last_liftoff: None,
                         ground_time_left: self.config.ground_time_max,
                         lowest_y: next_piece.position.1,
+                        lock_resets: 0,
                     },
                 };
                 // Set lock timer if there isn't one, or refresh it if piece was moved.
@@ -1591,9 +2684,17 @@ impl Game {
                     .unwrap_or(false);
                 #[rustfmt::skip]
                 let move_rotate = matches!(event, InternalEvent::Rotate(_) | InternalEvent::MoveSlow | InternalEvent::MoveFast);
-                if !self.state.events.contains_key(&InternalEvent::LockTimer)
-                    || (repositioned && move_rotate)
+                let lock_timer_running = self.state.events.contains_key(&InternalEvent::LockTimer);
+                let reset_allowed = self.config.lock_resets_max.map_or(true, |lock_resets_max| {
+                    next_locking_data.lock_resets < lock_resets_max
+                });
+                // While gravity is suspended, pieces only lock when dropped.
+                if !self.state.gravity_suspended
+                    && (!lock_timer_running || (repositioned && move_rotate && reset_allowed))
                 {
+                    if lock_timer_running {
+                        next_locking_data.lock_resets += 1;
+                    }
                     // SAFETY: We know this must be `Some` in this case.
                     let current_ground_time =
                         event_time.saturating_sub(next_locking_data.last_touchdown.unwrap());
@@ -1613,32 +2714,52 @@ impl Game {
         }
     }
 
-    /// The amount of time left for a piece to fall naturally, purely dependent on level
-    /// and an optional soft-drop-factor.
+    /// The amount of time left for a piece to fall naturally, dependent on gravity, level and
+    /// whether soft drop is active (with which factor).
     #[rustfmt::skip]
-    fn drop_delay(level: NonZeroU32, soft_drop: Option<f64>) -> Duration {
-        let mut drop_delay = Duration::from_nanos(match level.get() {
-             1 => 1_000_000_000,
-             2 =>   793_000_000,
-             3 =>   617_796_000,
-             4 =>   472_729_139,
-             5 =>   355_196_928,
-             6 =>   262_003_550,
-             7 =>   189_677_245,
-             8 =>   134_734_731,
-             9 =>    93_882_249,
-            10 =>    64_151_585,
-            11 =>    42_976_258,
-            12 =>    28_217_678,
-            13 =>    18_153_329,
-            14 =>    11_439_342,
-            15 =>     7_058_616,
-            16 =>     4_263_557,
-            17 =>     2_520_084,
-            18 =>     1_457_139,
-            19 =>       823_907, // NOTE: 20G is at `833_333`, but falling speeds at that level are handled especially by the engine.
-             _ =>             0,
-        });
+    fn drop_delay(&self, soft_drop: Option<f64>) -> Duration {
+        let level = self.state.level.get();
+        let mut drop_delay = match self.config.gravity {
+            Gravity::Standard => Duration::from_nanos(match level {
+                 1 => 1_000_000_000,
+                 2 =>   793_000_000,
+                 3 =>   617_796_000,
+                 4 =>   472_729_139,
+                 5 =>   355_196_928,
+                 6 =>   262_003_550,
+                 7 =>   189_677_245,
+                 8 =>   134_734_731,
+                 9 =>    93_882_249,
+                10 =>    64_151_585,
+                11 =>    42_976_258,
+                12 =>    28_217_678,
+                13 =>    18_153_329,
+                14 =>    11_439_342,
+                15 =>     7_058_616,
+                16 =>     4_263_557,
+                17 =>     2_520_084,
+                18 =>     1_457_139,
+                19 =>       823_907, // NOTE: 20G is at `833_333`, but falling speeds at that level are handled especially by the engine.
+                 _ =>             0,
+            }),
+            // NES frames per row, i.e. 43 at level 1 down to 1 at level 29 and beyond.
+            Gravity::Classic => Game::NES_FRAME * match level {
+                 1 => 43,
+                 2 => 38,
+                 3 => 33,
+                 4 => 28,
+                 5 => 23,
+                 6 => 18,
+                 7 => 13,
+                 8 =>  8,
+                 9 =>  6,
+                10..=12 => 5,
+                13..=15 => 4,
+                16..=18 => 3,
+                19..=28 => 2,
+                 _ => 1,
+            },
+        };
         if let Some(soft_drop_factor) = soft_drop {
             drop_delay = Duration::from_secs_f64(
                 drop_delay.as_secs_f64() / soft_drop_factor.max(0.00001),
@@ -1667,19 +2788,833 @@ impl Game {
     }
 }
 
+/// Clones a configuration without resetting the internal state of its piece generator.
+fn config_with_state(config: &GameConfig) -> GameConfig {
+    GameConfig {
+        tetromino_generator: config.tetromino_generator.clone_with_state(),
+        ..config.clone()
+    }
+}
+
+/// Reads buttons pressed leniently, as states saved before new buttons were added have fewer of them.
+#[cfg(feature = "serde")]
+fn deserialize_buttons_pressed<'de, D>(deserializer: D) -> Result<ButtonsPressed, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let saved = <Vec<bool> as serde::Deserialize>::deserialize(deserializer)?;
+    let mut buttons_pressed = ButtonsPressed::default();
+    for (pressed, saved_pressed) in buttons_pressed.iter_mut().zip(saved) {
+        *pressed = saved_pressed;
+    }
+    Ok(buttons_pressed)
+}
+
+/// The center mino of a T piece, i.e. the one adjacent to all others.
+fn t_center(piece: &ActivePiece) -> Coord {
+    let coords = piece.tiles().map(|(coord, _)| coord);
+    *coords
+        .iter()
+        .find(|(x, y)| {
+            coords
+                .iter()
+                .filter(|(x1, y1)| x.abs_diff(*x1) + y.abs_diff(*y1) == 1)
+                .count()
+                == 3
+        })
+        .unwrap()
+}
+
 /// Adds an offset to a board coordinate, failing if the result is out of bounds
 /// (negative or positive overflow in either direction).
 pub fn add((x0, y0): Coord, (x1, y1): Offset) -> Option<Coord> {
     Some((x0.checked_add_signed(x1)?, y0.checked_add_signed(y1)?))
 }
 
-/*#[cfg(test)]
+#[cfg(test)]
 mod tests {
     use super::*;
 
+    fn t_piece(orientation: Orientation, position: Coord) -> ActivePiece {
+        ActivePiece {
+            shape: Tetromino::T,
+            orientation,
+            position,
+        }
+    }
+
+    fn board_with(cells: &[Coord]) -> Board {
+        let mut board = vec![Line::default(); Game::HEIGHT];
+        for &(x, y) in cells {
+            board[y][x] = Some(Game::GARBAGE_TILE);
+        }
+        board
+    }
+
+    /// A T pointing down with its center at (4, 1), rotated there from pointing right.
+    fn t_down() -> (ActivePiece, Option<(ActivePiece, ActivePiece)>) {
+        let piece = t_piece(Orientation::S, (3, 0));
+        let piece_before_rotation = t_piece(Orientation::E, (4, 0));
+        (piece, Some((piece_before_rotation, piece)))
+    }
+
+    /// Plays a game by placing pieces directly, keeping track of all feedback given.
+    struct Player {
+        game: Game,
+        time: GameTime,
+        feedback: Vec<Feedback>,
+    }
+
+    impl Player {
+        fn new(mode: GameMode, config: GameConfig) -> Self {
+            Self {
+                game: Game::with_seed(mode, config, 0),
+                time: Duration::ZERO,
+                feedback: Vec::new(),
+            }
+        }
+
+        fn update(&mut self, buttons_pressed: Option<ButtonsPressed>, time: GameTime) {
+            self.time = time;
+            if let Ok(feedback_events) = self.game.update(buttons_pressed, time) {
+                self.feedback
+                    .extend(feedback_events.into_iter().map(|(_, feedback)| feedback));
+            }
+        }
+
+        fn wait(&mut self, duration: Duration) {
+            self.update(None, self.time + duration);
+        }
+
+        fn press(&mut self, button: Button) {
+            let mut buttons_pressed = ButtonsPressed::default();
+            buttons_pressed[button] = true;
+            self.update(Some(buttons_pressed), self.time + Duration::from_millis(1));
+            self.update(
+                Some(ButtonsPressed::default()),
+                self.time + Duration::from_millis(1),
+            );
+        }
+
+        /// Lets time pass in small steps until a piece is in play.
+        fn await_piece(&mut self) {
+            while self.game.state.active_piece_data.is_none() && !self.game.ended() {
+                self.wait(Duration::from_millis(1));
+            }
+        }
+
+        /// Puts a piece in play in place of the spawned one and hard drops it, optionally as if it
+        /// was just rotated into place, then waits for the next piece.
+        fn lock(&mut self, piece: ActivePiece, rotated: bool) {
+            self.await_piece();
+            self.game.state.active_piece_data.as_mut().unwrap().0 = piece;
+            self.game.state.last_rotation = rotated.then_some((piece, piece));
+            self.press(Button::DropHard);
+            self.await_piece();
+        }
+
+        fn level_ups(&self) -> Vec<u32> {
+            self.feedback
+                .iter()
+                .filter_map(|feedback| match feedback {
+                    Feedback::LevelUp(level) => Some(level.get()),
+                    _ => None,
+                })
+                .collect()
+        }
+    }
+
+    fn piece(shape: Tetromino, orientation: Orientation, position: Coord) -> ActivePiece {
+        ActivePiece {
+            shape,
+            orientation,
+            position,
+        }
+    }
+
+    fn level_mode(level_progression: LevelProgression) -> GameMode {
+        GameMode {
+            level_progression,
+            ..GameMode::marathon()
+        }
+    }
+
+    /// Fills the lowest rows of the board except for the given column.
+    fn fill_rows(game: &mut Game, rows: usize, hole: usize) {
+        for line in &mut game.state.board[..rows] {
+            *line = [Some(Game::GARBAGE_TILE); Game::WIDTH];
+            line[hole] = None;
+        }
+    }
+
+    /// A vertical I piece dropped into a well in the given column.
+    fn i_in_well(x: usize) -> ActivePiece {
+        piece(Tetromino::I, Orientation::E, (x, 10))
+    }
+
+    #[test]
+    fn levels_advance_every_few_lines() {
+        let lines = NonZeroU32::try_from(3).unwrap();
+        let mut player = Player::new(
+            level_mode(LevelProgression::Lines(lines)),
+            GameConfig::default(),
+        );
+        player.await_piece();
+        fill_rows(&mut player.game, 4, 0);
+        player.lock(i_in_well(0), false);
+        assert_eq!(player.game.state.lines_cleared, 4);
+        assert_eq!(player.level_ups(), [2]);
+        fill_rows(&mut player.game, 2, 0);
+        player.lock(i_in_well(0), false);
+        assert_eq!(player.level_ups(), [2, 3]);
+    }
+
+    #[test]
+    fn levels_advance_by_the_variable_goal() {
+        let mut player = Player::new(
+            level_mode(LevelProgression::VariableGoal),
+            GameConfig::default(),
+        );
+        player.await_piece();
+        // A quadruple awards 8 lines, of the 5 needed at level 1.
+        fill_rows(&mut player.game, 4, 0);
+        player.lock(i_in_well(0), false);
+        assert_eq!(player.level_ups(), [2]);
+        assert_eq!(player.game.state.level_progress, 3);
+        // A single awards 1 line, of the 10 needed at level 2.
+        fill_rows(&mut player.game, 1, 0);
+        player.lock(i_in_well(0), false);
+        assert_eq!(player.game.state.level_progress, 4);
+        assert_eq!(player.level_ups(), [2]);
+    }
+
+    /// Sets up a T-spin single of a T pointing down with its center at (4, 1), with its second
+    /// front corner at (5, 0) filled only for a full (not mini) T-spin.
+    fn t_spin_single(full: bool) -> Player {
+        let config = GameConfig {
+            spin_detection: SpinDetection::TCorners,
+            ..GameConfig::default()
+        };
+        let mut player = Player::new(level_mode(LevelProgression::VariableGoal), config);
+        player.await_piece();
+        let board = &mut player.game.state.board;
+        board[0] = [Some(Game::GARBAGE_TILE); Game::WIDTH];
+        board[0][4] = None;
+        board[0][5] = full.then_some(Game::GARBAGE_TILE);
+        board[0][9] = None;
+        board[1] = [Some(Game::GARBAGE_TILE); Game::WIDTH];
+        board[1][3..6].fill(None);
+        board[2][3] = Some(Game::GARBAGE_TILE);
+        board[2][5] = Some(Game::GARBAGE_TILE);
+        player.lock(piece(Tetromino::T, Orientation::S, (3, 0)), true);
+        player
+    }
+
+    #[test]
+    fn variable_goal_awards_t_spins_and_minis() {
+        let player = t_spin_single(true);
+        assert_eq!(player.game.state.lines_cleared, 1);
+        assert_eq!(player.level_ups(), [2]);
+        assert_eq!(player.game.state.level_progress, 3);
+        let player = t_spin_single(false);
+        assert_eq!(player.game.state.lines_cleared, 1);
+        assert!(player.level_ups().is_empty());
+        assert_eq!(player.game.state.level_progress, 2);
+    }
+
+    #[test]
+    fn levels_advance_by_sections_of_pieces_and_lines() {
+        let section = NonZeroU32::try_from(4).unwrap();
+        let mut player = Player::new(
+            level_mode(LevelProgression::Sections(section)),
+            GameConfig::default(),
+        );
+        player.await_piece();
+        // The first piece spawned earned a point.
+        assert_eq!(player.game.state.level_progress, 1);
+        // Spawning pieces alone never completes a section.
+        for x in [0, 4, 0, 4, 0] {
+            player.lock(piece(Tetromino::O, Orientation::N, (x, 10)), false);
+        }
+        assert_eq!(player.game.state.level_progress, 3);
+        assert!(player.level_ups().is_empty());
+        // A line clear does, and the next piece earns another point.
+        for line in &mut player.game.state.board {
+            *line = Default::default();
+        }
+        fill_rows(&mut player.game, 1, 0);
+        player.lock(i_in_well(0), false);
+        assert_eq!(player.level_ups(), [2]);
+        assert_eq!(player.game.state.level_progress, 1);
+    }
+
+    #[test]
+    fn levels_advance_with_time() {
+        let interval = Duration::from_secs(2);
+        let mut player = Player::new(
+            level_mode(LevelProgression::Time(interval)),
+            GameConfig::default(),
+        );
+        player.wait(Duration::from_millis(1999));
+        assert!(player.level_ups().is_empty());
+        player.wait(Duration::from_millis(1));
+        assert_eq!(player.level_ups(), [2]);
+        player.wait(Duration::from_secs(4));
+        assert_eq!(player.level_ups(), [2, 3, 4]);
+    }
+
+    fn limits_game() -> Game {
+        let mut game = Game::with_seed(GameMode::zen(), GameConfig::default(), 0);
+        game.update(None, Duration::from_secs(1)).ok();
+        game
+    }
+
+    /// Checks that a single limit is reached exactly once the value given is reached.
+    fn assert_reached_at(limits: Limits, game: &mut Game, set_value: impl Fn(&mut GameState, u32)) {
+        set_value(&mut game.state, 2);
+        assert_eq!(limits.check(&game.state), [(true, false)]);
+        assert_eq!(limits.outcome(&game.state), None);
+        set_value(&mut game.state, 3);
+        assert_eq!(limits.check(&game.state), [(true, true)]);
+        assert_eq!(limits.outcome(&game.state), Some(true));
+    }
+
+    #[test]
+    fn level_limit_is_reached_once_the_level_given_is_completed() {
+        let limits = Limits {
+            level: Some((true, NonZeroU32::try_from(2).unwrap())),
+            ..Default::default()
+        };
+        assert_reached_at(limits, &mut limits_game(), |state, value| {
+            state.level = NonZeroU32::try_from(value).unwrap();
+        });
+    }
+
+    #[test]
+    fn board_limits_are_reached_at_the_value_given() {
+        let limits = Limits {
+            holes: Some((true, 3)),
+            ..Default::default()
+        };
+        assert_reached_at(limits, &mut limits_game(), |state, value| {
+            // A column covered at the top, with the given number of holes below.
+            for line in &mut state.board {
+                line[0] = None;
+            }
+            state.board[value as usize][0] = Some(Game::GARBAGE_TILE);
+        });
+        let limits = Limits {
+            stack_height: Some((true, 3)),
+            ..Default::default()
+        };
+        assert_reached_at(limits, &mut limits_game(), |state, value| {
+            for line in &mut state.board {
+                line[0] = None;
+            }
+            state.board[value as usize - 1][0] = Some(Game::GARBAGE_TILE);
+        });
+    }
+
+    #[test]
+    fn counting_limits_are_reached_at_the_value_given() {
+        let limits = Limits {
+            pieces_without_clear: Some((true, 3)),
+            ..Default::default()
+        };
+        assert_reached_at(limits, &mut limits_game(), |state, value| {
+            state.pieces_since_line_clear = value;
+        });
+        let limits = Limits {
+            garbage_lines: Some((true, 3)),
+            ..Default::default()
+        };
+        assert_reached_at(limits, &mut limits_game(), |state, value| {
+            state.garbage_lines_cleared = value as usize;
+        });
+    }
+
+    #[test]
+    fn piece_time_limit_is_reached_while_a_piece_is_in_play_for_long_enough() {
+        let limits = Limits {
+            piece_time: Some((true, Duration::from_secs(3))),
+            ..Default::default()
+        };
+        let mut game = limits_game();
+        assert_reached_at(limits, &mut game, |state, value| {
+            state.time = state.piece_spawn_time + Duration::from_secs(value.into());
+        });
+        game.state.active_piece_data = None;
+        assert_eq!(limits.check(&game.state), [(true, false)]);
+    }
+
+    #[test]
+    fn any_limit_ends_the_game_by_the_first_reached() {
+        let mut game = limits_game();
+        let limits = Limits {
+            lines: Some((true, 1)),
+            pieces_without_clear: Some((false, 1)),
+            ..Default::default()
+        };
+        assert_eq!(limits.outcome(&game.state), None);
+        game.state.pieces_since_line_clear = 1;
+        assert_eq!(limits.outcome(&game.state), Some(false));
+        game.state.pieces_since_line_clear = 0;
+        game.state.lines_cleared = 1;
+        assert_eq!(limits.outcome(&game.state), Some(true));
+    }
+
+    #[test]
+    fn all_limits_end_the_game_once_every_one_of_a_kind_is_reached() {
+        let mut game = limits_game();
+        let limits = Limits {
+            lines: Some((true, 1)),
+            score: Some((true, 1)),
+            pieces_without_clear: Some((false, 1)),
+            garbage_lines: Some((false, 1)),
+            combination: LimitCombination::All,
+            ..Default::default()
+        };
+        game.state.lines_cleared = 1;
+        game.state.pieces_since_line_clear = 1;
+        assert_eq!(limits.outcome(&game.state), None);
+        game.state.score = 1;
+        assert_eq!(limits.outcome(&game.state), Some(true));
+        // Negative limits take precedence.
+        game.state.garbage_lines_cleared = 1;
+        assert_eq!(limits.outcome(&game.state), Some(false));
+    }
+
+    fn big_player() -> Player {
+        let mode = GameMode {
+            big: true,
+            ..GameMode::marathon()
+        };
+        let mut player = Player::new(mode, GameConfig::default());
+        player.await_piece();
+        player
+    }
+
+    fn active_piece(game: &Game) -> ActivePiece {
+        game.state.active_piece_data.unwrap().0
+    }
+
+    #[test]
+    fn big_pieces_move_and_rotate_within_the_halved_board() {
+        let mut player = big_player();
+        player.game.state.active_piece_data.as_mut().unwrap().0 =
+            piece(Tetromino::T, Orientation::N, (1, 10));
+        player.press(Button::RotateRight);
+        assert_eq!(active_piece(&player.game).orientation, Orientation::E);
+        for _ in 0..Game::WIDTH {
+            player.press(Button::MoveRight);
+        }
+        player.press(Button::RotateRight);
+        let piece = active_piece(&player.game);
+        let right_edge = piece.tiles().iter().map(|((x, _), _)| *x).max().unwrap();
+        assert_eq!(right_edge, Game::WIDTH / 2 - 1);
+        assert!(piece
+            .board_tiles(true)
+            .iter()
+            .all(|((x, _), _)| *x < Game::WIDTH));
+    }
+
+    #[test]
+    fn big_pieces_collide_with_any_cell_they_would_cover() {
+        let mut player = big_player();
+        // A single cell of the board blocks the 2×2 cells it is part of.
+        player.game.state.board[1][1] = Some(Game::GARBAGE_TILE);
+        player.lock(piece(Tetromino::O, Orientation::N, (0, 10)), false);
+        let board = &player.game.state.board;
+        for line in &board[2..6] {
+            assert!(line[..4].iter().all(|tile| tile.is_some()));
+            assert!(line[4..].iter().all(|tile| tile.is_none()));
+        }
+        assert_eq!(board[1].iter().filter(|tile| tile.is_some()).count(), 1);
+        assert!(board[0].iter().all(|tile| tile.is_none()));
+    }
+
+    #[test]
+    fn big_pieces_clear_lines_in_pairs() {
+        let mut player = big_player();
+        fill_rows(&mut player.game, 2, 8);
+        for line in &mut player.game.state.board[..2] {
+            line[9] = None;
+        }
+        player.lock(i_in_well(4), false);
+        assert_eq!(player.game.state.lines_cleared, 1);
+        // The rest of the upright I piece remains, two cells wide.
+        let board = &player.game.state.board;
+        for (y, line) in board.iter().enumerate() {
+            let expected = if y < 6 { 2 } else { 0 };
+            assert_eq!(line.iter().filter(|tile| tile.is_some()).count(), expected);
+        }
+        assert!(board[..6]
+            .iter()
+            .all(|line| line[8..].iter().all(|tile| tile.is_some())));
+    }
+
+    #[test]
+    fn big_pieces_only_clear_full_pairs_of_lines() {
+        let mut player = big_player();
+        fill_rows(&mut player.game, 2, 8);
+        for line in &mut player.game.state.board[..2] {
+            line[9] = None;
+        }
+        player.game.state.board[1][0] = None;
+        player.lock(i_in_well(4), false);
+        assert_eq!(player.game.state.lines_cleared, 0);
+        // The full bottom row stays, so the stack keeps to the doubled grid.
+        let board = &player.game.state.board;
+        assert!(board[0].iter().all(|tile| tile.is_some()));
+        assert_eq!(board[1].iter().filter(|tile| tile.is_some()).count(), 9);
+        for line in &board[2..8] {
+            assert!(line[8..].iter().all(|tile| tile.is_some()));
+        }
+        assert!(board[8].iter().all(|tile| tile.is_none()));
+    }
+
+    /// Clears the bottom row not part of the zone, with an I piece dropped into the first column.
+    fn clear_line_above_zone(player: &mut Player) {
+        let zone_rows = player.game.state.zone_lines as usize;
+        for line in &mut player.game.state.board[zone_rows..] {
+            *line = Default::default();
+        }
+        player.game.state.board[zone_rows] = [Some(Game::GARBAGE_TILE); Game::WIDTH];
+        player.game.state.board[zone_rows][0] = None;
+        player.lock(i_in_well(0), false);
+    }
+
     #[test]
-    fn it_works() {
-        let res = add((1,2),(3,4));
-        assert_eq!(res, (4,6));
+    fn zones_suspend_gravity_and_clear_their_lines_at_the_end() {
+        let mode = GameMode {
+            zone: true,
+            ..GameMode::marathon()
+        };
+        let mut player = Player::new(mode, GameConfig::default());
+        player.await_piece();
+        // Line clears fill the meter, which must be a quarter full to be activated.
+        player.game.state.zone_meter = Game::ZONE_METER_MAX / 4 - 2;
+        clear_line_above_zone(&mut player);
+        assert_eq!(player.game.state.zone_meter, Game::ZONE_METER_MAX / 4 - 1);
+        player.press(Button::ActivateZone);
+        assert!(player.game.state.zone_end.is_none());
+        clear_line_above_zone(&mut player);
+        assert_eq!(player.game.state.zone_meter, Game::ZONE_METER_MAX / 4);
+        // Activating the zone drains the meter.
+        let activation_time = player.time + Duration::from_millis(1);
+        player.press(Button::ActivateZone);
+        let zone_end = player.game.state.zone_end.unwrap();
+        assert_eq!(zone_end, activation_time + Game::ZONE_DURATION_MAX / 4);
+        assert_eq!(player.game.state.zone_meter, 0);
+        assert!(player.game.state.gravity_suspended);
+        // Pieces neither fall nor lock on their own.
+        let piece = active_piece(&player.game);
+        player.wait(Duration::from_secs(2));
+        assert_eq!(active_piece(&player.game), piece);
+        // Cleared lines sink to the bottom instead, without counting yet or filling the meter.
+        let (lines_before, score_before) =
+            (player.game.state.lines_cleared, player.game.state.score);
+        clear_line_above_zone(&mut player);
+        clear_line_above_zone(&mut player);
+        let state = &player.game.state;
+        assert_eq!(state.zone_lines, 2);
+        assert_eq!(state.lines_cleared, lines_before);
+        assert_eq!(state.zone_meter, 0);
+        assert!(state.board[..2]
+            .iter()
+            .all(|line| line.iter().all(|tile| tile.is_some())));
+        // They are cleared at once when the zone ends.
+        player.wait(zone_end - player.time);
+        let state = &player.game.state;
+        assert!(state.zone_end.is_none());
+        assert!(!state.gravity_suspended);
+        assert_eq!(state.zone_lines, 0);
+        assert_eq!(state.lines_cleared, lines_before + 2);
+        assert!(!state.board[0].iter().all(|tile| tile.is_some()));
+        let level = NonZeroU32::MIN;
+        assert_eq!(
+            state.score - score_before,
+            u64::from(Scoring::Standard.score_bonus(clear(2, 1), level))
+        );
+    }
+
+    fn classic_player(mode: GameMode) -> Player {
+        let mut player = Player::new(mode, Ruleset::Classic.game_config());
+        player.await_piece();
+        player
+    }
+
+    /// Puts a piece in play where it lands and waits for it to lock, returning the lock time.
+    fn lock_in_place(player: &mut Player, piece: ActivePiece) -> GameTime {
+        let piece = piece.well_piece(&player.game.state.board);
+        player.game.state.active_piece_data.as_mut().unwrap().0 = piece;
+        while player.game.state.active_piece_data.is_some() {
+            player.wait(Duration::from_millis(1));
+        }
+        player.time
+    }
+
+    #[test]
+    fn classic_gravity_follows_the_nes_frame_table() {
+        // Without a level limit ending the game.
+        let mut player = classic_player(GameMode::zen());
+        for (level, frames) in [(1, 43), (8, 8), (9, 6), (12, 5), (18, 3), (19, 2), (28, 2)] {
+            player.game.state.level = NonZeroU32::try_from(level).unwrap();
+            assert_eq!(player.game.drop_delay(None), Game::NES_FRAME * frames);
+        }
+        // The kill screen: one row per frame from level 29 on.
+        for level in [29, 30, 99] {
+            player.game.state.level = NonZeroU32::try_from(level).unwrap();
+            assert_eq!(player.game.drop_delay(None), Game::NES_FRAME);
+        }
+        // Once the fall scheduled at level 1 has happened, the piece falls every frame.
+        let piece = active_piece(&player.game);
+        while active_piece(&player.game) == piece {
+            player.wait(Duration::from_millis(1));
+        }
+        let piece = active_piece(&player.game);
+        player.wait(Game::NES_FRAME * 10);
+        let fallen = piece.position.1 - active_piece(&player.game).position.1;
+        assert_eq!(fallen, 10);
+    }
+
+    #[test]
+    fn classic_scoring_multiplies_the_line_award_by_the_level_plus_one() {
+        let level = NonZeroU32::try_from(5).unwrap();
+        for (lines, points) in [(1, 40), (2, 100), (3, 300), (4, 1200)] {
+            let clear = ScoredClear {
+                // Neither combos nor back-to-backs are rewarded.
+                back_to_back: 2,
+                ..clear(lines, 3)
+            };
+            assert_eq!(Scoring::Classic.score_bonus(clear, level), points * 6);
+        }
+        let mut player = classic_player(GameMode::marathon());
+        // Hard drops are disabled, so the piece locks where it rests.
+        fill_rows(&mut player.game, 4, 0);
+        player.press(Button::DropHard);
+        assert!(player.game.state.active_piece_data.is_some());
+        lock_in_place(&mut player, i_in_well(0));
+        player.await_piece();
+        assert_eq!(player.game.state.lines_cleared, 4);
+        assert_eq!(player.game.state.score, 1200 * 2);
+    }
+
+    #[test]
+    fn classic_entry_delay_grows_with_the_lock_height() {
+        let mut player = classic_player(GameMode::marathon());
+        let config = player.game.config.clone();
+        let assert_entry_delay = |player: &mut Player, height: usize, delay: Duration| {
+            for line in &mut player.game.state.board {
+                *line = Default::default();
+            }
+            for line in &mut player.game.state.board[..height] {
+                line[0] = Some(Game::GARBAGE_TILE);
+            }
+            let lock_time = lock_in_place(player, piece(Tetromino::O, Orientation::N, (0, 20)));
+            player.await_piece();
+            let entry_delay = player.time - lock_time;
+            assert!(
+                delay <= entry_delay && entry_delay <= delay + Duration::from_millis(1),
+                "{height}: {entry_delay:?}"
+            );
+        };
+        assert_entry_delay(&mut player, 0, config.appearance_delay);
+        assert_entry_delay(
+            &mut player,
+            2,
+            config.appearance_delay + config.appearance_delay_per_height,
+        );
+        assert_entry_delay(
+            &mut player,
+            18,
+            config.appearance_delay + 4 * config.appearance_delay_per_height,
+        );
+        assert_eq!(config.appearance_delay, 10 * Game::NES_FRAME);
+        assert_eq!(config.appearance_delay_per_height, 2 * Game::NES_FRAME);
+    }
+
+    /// Plays a game at 60 frames per second, pressing and releasing buttons in the given frames,
+    /// with every update made the given time into its frame.
+    fn play_frames(
+        inputs: &[(u64, Button, bool)],
+        offset_in_frame: impl Fn(u64) -> Duration,
+    ) -> GameState {
+        let config = GameConfig {
+            tick_rate: NonZeroU32::new(60),
+            ..GameConfig::default()
+        };
+        let mut game = Game::with_seed(GameMode::marathon(), config, 7);
+        let mut buttons_pressed = ButtonsPressed::default();
+        let mut inputs = inputs.iter().peekable();
+        for frame in 0..600 {
+            let time = game.frame_start(frame).unwrap() + offset_in_frame(frame);
+            let mut changed = false;
+            while let Some((_, button, pressed)) = inputs.next_if(|(f, _, _)| *f == frame) {
+                buttons_pressed[*button] = *pressed;
+                changed = true;
+            }
+            game.update(changed.then_some(buttons_pressed), time).ok();
+        }
+        game.state
     }
-}*/
+
+    #[test]
+    fn games_in_frames_depend_only_on_the_frames_of_inputs() {
+        let mut inputs = Vec::new();
+        for (frame, button, frames_held) in [
+            (10, Button::MoveLeft, 2),
+            (20, Button::RotateRight, 1),
+            (30, Button::DropHard, 1),
+            (60, Button::MoveRight, 40),
+            (110, Button::DropSoft, 30),
+            (150, Button::RotateLeft, 1),
+            (151, Button::DropHard, 1),
+            (200, Button::Hold, 1),
+            (230, Button::MoveLeft, 25),
+            (300, Button::DropHard, 1),
+        ] {
+            inputs.push((frame, button, true));
+            inputs.push((frame + frames_held, button, false));
+        }
+        inputs.sort_by_key(|(frame, _, _)| *frame);
+        let frame = Duration::from_secs(1) / 60;
+        let on_frame_start = play_frames(&inputs, |_| Duration::ZERO);
+        let late_in_frame = play_frames(&inputs, |_| frame - Duration::from_micros(1));
+        let jittery = play_frames(&inputs, |n| frame * u32::try_from(n * 7 % 10).unwrap() / 10);
+        assert_eq!(on_frame_start.pieces_played.iter().sum::<u32>(), 3);
+        assert_eq!(on_frame_start, late_in_frame);
+        assert_eq!(on_frame_start, jittery);
+    }
+
+    fn clear(lines: u32, combo: u32) -> ScoredClear {
+        ScoredClear {
+            lines,
+            spin: false,
+            mini: false,
+            perfect_clear: false,
+            combo,
+            back_to_back: 0,
+        }
+    }
+
+    #[test]
+    fn cascades_fall_after_a_clear_and_chain_further_clears() {
+        let mode = GameMode {
+            cascade: true,
+            ..GameMode::marathon()
+        };
+        let mut player = Player::new(mode, GameConfig::default());
+        player.await_piece();
+        fill_rows(&mut player.game, 2, 0);
+        let state = &mut player.game.state;
+        state.board[1][1] = None;
+        // A block of an earlier piece, hanging over the gap in the second row.
+        state.board[2][1] = Some(Game::GARBAGE_TILE);
+        state
+            .board_lock_times
+            .resize(state.board.len(), Default::default());
+        state.board_lock_times[2][1] = Some(Duration::from_millis(1));
+        player.game.state.active_piece_data.as_mut().unwrap().0 = i_in_well(0);
+        player.press(Button::DropHard);
+        let score_before = player.game.state.score;
+        // The first clear takes the bottom row, after which the block falls into the gap.
+        player.wait(player.game.config.line_clear_delay);
+        let state = &player.game.state;
+        assert_eq!(state.lines_cleared, 1);
+        assert!(state.board[0].iter().all(|tile| tile.is_some()));
+        assert!(state.active_piece_data.is_none());
+        // The row it completes is cleared as a chain after another delay.
+        player.await_piece();
+        let state = &player.game.state;
+        assert_eq!(state.lines_cleared, 2);
+        assert_eq!(state.consecutive_line_clears, 2);
+        assert_eq!(state.stack_height(), 2);
+        let level = NonZeroU32::MIN;
+        assert_eq!(
+            state.score - score_before,
+            u64::from(Scoring::Standard.score_bonus(clear(1, 2), level))
+        );
+        let messages = player
+            .feedback
+            .iter()
+            .filter_map(|feedback| match feedback {
+                Feedback::LineClears(lines, _) => Some(format!("{lines:?}")),
+                Feedback::Message(message) => Some(message.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let chain_message = format!("2-chain! +{}", state.score - score_before);
+        assert_eq!(messages, ["[0]", &chain_message, "[0]"]);
+    }
+
+    #[test]
+    fn chain_clears_are_scored_as_combos_by_the_configured_scoring() {
+        let level = NonZeroU32::try_from(3).unwrap();
+        // A single line cleared as the second link of a chain.
+        let chain = clear(1, 2);
+        assert_eq!(Scoring::Standard.score_bonus(chain, level), 40);
+        assert_eq!(Scoring::Classic.score_bonus(chain, level), 160);
+        assert_eq!(Scoring::Guideline.score_bonus(chain, level), 450);
+    }
+
+    #[test]
+    fn zone_clears_are_scored_as_a_single_clear_by_the_configured_scoring() {
+        let level = NonZeroU32::try_from(3).unwrap();
+        let zone = clear(12, 1);
+        assert_eq!(Scoring::Standard.score_bonus(zone, level), 1440);
+        assert_eq!(Scoring::Classic.score_bonus(zone, level), 4800);
+        assert_eq!(Scoring::Guideline.score_bonus(zone, level), 2400);
+    }
+
+    #[test]
+    fn three_corners_with_both_front_corners_make_a_t_spin() {
+        let (piece, last_rotation) = t_down();
+        let board = board_with(&[(3, 0), (5, 0), (3, 2)]);
+        let detection = SpinDetection::TCorners.detect(&piece, &board, last_rotation);
+        assert_eq!(detection, (true, false));
+    }
+
+    #[test]
+    fn three_corners_with_one_front_corner_make_a_mini_t_spin() {
+        let (piece, last_rotation) = t_down();
+        let board = board_with(&[(3, 0), (3, 2), (5, 2)]);
+        let detection = SpinDetection::TCorners.detect(&piece, &board, last_rotation);
+        assert_eq!(detection, (true, true));
+        // The floor counts as occupied: a T pointing up on the bottom row, with one upper corner.
+        let piece = t_piece(Orientation::N, (3, 0));
+        let last_rotation = Some((t_piece(Orientation::W, (3, 0)), piece));
+        let board = board_with(&[(3, 1)]);
+        let detection = SpinDetection::TCorners.detect(&piece, &board, last_rotation);
+        assert_eq!(detection, (true, true));
+    }
+
+    #[test]
+    fn far_kicks_upgrade_a_mini_t_spin() {
+        let (piece, _) = t_down();
+        // Kicked one column over and two rows down, from a center at (3, 3).
+        let piece_before_rotation = t_piece(Orientation::E, (3, 2));
+        let board = board_with(&[(3, 0), (3, 2), (5, 2)]);
+        let detection =
+            SpinDetection::TCorners.detect(&piece, &board, Some((piece_before_rotation, piece)));
+        assert_eq!(detection, (true, false));
+    }
+
+    #[test]
+    fn pieces_not_spun_last_or_with_two_corners_are_no_t_spin() {
+        let (piece, last_rotation) = t_down();
+        let board = board_with(&[(3, 0), (5, 0), (3, 2)]);
+        let detection = SpinDetection::TCorners.detect(&piece, &board, None);
+        assert_eq!(detection, (false, false));
+        let board = board_with(&[(3, 0), (5, 0)]);
+        let detection = SpinDetection::TCorners.detect(&piece, &board, last_rotation);
+        assert_eq!(detection, (false, false));
+        let l_piece = ActivePiece {
+            shape: Tetromino::L,
+            ..piece
+        };
+        let board = board_with(&[(3, 0), (5, 0), (3, 2), (5, 2)]);
+        let detection = SpinDetection::TCorners.detect(&l_piece, &board, Some((l_piece, l_piece)));
+        assert_eq!(detection, (false, false));
+    }
+}
@@ -8,7 +8,6 @@ use rand::{
     self,
     distributions::{Distribution, WeightedIndex},
     //prelude::SliceRandom, // vec.shuffle(rng)...
-    rngs::ThreadRng,
     Rng,
 };
 
@@ -61,6 +60,14 @@ pub enum TetrominoSource {
         /// at least one is `0`.
         relative_counts: [u32; 7],
     },
+    /// The generator used in NES Tetris.
+    ///
+    /// It picks a piece uniformly at random, but rerolls once (uniformly, and without further
+    /// checks) if the piece is the same as the one generated last.
+    Classic {
+        /// The most recently generated piece, if any.
+        last_generated: Option<Tetromino>,
+    },
     /// Debug generator which repeats a certain pattern of [`Tetromino`]s forever.
     Cycle {
         /// The sequence of pieces that is repeated.
@@ -118,18 +125,61 @@ impl TetrominoSource {
         }
     }
 
+    /// Initialize an instance of the [`TetrominoSource::Classic`] variant.
+    pub const fn classic() -> Self {
+        Self::Classic {
+            last_generated: None,
+        }
+    }
+
     /// Initialize a custom instance of the [`TetrominoSource::Cycle`] variant.
     pub const fn cycle(pattern: Vec<Tetromino>) -> Self {
         Self::Cycle { pattern, index: 0 }
     }
 
     /// Method that allows `TetrominoSource` to be used as [`Iterator`].
-    pub fn with_rng<'a, 'b>(&'a mut self, rng: &'b mut ThreadRng) -> TetrominoIterator<'a, 'b> {
+    pub fn with_rng<'a, 'b, R: Rng>(&'a mut self, rng: &'b mut R) -> TetrominoIterator<'a, 'b, R> {
         TetrominoIterator {
             tetromino_generator: self,
             rng,
         }
     }
+
+    /// Copy the generator *including* its internal state.
+    ///
+    /// Unlike [`Clone::clone`], which yields a freshly initialized generator of the same kind, this
+    /// continues to generate pieces exactly like the original would (given the same randomness).
+    pub fn clone_with_state(&self) -> Self {
+        match self {
+            Self::Uniform => Self::Uniform,
+            Self::Stock {
+                pieces_left,
+                multiplicity,
+                restock_threshold,
+            } => Self::Stock {
+                pieces_left: *pieces_left,
+                multiplicity: *multiplicity,
+                restock_threshold: *restock_threshold,
+            },
+            Self::Recency {
+                last_generated,
+                snap,
+            } => Self::Recency {
+                last_generated: *last_generated,
+                snap: *snap,
+            },
+            Self::BalanceRelative { relative_counts } => Self::BalanceRelative {
+                relative_counts: *relative_counts,
+            },
+            Self::Classic { last_generated } => Self::Classic {
+                last_generated: *last_generated,
+            },
+            Self::Cycle { pattern, index } => Self::Cycle {
+                pattern: pattern.clone(),
+                index: *index,
+            },
+        }
+    }
 }
 
 impl Clone for TetrominoSource {
@@ -146,20 +196,21 @@ impl Clone for TetrominoSource {
                 snap,
             } => Self::recency_with(*snap),
             Self::BalanceRelative { relative_counts: _ } => Self::balance_relative(),
+            Self::Classic { last_generated: _ } => Self::classic(),
             Self::Cycle { pattern, index: _ } => Self::cycle(pattern.clone()),
         }
     }
 }
 
 /// Struct produced from [`TetrominoSource::with_rng`] which implements [`Iterator`].
-pub struct TetrominoIterator<'a, 'b> {
+pub struct TetrominoIterator<'a, 'b, R: Rng> {
     /// Selected tetromino generator to use as information source.
     pub tetromino_generator: &'a mut TetrominoSource,
-    /// Thread random number generator for raw soure of randomness.
-    pub rng: &'b mut ThreadRng,
+    /// Random number generator for raw soure of randomness.
+    pub rng: &'b mut R,
 }
 
-impl<'a, 'b> Iterator for TetrominoIterator<'a, 'b> {
+impl<'a, 'b, R: Rng> Iterator for TetrominoIterator<'a, 'b, R> {
     type Item = Tetromino;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -216,6 +267,16 @@ impl<'a, 'b> Iterator for TetrominoIterator<'a, 'b> {
                 // SAFETY: 0 <= idx <= 6.
                 Some(Tetromino::SHAPES[idx])
             }
+            TetrominoSource::Classic { last_generated } => {
+                // Roll an eighth, invalid piece type to also reroll on.
+                let mut idx = self.rng.gen_range(0..=7);
+                if idx == 7 || last_generated.is_some_and(|last| Tetromino::SHAPES[idx] == last) {
+                    idx = self.rng.gen_range(0..=6);
+                }
+                let tetromino = Tetromino::SHAPES[idx];
+                *last_generated = Some(tetromino);
+                Some(tetromino)
+            }
             TetrominoSource::Cycle { pattern, index } => {
                 let tetromino = pattern[*index];
                 *index += 1;
@@ -0,0 +1,244 @@
+/*!
+This module allows correcting a [`Game`] after the fact, when some of its inputs only become known
+late (as is usual for the opponent's inputs in online play).
+*/
+
+use std::collections::VecDeque;
+
+use crate::{ButtonsPressed, FeedbackEvents, Game, GameSnapshot, GameTime, GameUpdateError};
+
+/// Keeps saved states of the last few updates of a [`Game`], so inputs arriving late can be
+/// re-applied at the time they actually happened.
+///
+/// For this to work, the game must only be updated through [`Rollback::update`] and
+/// [`Rollback::insert_input`].
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use tetrs_engine::{rollback::Rollback, *};
+///
+/// let mut left = ButtonsPressed::default();
+/// left[Button::MoveLeft] = true;
+/// let released = ButtonsPressed::default();
+///
+/// // A game receiving its inputs in time.
+/// let mut game = Game::with_seed(GameMode::marathon(), GameConfig::default(), 7);
+/// for (buttons, millis) in [(None, 200), (Some(left), 400), (Some(released), 450), (None, 600)] {
+///     game.update(buttons, Duration::from_millis(millis)).ok();
+/// }
+///
+/// // The same game, where the inputs only arrived after the game advanced to 600ms.
+/// let mut late_game = Game::with_seed(GameMode::marathon(), GameConfig::default(), 7);
+/// let mut rollback = Rollback::new(16);
+/// rollback.update(&mut late_game, None, Duration::from_millis(200)).ok();
+/// rollback.update(&mut late_game, None, Duration::from_millis(600)).ok();
+/// rollback.insert_input(&mut late_game, left, Duration::from_millis(400));
+/// rollback.insert_input(&mut late_game, released, Duration::from_millis(450));
+///
+/// assert_eq!(late_game.state(), game.state());
+/// ```
+#[derive(Debug)]
+pub struct Rollback {
+    capacity: usize,
+    /// The most recent updates, each with the state saved right before it, oldest first.
+    frames: VecDeque<(GameSnapshot, Option<ButtonsPressed>, GameTime)>,
+}
+
+impl Rollback {
+    /// Create a new helper remembering at most `capacity` updates.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            frames: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Updates the game like [`Game::update`], remembering the update in case it has to be redone.
+    pub fn update(
+        &mut self,
+        game: &mut Game,
+        new_button_state: Option<ButtonsPressed>,
+        update_time: GameTime,
+    ) -> Result<FeedbackEvents, GameUpdateError> {
+        let snapshot = game.save_state();
+        let feedback_events = game.update(new_button_state, update_time)?;
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames
+            .push_back((snapshot, new_button_state, update_time));
+        Ok(feedback_events)
+    }
+
+    /// Applies a change of buttons that happened at some (possibly past) time.
+    ///
+    /// If the input lies in the past the game is rolled back to before it, and all updates since are
+    /// redone with the input inserted. The returned feedback events then contain all feedback of the
+    /// re-simulated time span.
+    ///
+    /// Returns `None` if the input lies too far in the past to be inserted.
+    pub fn insert_input(
+        &mut self,
+        game: &mut Game,
+        button_state: ButtonsPressed,
+        time: GameTime,
+    ) -> Option<FeedbackEvents> {
+        // Find the first update that would have happened after the input.
+        let index = self
+            .frames
+            .iter()
+            .position(|(_, _, update_time)| time < *update_time);
+        let Some(index) = index else {
+            // Not late at all.
+            return Some(
+                self.update(game, Some(button_state), time)
+                    .unwrap_or_default(),
+            );
+        };
+        let (snapshot, ..) = &self.frames[index];
+        if time < snapshot.state().time {
+            return None;
+        }
+        game.load_state(snapshot);
+        let redo = self.frames.split_off(index);
+        let mut feedback_events = Vec::new();
+        let updates = std::iter::once((Some(button_state), time))
+            .chain(redo.into_iter().map(|(_, buttons, time)| (buttons, time)));
+        for (buttons, time) in updates {
+            match self.update(game, buttons, time) {
+                Ok(new_feedback_events) => feedback_events.extend(new_feedback_events),
+                // The game ended earlier than before.
+                Err(_) => break,
+            }
+        }
+        Some(feedback_events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{Button, GameConfig, GameMode};
+
+    const TICK: Duration = Duration::from_millis(16);
+    const END: Duration = Duration::from_secs(20);
+
+    /// A made-up log of button changes, as a player might produce them.
+    fn input_log(seed: u64) -> Vec<(GameTime, ButtonsPressed)> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let buttons = [
+            Button::MoveLeft,
+            Button::MoveRight,
+            Button::RotateLeft,
+            Button::RotateRight,
+            Button::DropSoft,
+            Button::DropHard,
+            Button::Hold,
+        ];
+        let mut buttons_pressed = ButtonsPressed::default();
+        let mut time = Duration::ZERO;
+        let mut inputs = Vec::new();
+        loop {
+            time += Duration::from_millis(rng.gen_range(20..200));
+            if time >= END {
+                break inputs;
+            }
+            let button = buttons[rng.gen_range(0..buttons.len())];
+            buttons_pressed[button] = !buttons_pressed[button];
+            inputs.push((time, buttons_pressed));
+        }
+    }
+
+    fn ticks() -> impl Iterator<Item = GameTime> {
+        (1..).map(|i| TICK * i).take_while(|time| *time <= END)
+    }
+
+    /// Plays a game by updating it every tick and at every input, as if all inputs arrived in time.
+    fn straight_run(seed: u64, inputs: &[(GameTime, ButtonsPressed)]) -> Game {
+        let mut game = Game::with_seed(GameMode::marathon(), GameConfig::default(), seed);
+        let mut inputs = inputs.iter().peekable();
+        for tick in ticks() {
+            game.update(None, tick).ok();
+            while let Some((time, buttons_pressed)) =
+                inputs.next_if(|(time, _)| *time < tick + TICK)
+            {
+                game.update(Some(*buttons_pressed), *time).ok();
+            }
+        }
+        game
+    }
+
+    #[test]
+    fn same_seed_and_inputs_give_same_state() {
+        let inputs = input_log(1);
+        let game = straight_run(42, &inputs);
+        assert!(game.state().pieces_played.iter().sum::<u32>() > 10);
+        assert_eq!(straight_run(42, &inputs).state(), game.state());
+        assert_ne!(straight_run(43, &inputs).state(), game.state());
+    }
+
+    #[test]
+    fn late_inputs_are_resimulated_like_a_straight_run() {
+        let inputs = input_log(2);
+        let latency = Duration::from_millis(100);
+        let mut game = Game::with_seed(GameMode::marathon(), GameConfig::default(), 42);
+        let mut rollback = Rollback::new(32);
+        let mut late_inputs = inputs.iter().peekable();
+        for tick in ticks() {
+            rollback.update(&mut game, None, tick).ok();
+            while let Some((time, buttons_pressed)) =
+                late_inputs.next_if(|(time, _)| *time + latency <= tick)
+            {
+                assert!(rollback
+                    .insert_input(&mut game, *buttons_pressed, *time)
+                    .is_some());
+            }
+        }
+        // Inputs only arriving after the last tick.
+        for (time, buttons_pressed) in late_inputs {
+            rollback.insert_input(&mut game, *buttons_pressed, *time);
+        }
+        assert!(game.state().pieces_played.iter().sum::<u32>() > 10);
+        assert_eq!(game.state(), straight_run(42, &inputs).state());
+    }
+
+    #[test]
+    fn inputs_too_far_in_the_past_are_refused() {
+        let mut game = Game::with_seed(GameMode::marathon(), GameConfig::default(), 42);
+        let mut rollback = Rollback::new(4);
+        for tick in ticks().take(10) {
+            rollback.update(&mut game, None, tick).ok();
+        }
+        let state = game.state().clone();
+        let mut left = ButtonsPressed::default();
+        left[Button::MoveLeft] = true;
+        assert!(rollback.insert_input(&mut game, left, TICK).is_none());
+        assert_eq!(game.state(), &state);
+    }
+
+    #[test]
+    fn loaded_state_replays_like_a_straight_run() {
+        let inputs = input_log(3);
+        let (before, after) = inputs.split_at(inputs.len() / 2);
+        let mut game = straight_run(42, before);
+        let snapshot = game.save_state();
+        let replay = |game: &mut Game| {
+            for (time, buttons_pressed) in after {
+                game.update(Some(*buttons_pressed), *time).ok();
+            }
+            game.update(None, END).ok();
+        };
+        replay(&mut game);
+        let first_run = game.state().clone();
+        game.load_state(&snapshot);
+        assert_eq!(game.state(), snapshot.state());
+        replay(&mut game);
+        assert_eq!(game.state(), &first_run);
+    }
+}
@@ -0,0 +1,203 @@
+//! A tiny bot talking the Tetris Bot Protocol over stdin/stdout, see
+//! <https://github.com/tetris-bot-protocol/tbp-spec>.
+//!
+//! It only considers placements reachable by dropping pieces straight down and rates them with a
+//! simple board heuristic. It mostly serves as a stand-in for real bots when trying out
+//! `./tetrs_tui --bot`.
+
+use std::io::{self, BufRead, Write};
+
+use serde_json::{json, Value};
+
+const WIDTH: usize = 10;
+const HEIGHT: usize = 40;
+const ORIENTATIONS: [&str; 4] = ["north", "east", "south", "west"];
+
+type Board = Vec<[bool; WIDTH]>;
+
+#[derive(Default)]
+struct Bot {
+    board: Board,
+    hold: Option<char>,
+    queue: Vec<char>,
+}
+
+fn main() -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    let mut respond = |message: Value| -> io::Result<()> {
+        writeln!(stdout, "{message}")?;
+        stdout.flush()
+    };
+    respond(json!({
+        "type": "info",
+        "name": "tetrs reference bot",
+        "version": env!("CARGO_PKG_VERSION"),
+        "author": "tetrs",
+        "features": [],
+    }))?;
+    let mut bot = Bot::default();
+    for line in io::stdin().lock().lines() {
+        let message: Value = serde_json::from_str(&line?)?;
+        match message["type"].as_str() {
+            Some("rules") => respond(json!({ "type": "ready" }))?,
+            Some("start") => {
+                bot.board = message["board"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|row| std::array::from_fn(|x| !row[x].is_null()))
+                    .collect();
+                bot.board.resize(HEIGHT, [false; WIDTH]);
+                bot.hold = message["hold"]
+                    .as_str()
+                    .and_then(|piece| piece.chars().next());
+                bot.queue = pieces(&message["queue"]);
+            }
+            Some("suggest") => respond(json!({
+                "type": "suggestion",
+                "moves": bot.suggest(),
+            }))?,
+            Some("play") => bot.play(&message["move"]["location"]),
+            Some("new_piece") => bot.queue.extend(pieces(&json!([message["piece"]]))),
+            Some("quit") => break,
+            // "stop" and unknown messages need no reaction.
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn pieces(value: &Value) -> Vec<char> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|piece| piece.as_str()?.chars().next())
+        .collect()
+}
+
+impl Bot {
+    /// All placements of the current (or hold) piece, best first.
+    fn suggest(&self) -> Vec<Value> {
+        let mut candidates = Vec::new();
+        let alternative = self.hold.or(self.queue.get(1).copied());
+        for piece in self.queue.first().into_iter().chain(alternative.as_ref()) {
+            for orientation in ORIENTATIONS {
+                for x in 0..WIDTH as isize {
+                    if let Some((y, board)) = drop(&self.board, *piece, orientation, x) {
+                        let location = json!({
+                            "type": piece.to_string(),
+                            "orientation": orientation,
+                            "x": x,
+                            "y": y,
+                        });
+                        candidates.push((evaluate(&board), location));
+                    }
+                }
+            }
+        }
+        candidates.sort_by(|(score0, _), (score1, _)| score1.total_cmp(score0));
+        candidates
+            .into_iter()
+            .map(|(_, location)| json!({ "location": location, "spin": "none" }))
+            .collect()
+    }
+
+    fn play(&mut self, location: &Value) {
+        let piece = location["type"]
+            .as_str()
+            .and_then(|piece| piece.chars().next());
+        if self.queue.is_empty() || piece.is_none() {
+            return;
+        }
+        if piece != self.queue.first().copied() {
+            let held = self.queue.remove(0);
+            if self.hold.replace(held).is_none() && !self.queue.is_empty() {
+                self.queue.remove(0);
+            }
+        } else {
+            self.queue.remove(0);
+        }
+        let (Some(piece), Some(orientation), Some(x), Some(y)) = (
+            piece,
+            location["orientation"].as_str(),
+            location["x"].as_i64(),
+            location["y"].as_i64(),
+        ) else {
+            return;
+        };
+        if let Some(cells) = cells(&self.board, piece, orientation, x as isize, y as isize) {
+            self.board = lock(&self.board, cells);
+        }
+    }
+}
+
+/// The cells a piece covers if they are free, with `(x, y)` being the piece's rotation center.
+fn cells(
+    board: &Board,
+    piece: char,
+    orientation: &str,
+    x: isize,
+    y: isize,
+) -> Option<[(usize, usize); 4]> {
+    let minos = match piece {
+        'O' => [(0, 0), (1, 0), (0, 1), (1, 1)],
+        'I' => [(-1, 0), (0, 0), (1, 0), (2, 0)],
+        'S' => [(-1, 0), (0, 0), (0, 1), (1, 1)],
+        'Z' => [(-1, 1), (0, 1), (0, 0), (1, 0)],
+        'T' => [(-1, 0), (0, 0), (1, 0), (0, 1)],
+        'L' => [(-1, 0), (0, 0), (1, 0), (1, 1)],
+        'J' => [(-1, 0), (0, 0), (1, 0), (-1, 1)],
+        _ => return None,
+    };
+    let mut cells = [(0, 0); 4];
+    for (cell, (dx, dy)) in cells.iter_mut().zip(minos) {
+        let (dx, dy) = match orientation {
+            "east" => (dy, -dx),
+            "south" => (-dx, -dy),
+            "west" => (-dy, dx),
+            _ => (dx, dy),
+        };
+        let (x, y) = (usize::try_from(x + dx).ok()?, usize::try_from(y + dy).ok()?);
+        if x >= WIDTH || y >= HEIGHT || board[y][x] {
+            return None;
+        }
+        *cell = (x, y);
+    }
+    Some(cells)
+}
+
+/// Drops a piece straight down from the top, returning where it lands and the resulting board.
+fn drop(board: &Board, piece: char, orientation: &str, x: isize) -> Option<(isize, Board)> {
+    let mut y = HEIGHT as isize - 2;
+    let mut landed = cells(board, piece, orientation, x, y)?;
+    while let Some(below) = cells(board, piece, orientation, x, y - 1) {
+        landed = below;
+        y -= 1;
+    }
+    Some((y, lock(board, landed)))
+}
+
+fn lock(board: &Board, cells: [(usize, usize); 4]) -> Board {
+    let mut board = board.clone();
+    for (x, y) in cells {
+        board[y][x] = true;
+    }
+    board.retain(|row| row.contains(&false));
+    board.resize(HEIGHT, [false; WIDTH]);
+    board
+}
+
+fn evaluate(board: &Board) -> f64 {
+    let heights: [usize; WIDTH] =
+        std::array::from_fn(|x| board.iter().rposition(|row| row[x]).map_or(0, |y| y + 1));
+    let holes = (0..WIDTH)
+        .map(|x| board[..heights[x]].iter().filter(|row| !row[x]).count())
+        .sum::<usize>();
+    let bumpiness = heights
+        .windows(2)
+        .map(|pair| pair[0].abs_diff(pair[1]))
+        .sum::<usize>();
+    let aggregate_height = heights.iter().sum::<usize>();
+    -0.51 * aggregate_height as f64 - 0.36 * holes as f64 - 0.18 * bumpiness as f64
+}
@@ -181,7 +181,8 @@ impl ComboBotHandler {
                                     | Button::DropSoft
                                     | Button::DropHard
                                     | Button::DropSonic
-                                    | Button::Hold => button,
+                                    | Button::Hold
+                                    | Button::ActivateZone => button,
                                 };
                             }
                             let _ = button_sender.send(Ok((Instant::now(), button, true)));
@@ -713,7 +714,7 @@ mod tests {
         }
     }
 
-    fn run_bot(lookahead: usize, iter: &mut TetrominoIterator) -> usize {
+    fn run_bot(lookahead: usize, iter: &mut TetrominoIterator<rand::rngs::ThreadRng>) -> usize {
         let mut next_pieces: VecDeque<_> = iter.take(lookahead).collect();
         let mut state = ComboState {
             layout: (Pat::_200, false),
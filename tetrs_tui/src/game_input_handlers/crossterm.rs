@@ -29,9 +29,9 @@ impl Drop for CrosstermHandler {
 }
 
 impl CrosstermHandler {
-    pub fn new(
-        button_sender: &Sender<InputOrInterrupt>,
-        keybinds: &HashMap<KeyCode, Button>,
+    pub fn new<B: Copy + Send + 'static>(
+        button_sender: &Sender<InputOrInterrupt<B>>,
+        keybinds: &HashMap<KeyCode, B>,
         kitty_enabled: bool,
     ) -> Self {
         let flag = Arc::new(AtomicBool::new(true));
@@ -56,13 +56,37 @@ impl CrosstermHandler {
             (KeyCode::Up, Button::DropHard),
             //(KeyCode::Char('w'), Button::DropSonic),
             (KeyCode::Char(' '), Button::Hold),
+            (KeyCode::Char('e'), Button::ActivateZone),
         ])
     }
 
-    fn spawn_standard(
+    pub fn default_versus_keybinds() -> [HashMap<KeyCode, Button>; 2] {
+        [
+            HashMap::from([
+                (KeyCode::Char('a'), Button::MoveLeft),
+                (KeyCode::Char('d'), Button::MoveRight),
+                (KeyCode::Char('q'), Button::RotateLeft),
+                (KeyCode::Char('e'), Button::RotateRight),
+                (KeyCode::Char('s'), Button::DropSoft),
+                (KeyCode::Char('w'), Button::DropHard),
+                (KeyCode::Char('f'), Button::Hold),
+            ]),
+            HashMap::from([
+                (KeyCode::Left, Button::MoveLeft),
+                (KeyCode::Right, Button::MoveRight),
+                (KeyCode::Char('k'), Button::RotateLeft),
+                (KeyCode::Char('l'), Button::RotateRight),
+                (KeyCode::Down, Button::DropSoft),
+                (KeyCode::Up, Button::DropHard),
+                (KeyCode::Char('j'), Button::Hold),
+            ]),
+        ]
+    }
+
+    fn spawn_standard<B: Copy + Send + 'static>(
         flag: Arc<AtomicBool>,
-        button_sender: Sender<InputOrInterrupt>,
-        keybinds: HashMap<KeyCode, Button>,
+        button_sender: Sender<InputOrInterrupt<B>>,
+        keybinds: HashMap<KeyCode, B>,
     ) -> JoinHandle<()> {
         thread::spawn(move || {
             'react_to_event: loop {
@@ -120,10 +144,10 @@ impl CrosstermHandler {
         })
     }
 
-    fn spawn_kitty(
+    fn spawn_kitty<B: Copy + Send + 'static>(
         flag: Arc<AtomicBool>,
-        button_sender: Sender<InputOrInterrupt>,
-        keybinds: HashMap<KeyCode, Button>,
+        button_sender: Sender<InputOrInterrupt<B>>,
+        keybinds: HashMap<KeyCode, B>,
     ) -> JoinHandle<()> {
         thread::spawn(move || {
             'react_to_event: loop {
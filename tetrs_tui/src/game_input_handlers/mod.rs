@@ -1,7 +1,11 @@
 pub mod combo_bot;
 pub mod crossterm;
+pub mod socket;
+pub mod tbp_bot;
+pub mod versus_bot;
 
-pub type InputOrInterrupt = Result<(std::time::Instant, tetrs_engine::Button, bool), Interrupt>;
+pub type InputOrInterrupt<B = tetrs_engine::Button> =
+    Result<(std::time::Instant, B, bool), Interrupt>;
 
 pub enum Interrupt {
     WindowResize,
@@ -0,0 +1,499 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+    net::TcpStream,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+use crate::game_input_handlers::{InputOrInterrupt, Interrupt};
+
+// Telnet commands and options, see RFC 854 and RFC 1073.
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const OPT_ECHO: u8 = 1;
+const OPT_SUPPRESS_GO_AHEAD: u8 = 3;
+const OPT_NAWS: u8 = 31;
+
+/// How many bytes of an unfinished sequence are kept before it is given up on.
+const PENDING_MAX: usize = 1024;
+
+/// The keyboard events and window size of a player connected over TCP.
+#[derive(Clone, Debug)]
+pub struct SocketSession {
+    events: Arc<Mutex<Receiver<Event>>>,
+    size: Arc<Mutex<(u16, u16)>>,
+}
+
+impl SocketSession {
+    /// Starts decoding keys sent by a (telnet or raw) TCP client.
+    pub fn new(mut stream: TcpStream, default_size: (u16, u16)) -> io::Result<Self> {
+        // Ask telnet clients to stop echoing and buffering lines, and to tell us their window size.
+        stream.write_all(&[
+            IAC,
+            WILL,
+            OPT_ECHO,
+            IAC,
+            WILL,
+            OPT_SUPPRESS_GO_AHEAD,
+            IAC,
+            DO,
+            OPT_NAWS,
+        ])?;
+        let (event_sender, event_receiver) = mpsc::channel();
+        let size = Arc::new(Mutex::new(default_size));
+        let mut decoder = Decoder::new(event_sender, size.clone());
+        thread::spawn(move || {
+            let mut buf = [0; 1024];
+            loop {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        if decoder.decode(&buf[..n]).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+        Ok(SocketSession {
+            events: Arc::new(Mutex::new(event_receiver)),
+            size,
+        })
+    }
+
+    /// Blocks until the next event, like [`crossterm::event::read`].
+    pub fn read(&self) -> io::Result<Event> {
+        self.events
+            .lock()
+            .unwrap()
+            .recv()
+            .map_err(|_| io::Error::from(io::ErrorKind::ConnectionAborted))
+    }
+
+    /// Waits at most `timeout` for the next event.
+    pub fn read_timeout(&self, timeout: Duration) -> io::Result<Option<Event>> {
+        match self.events.lock().unwrap().recv_timeout(timeout) {
+            Ok(event) => Ok(Some(event)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => {
+                Err(io::Error::from(io::ErrorKind::ConnectionAborted))
+            }
+        }
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        *self.size.lock().unwrap()
+    }
+}
+
+/// Turns the bytes sent by a client into key events.
+struct Decoder {
+    event_sender: Sender<Event>,
+    size: Arc<Mutex<(u16, u16)>>,
+    /// The start of a sequence whose remaining bytes have not been read yet.
+    pending: Vec<u8>,
+    /// Whether the last byte decoded was a carriage return, which a line feed or NUL may follow.
+    after_carriage_return: bool,
+}
+
+impl Decoder {
+    fn new(event_sender: Sender<Event>, size: Arc<Mutex<(u16, u16)>>) -> Self {
+        Decoder {
+            event_sender,
+            size,
+            pending: Vec::new(),
+            after_carriage_return: false,
+        }
+    }
+
+    fn send(&self, event: Event) -> Result<(), mpsc::SendError<Event>> {
+        self.event_sender.send(event)
+    }
+
+    fn key(&self, code: KeyCode, modifiers: KeyModifiers) -> Result<(), mpsc::SendError<Event>> {
+        self.send(Event::Key(KeyEvent::new(code, modifiers)))
+    }
+
+    /// Decodes the bytes of one read, keeping any sequence they end in the middle of for the next.
+    ///
+    /// NOTE: A lone escape byte at the end of a read is taken to be the escape key, as it cannot
+    /// be told apart from the start of an escape sequence split across reads.
+    fn decode(&mut self, bytes: &[u8]) -> Result<(), mpsc::SendError<Event>> {
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.extend_from_slice(bytes);
+        let mut i = 0;
+        while i < pending.len() {
+            let rest = &pending[i..];
+            if std::mem::take(&mut self.after_carriage_return) && matches!(rest[0], b'\n' | b'\0') {
+                i += 1;
+                continue;
+            }
+            let Some(len) = self.decode_sequence(rest)? else {
+                break;
+            };
+            i += len;
+        }
+        pending.drain(..i);
+        if pending.len() > PENDING_MAX {
+            pending.clear();
+        }
+        self.pending = pending;
+        Ok(())
+    }
+
+    /// Decodes the sequence at the start of the bytes, returning its length, or `None` if the
+    /// bytes end before it does.
+    fn decode_sequence(&mut self, rest: &[u8]) -> Result<Option<usize>, mpsc::SendError<Event>> {
+        let len = match rest {
+            // Subnegotiation, which ends with `IAC SE`.
+            [IAC, SB, ..] => {
+                let Some(end) = rest.windows(2).position(|window| window == [IAC, SE]) else {
+                    return Ok(None);
+                };
+                // Data bytes equal to IAC are doubled.
+                let mut data = Vec::new();
+                let mut body = rest[2..end].iter();
+                while let Some(&byte) = body.next() {
+                    if byte == IAC {
+                        body.next();
+                    }
+                    data.push(byte);
+                }
+                // Window size.
+                if let [OPT_NAWS, w0, w1, h0, h1] = data[..] {
+                    let size = (u16::from_be_bytes([w0, w1]), u16::from_be_bytes([h0, h1]));
+                    *self.size.lock().unwrap() = size;
+                    self.send(Event::Resize(size.0, size.1))?;
+                }
+                end + 2
+            }
+            [IAC, WILL | WONT | DO | DONT, _, ..] => 3,
+            [IAC] | [IAC, WILL | WONT | DO | DONT] => return Ok(None),
+            [IAC, IAC, ..] => 2,
+            [IAC, ..] => 2,
+            // Escape sequences.
+            [0x1b, b'[' | b'O', b'A', ..] => {
+                self.key(KeyCode::Up, KeyModifiers::NONE).map(|_| 3)?
+            }
+            [0x1b, b'[' | b'O', b'B', ..] => {
+                self.key(KeyCode::Down, KeyModifiers::NONE).map(|_| 3)?
+            }
+            [0x1b, b'[' | b'O', b'C', ..] => {
+                self.key(KeyCode::Right, KeyModifiers::NONE).map(|_| 3)?
+            }
+            [0x1b, b'[' | b'O', b'D', ..] => {
+                self.key(KeyCode::Left, KeyModifiers::NONE).map(|_| 3)?
+            }
+            [0x1b, b'[', b'H', ..] => self.key(KeyCode::Home, KeyModifiers::NONE).map(|_| 3)?,
+            [0x1b, b'[', b'F', ..] => self.key(KeyCode::End, KeyModifiers::NONE).map(|_| 3)?,
+            [0x1b, b'[', digit @ b'1'..=b'8', b'~', ..] => {
+                let code = match digit {
+                    b'1' | b'7' => KeyCode::Home,
+                    b'2' => KeyCode::Insert,
+                    b'3' => KeyCode::Delete,
+                    b'4' | b'8' => KeyCode::End,
+                    b'5' => KeyCode::PageUp,
+                    _ => KeyCode::PageDown,
+                };
+                self.key(code, KeyModifiers::NONE)?;
+                4
+            }
+            // Unknown escape sequence: skip it.
+            [0x1b, b'[', ..] => {
+                let Some(end) = rest[2..]
+                    .iter()
+                    .position(|byte| (0x40..=0x7e).contains(byte))
+                else {
+                    return Ok(None);
+                };
+                end + 3
+            }
+            [0x1b, b'O'] => return Ok(None),
+            [0x1b, ..] => {
+                self.key(KeyCode::Esc, KeyModifiers::NONE)?;
+                1
+            }
+            // Control characters.
+            [b'\r', ..] => {
+                self.key(KeyCode::Enter, KeyModifiers::NONE)?;
+                self.after_carriage_return = true;
+                1
+            }
+            [b'\n', ..] => {
+                self.key(KeyCode::Enter, KeyModifiers::NONE)?;
+                1
+            }
+            [b'\t', ..] => {
+                self.key(KeyCode::Tab, KeyModifiers::NONE)?;
+                1
+            }
+            [0x08 | 0x7f, ..] => {
+                self.key(KeyCode::Backspace, KeyModifiers::NONE)?;
+                1
+            }
+            [ctrl @ 0x01..=0x1a, ..] => {
+                self.key(
+                    KeyCode::Char(char::from(b'a' + ctrl - 1)),
+                    KeyModifiers::CONTROL,
+                )?;
+                1
+            }
+            // Printable characters.
+            _ => {
+                let len = match rest[0] {
+                    0xf0.. => 4,
+                    0xe0.. => 3,
+                    0xc0.. => 2,
+                    _ => 1,
+                };
+                if rest.len() < len {
+                    return Ok(None);
+                }
+                if let Some(ch) = std::str::from_utf8(&rest[..len])
+                    .ok()
+                    .and_then(|str| str.chars().next())
+                {
+                    self.key(KeyCode::Char(ch), KeyModifiers::NONE)?;
+                }
+                len
+            }
+        };
+        Ok(Some(len))
+    }
+}
+
+/// Sends button presses read from a [`SocketSession`].
+///
+/// Like [`crate::game_input_handlers::crossterm::CrosstermHandler`] without keyboard
+/// enhancements, a key press is immediately followed by its release as no release events are known.
+#[derive(Debug)]
+pub struct SocketHandler {
+    handles: Option<(Arc<AtomicBool>, JoinHandle<()>)>,
+}
+
+impl Drop for SocketHandler {
+    fn drop(&mut self) {
+        if let Some((flag, _)) = self.handles.take() {
+            flag.store(false, Ordering::Release);
+        }
+    }
+}
+
+impl SocketHandler {
+    pub fn new<B: Copy + Send + 'static>(
+        button_sender: &Sender<InputOrInterrupt<B>>,
+        keybinds: &HashMap<KeyCode, B>,
+        session: &SocketSession,
+    ) -> Self {
+        let flag = Arc::new(AtomicBool::new(true));
+        let join_handle = Self::spawn(
+            flag.clone(),
+            button_sender.clone(),
+            keybinds.clone(),
+            session.clone(),
+        );
+        SocketHandler {
+            handles: Some((flag, join_handle)),
+        }
+    }
+
+    fn spawn<B: Copy + Send + 'static>(
+        flag: Arc<AtomicBool>,
+        button_sender: Sender<InputOrInterrupt<B>>,
+        keybinds: HashMap<KeyCode, B>,
+        session: SocketSession,
+    ) -> JoinHandle<()> {
+        thread::spawn(move || {
+            'react_to_event: loop {
+                // Maybe stop thread.
+                let running = flag.load(Ordering::Acquire);
+                if !running {
+                    break 'react_to_event;
+                };
+                match session.read_timeout(Duration::from_millis(100)) {
+                    Ok(None) => {}
+                    // Client disconnected.
+                    Err(_)
+                    | Ok(Some(Event::Key(KeyEvent {
+                        code: KeyCode::Char('c'),
+                        modifiers: KeyModifiers::CONTROL,
+                        ..
+                    }))) => {
+                        let _ = button_sender.send(Err(Interrupt::ExitProgram));
+                        break 'react_to_event;
+                    }
+                    Ok(Some(Event::Key(KeyEvent {
+                        code: KeyCode::Char('d'),
+                        modifiers: KeyModifiers::CONTROL,
+                        ..
+                    }))) => {
+                        let _ = button_sender.send(Err(Interrupt::ForfeitGame));
+                        break 'react_to_event;
+                    }
+                    // Escape pressed: send pause.
+                    Ok(Some(Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }))) => {
+                        let _ = button_sender.send(Err(Interrupt::Pause));
+                        break 'react_to_event;
+                    }
+                    Ok(Some(Event::Resize(..))) => {
+                        let _ = button_sender.send(Err(Interrupt::WindowResize));
+                    }
+                    // Candidate key pressed.
+                    Ok(Some(Event::Key(KeyEvent {
+                        code: key,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }))) => {
+                        if let Some(&button) = keybinds.get(&key) {
+                            // Binding found: send button press.
+                            let now = Instant::now();
+                            let _ = button_sender.send(Ok((now, button, true)));
+                            let _ = button_sender.send(Ok((now, button, false)));
+                        }
+                    }
+                    // Don't care about other events: ignore.
+                    _ => {}
+                };
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decoder() -> (Decoder, Receiver<Event>) {
+        let (event_sender, event_receiver) = mpsc::channel();
+        let decoder = Decoder::new(event_sender, Arc::new(Mutex::new((80, 24))));
+        (decoder, event_receiver)
+    }
+
+    /// The events decoded from the bytes of several reads.
+    fn decode(reads: &[&[u8]]) -> Vec<Event> {
+        let (mut decoder, event_receiver) = decoder();
+        for bytes in reads {
+            assert!(decoder.decode(bytes).is_ok());
+        }
+        event_receiver.try_iter().collect()
+    }
+
+    fn keys(codes: impl IntoIterator<Item = KeyCode>) -> Vec<Event> {
+        codes
+            .into_iter()
+            .map(|code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+            .collect()
+    }
+
+    #[test]
+    fn telnet_commands_are_stripped() {
+        assert_eq!(
+            decode(&[&[b'a', IAC, DO, OPT_ECHO, IAC, WONT, OPT_NAWS, IAC, 241, b'b']]),
+            keys([KeyCode::Char('a'), KeyCode::Char('b')])
+        );
+        // An escaped IAC is a data byte, which is not a character.
+        assert_eq!(decode(&[&[IAC, IAC, b'c']]), keys([KeyCode::Char('c')]));
+        // Unknown subnegotiations are skipped whole.
+        assert_eq!(
+            decode(&[&[IAC, SB, 24, 0, b'x', b't', IAC, SE, b'd']]),
+            keys([KeyCode::Char('d')])
+        );
+    }
+
+    #[test]
+    fn window_size_is_negotiated() {
+        let (mut decoder, event_receiver) = decoder();
+        assert!(decoder
+            .decode(&[IAC, SB, OPT_NAWS, 0, 100, 0, 40, IAC, SE])
+            .is_ok());
+        assert_eq!(*decoder.size.lock().unwrap(), (100, 40));
+        // Bytes equal to SE or IAC within the sizes.
+        assert!(decoder
+            .decode(&[IAC, SB, OPT_NAWS, 0, SE, 0, IAC, IAC, IAC, SE])
+            .is_ok());
+        assert_eq!(*decoder.size.lock().unwrap(), (240, 255));
+        assert_eq!(
+            event_receiver.try_iter().collect::<Vec<_>>(),
+            [Event::Resize(100, 40), Event::Resize(240, 255)]
+        );
+    }
+
+    #[test]
+    fn escape_sequences_are_keys() {
+        assert_eq!(
+            decode(&[b"\x1b[A\x1b[B\x1bOC\x1bOD\x1b[3~\x1b[5~\x1b[1;5A\x1b"]),
+            keys([
+                KeyCode::Up,
+                KeyCode::Down,
+                KeyCode::Right,
+                KeyCode::Left,
+                KeyCode::Delete,
+                KeyCode::PageUp,
+                KeyCode::Esc,
+            ])
+        );
+        assert_eq!(
+            decode(&[b"\r\n\r\0\rq\t\x7f"]),
+            keys([
+                KeyCode::Enter,
+                KeyCode::Enter,
+                KeyCode::Enter,
+                KeyCode::Char('q'),
+                KeyCode::Tab,
+                KeyCode::Backspace,
+            ])
+        );
+        assert_eq!(
+            decode(&[&[0x03]]),
+            [Event::Key(KeyEvent::new(
+                KeyCode::Char('c'),
+                KeyModifiers::CONTROL
+            ))]
+        );
+    }
+
+    #[test]
+    fn sequences_split_across_reads_are_decoded_once_complete() {
+        assert_eq!(
+            decode(&[b"\x1b[", b"A", b"\x1b[3", b"~", b"\x1bO", b"B"]),
+            keys([KeyCode::Up, KeyCode::Delete, KeyCode::Down])
+        );
+        assert_eq!(
+            decode(&[&[IAC], &[DO], &[OPT_ECHO, b'a']]),
+            keys([KeyCode::Char('a')])
+        );
+        let (mut decoder, event_receiver) = decoder();
+        assert!(decoder.decode(&[IAC, SB, OPT_NAWS, 0, 90]).is_ok());
+        assert_eq!(event_receiver.try_recv().ok(), None);
+        assert!(decoder.decode(&[0, 30, IAC]).is_ok());
+        assert!(decoder.decode(&[SE]).is_ok());
+        assert_eq!(event_receiver.try_recv().ok(), Some(Event::Resize(90, 30)));
+        // A line ending split after its carriage return, and a character split within.
+        assert_eq!(
+            decode(&[
+                b"\r",
+                b"\n",
+                "é".as_bytes().split_at(1).0,
+                "é".as_bytes().split_at(1).1
+            ]),
+            keys([KeyCode::Enter, KeyCode::Char('é')])
+        );
+    }
+}
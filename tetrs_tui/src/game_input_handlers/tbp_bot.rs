@@ -0,0 +1,549 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    sync::mpsc::{self, Receiver, Sender},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use tetrs_engine::{
+    piece_rotation::RotationSystem, ActivePiece, Board, Button, Coord, Game, Tetromino,
+};
+
+use crate::game_input_handlers::InputOrInterrupt;
+
+/// Number of board rows the Tetris Bot Protocol expects.
+const TBP_BOARD_HEIGHT: usize = 40;
+
+/// Messages sent to the bot, see <https://github.com/tetris-bot-protocol/tbp-spec>.
+#[derive(serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum FrontendMessage {
+    Rules {},
+    Start {
+        hold: Option<char>,
+        queue: Vec<char>,
+        combo: u32,
+        back_to_back: bool,
+        board: Vec<[Option<char>; Game::WIDTH]>,
+    },
+    Stop,
+    Suggest,
+    Play {
+        #[serde(rename = "move")]
+        mv: TbpMove,
+    },
+    NewPiece {
+        piece: char,
+    },
+    Quit,
+}
+
+/// Messages received from the bot; other message types are ignored, as the protocol demands.
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BotMessage {
+    Info {},
+    Ready,
+    Error {
+        reason: String,
+    },
+    Suggestion {
+        moves: Vec<TbpMove>,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct TbpMove {
+    location: TbpLocation,
+    spin: String,
+}
+
+/// Where a piece is placed, given by the center it would rotate around in SRS.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct TbpLocation {
+    #[serde(rename = "type")]
+    piece: char,
+    orientation: TbpOrientation,
+    x: isize,
+    y: isize,
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TbpOrientation {
+    North,
+    East,
+    South,
+    West,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct TbpState {
+    board: Board,
+    active_piece: ActivePiece,
+    hold_piece: Option<(Tetromino, bool)>,
+    next_pieces: Vec<Tetromino>,
+    rotation_system: RotationSystem,
+    /// Whether pieces must be soft dropped and locked, as hard and sonic drops are disabled.
+    no_hard_drop: bool,
+    /// Whether pressing hold does nothing.
+    no_hold: bool,
+    combo: u32,
+    back_to_back: bool,
+}
+
+/// What the bot believes the game will look like when the next piece spawns.
+struct BotView {
+    board: Board,
+    hold: Option<Tetromino>,
+    queue: VecDeque<Tetromino>,
+}
+
+/// Lets an external program play the game by talking the Tetris Bot Protocol (TBP) over its
+/// standard input and output.
+#[derive(Debug)]
+pub struct TbpBotHandler {
+    child: Child,
+    _handle: JoinHandle<()>,
+}
+
+impl Drop for TbpBotHandler {
+    fn drop(&mut self) {
+        // Also unblocks the thread waiting for the bot.
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl TbpBotHandler {
+    /// Launches the bot given by a command line (program followed by whitespace-separated arguments).
+    pub fn new(
+        button_sender: &Sender<InputOrInterrupt>,
+        command: &str,
+        action_idle_time: Duration,
+    ) -> io::Result<(Self, Sender<TbpState>)> {
+        let mut args = command.split_whitespace();
+        let program = args
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty bot command"))?;
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(io::Error::other("bot process has no standard input/output"));
+        };
+        let (state_sender, state_receiver) = mpsc::channel();
+        let join_handle = Self::spawn(
+            stdin,
+            stdout,
+            state_receiver,
+            button_sender.clone(),
+            action_idle_time,
+        );
+        let tbp_bot_handler = TbpBotHandler {
+            child,
+            _handle: join_handle,
+        };
+        Ok((tbp_bot_handler, state_sender))
+    }
+
+    pub fn encode(game: &Game) -> Option<TbpState> {
+        let state = game.state();
+        Some(TbpState {
+            board: state.board.clone(),
+            active_piece: state.active_piece_data?.0,
+            hold_piece: state.hold_piece,
+            next_pieces: state.next_pieces.iter().copied().collect(),
+            rotation_system: game.config().rotation_system,
+            no_hard_drop: game.config().no_hard_drop,
+            no_hold: game.config().no_hold,
+            combo: state.consecutive_line_clears,
+            back_to_back: state.back_to_back_special_clears > 0,
+        })
+    }
+
+    fn spawn(
+        stdin: ChildStdin,
+        stdout: ChildStdout,
+        state_receiver: Receiver<TbpState>,
+        button_sender: Sender<InputOrInterrupt>,
+        action_idle_time: Duration,
+    ) -> JoinHandle<()> {
+        let press = move |button| {
+            let _ = button_sender.send(Ok((Instant::now(), button, true)));
+            let _ = button_sender.send(Ok((Instant::now(), button, false)));
+            thread::sleep(action_idle_time);
+        };
+        thread::spawn(move || {
+            let mut bot = TbpConnection {
+                writer: BufWriter::new(stdin),
+                reader: BufReader::new(stdout),
+            };
+            // Bot quit or misbehaved, stop thread.
+            let _ = bot.play(state_receiver, press);
+        })
+    }
+}
+
+struct TbpConnection {
+    writer: BufWriter<ChildStdin>,
+    reader: BufReader<ChildStdout>,
+}
+
+impl TbpConnection {
+    fn send(&mut self, message: &FrontendMessage) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, message)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+
+    fn recv(&mut self) -> io::Result<BotMessage> {
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            match serde_json::from_str(&line)? {
+                BotMessage::Unknown => {}
+                BotMessage::Error { reason } => return Err(io::Error::other(reason)),
+                message => return Ok(message),
+            }
+        }
+    }
+
+    fn play(
+        &mut self,
+        state_receiver: Receiver<TbpState>,
+        press: impl Fn(Button),
+    ) -> io::Result<()> {
+        // Handshake.
+        if !matches!(self.recv()?, BotMessage::Info { .. }) {
+            return Err(io::Error::other("bot did not introduce itself"));
+        }
+        self.send(&FrontendMessage::Rules {})?;
+        if !matches!(self.recv()?, BotMessage::Ready) {
+            return Err(io::Error::other("bot did not accept the rules"));
+        }
+        let mut bot_view: Option<BotView> = None;
+        let mut started = false;
+        // Cells of a placement which needs the piece swapped in by holding.
+        let mut pending_target: Option<[Coord; 4]> = None;
+        while let Ok(mut state) = state_receiver.recv() {
+            // Only react to the most recent state if we fell behind.
+            while let Ok(newer_state) = state_receiver.try_recv() {
+                state = newer_state;
+            }
+            if let Some(target) = pending_target.take() {
+                if matches!(state.hold_piece, Some((_, false))) {
+                    let buttons = find_path(
+                        &state.board,
+                        state.active_piece,
+                        target,
+                        state.rotation_system,
+                        state.no_hard_drop,
+                    )
+                    .unwrap_or(vec![lock_button(state.no_hard_drop)]);
+                    for button in buttons {
+                        press(button);
+                    }
+                    continue;
+                }
+            }
+            let queue = std::iter::once(state.active_piece.shape)
+                .chain(state.next_pieces.iter().copied())
+                .collect::<VecDeque<_>>();
+            match bot_view.take() {
+                // Game went as expected, only tell the bot about newly revealed pieces.
+                Some(view)
+                    if view.board == state.board
+                        && view.hold == state.hold_piece.map(|(piece, _)| piece)
+                        && queue.iter().zip(&view.queue).all(|(p0, p1)| p0 == p1)
+                        && view.queue.len() <= queue.len() =>
+                {
+                    for &piece in queue.range(view.queue.len()..) {
+                        self.send(&FrontendMessage::NewPiece {
+                            piece: piece_char(piece),
+                        })?;
+                    }
+                }
+                // Something unexpected happened (garbage, a different placement, ...): restart.
+                _ => {
+                    if started {
+                        self.send(&FrontendMessage::Stop)?;
+                    }
+                    self.send(&FrontendMessage::Start {
+                        hold: state.hold_piece.map(|(piece, _)| piece_char(piece)),
+                        queue: queue.iter().copied().map(piece_char).collect(),
+                        combo: state.combo,
+                        back_to_back: state.back_to_back,
+                        board: tbp_board(&state.board),
+                    })?;
+                    started = true;
+                }
+            }
+            self.send(&FrontendMessage::Suggest)?;
+            let moves = loop {
+                if let BotMessage::Suggestion { moves } = self.recv()? {
+                    break moves;
+                }
+            };
+            let hold_available = !state.no_hold && !matches!(state.hold_piece, Some((_, false)));
+            let swap_piece = match state.hold_piece {
+                Some((piece, _)) => Some(piece),
+                None => state.next_pieces.first().copied(),
+            };
+            let chosen = moves.into_iter().find_map(|mv| {
+                let piece = parse_piece(mv.location.piece)?;
+                let target = tbp_cells(piece, &mv.location)?;
+                if piece == state.active_piece.shape {
+                    let buttons = find_path(
+                        &state.board,
+                        state.active_piece,
+                        target,
+                        state.rotation_system,
+                        state.no_hard_drop,
+                    )?;
+                    Some((mv, piece, target, Some(buttons)))
+                } else if hold_available && swap_piece == Some(piece) {
+                    Some((mv, piece, target, None))
+                } else {
+                    None
+                }
+            });
+            let Some((mv, piece, target, buttons)) = chosen else {
+                // No usable suggestion: give up on this piece and resynchronize afterwards.
+                press(lock_button(state.no_hard_drop));
+                continue;
+            };
+            self.send(&FrontendMessage::Play { mv })?;
+            let mut view = BotView {
+                board: lock(&state.board, target, piece),
+                hold: state.hold_piece.map(|(piece, _)| piece),
+                queue,
+            };
+            // The current piece is either played or held, and possibly replaced by the next one.
+            let played_from_queue = piece == state.active_piece.shape || view.hold.is_none();
+            if piece != state.active_piece.shape {
+                view.hold = view.queue.pop_front();
+            }
+            if played_from_queue {
+                view.queue.pop_front();
+            }
+            bot_view = Some(view);
+            if let Some(buttons) = buttons {
+                for button in buttons {
+                    press(button);
+                }
+            } else {
+                pending_target = Some(target);
+                press(Button::Hold);
+            }
+        }
+        let _ = self.send(&FrontendMessage::Quit);
+        Ok(())
+    }
+}
+
+fn piece_char(piece: Tetromino) -> char {
+    match piece {
+        Tetromino::O => 'O',
+        Tetromino::I => 'I',
+        Tetromino::S => 'S',
+        Tetromino::Z => 'Z',
+        Tetromino::T => 'T',
+        Tetromino::L => 'L',
+        Tetromino::J => 'J',
+    }
+}
+
+fn parse_piece(piece: char) -> Option<Tetromino> {
+    Tetromino::SHAPES
+        .into_iter()
+        .find(|&shape| piece_char(shape) == piece)
+}
+
+fn tbp_board(board: &Board) -> Vec<[Option<char>; Game::WIDTH]> {
+    let mut tbp_board = board
+        .iter()
+        .map(|line| {
+            line.map(|tile| {
+                tile.map(|tile_type_id| {
+                    Tetromino::SHAPES
+                        .into_iter()
+                        .find(|shape| shape.tiletypeid() == tile_type_id)
+                        .map_or('G', piece_char)
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+    tbp_board.resize(TBP_BOARD_HEIGHT, [None; Game::WIDTH]);
+    tbp_board
+}
+
+/// The cells a piece covers in a TBP location, sorted.
+fn tbp_cells(piece: Tetromino, location: &TbpLocation) -> Option<[Coord; 4]> {
+    let minos: [(isize, isize); 4] = match piece {
+        Tetromino::O => [(0, 0), (1, 0), (0, 1), (1, 1)],
+        Tetromino::I => [(-1, 0), (0, 0), (1, 0), (2, 0)],
+        Tetromino::S => [(-1, 0), (0, 0), (0, 1), (1, 1)],
+        Tetromino::Z => [(-1, 1), (0, 1), (0, 0), (1, 0)],
+        Tetromino::T => [(-1, 0), (0, 0), (1, 0), (0, 1)],
+        Tetromino::L => [(-1, 0), (0, 0), (1, 0), (1, 1)],
+        Tetromino::J => [(-1, 0), (0, 0), (1, 0), (-1, 1)],
+    };
+    let mut cells = [(0, 0); 4];
+    for (cell, (dx, dy)) in cells.iter_mut().zip(minos) {
+        let (dx, dy) = match location.orientation {
+            TbpOrientation::North => (dx, dy),
+            TbpOrientation::East => (dy, -dx),
+            TbpOrientation::South => (-dx, -dy),
+            TbpOrientation::West => (-dy, dx),
+        };
+        let (x, y) = (location.x + dx, location.y + dy);
+        if !(0..Game::WIDTH as isize).contains(&x) || !(0..Game::HEIGHT as isize).contains(&y) {
+            return None;
+        }
+        *cell = (x as usize, y as usize);
+    }
+    cells.sort();
+    Some(cells)
+}
+
+fn sorted_cells(piece: &ActivePiece) -> [Coord; 4] {
+    let mut cells = piece.tiles().map(|(coord, _)| coord);
+    cells.sort();
+    cells
+}
+
+/// The button which locks a piece where it lands.
+///
+/// Without hard drops, a soft drop of a piece which cannot move down any further locks it instead.
+fn lock_button(no_hard_drop: bool) -> Button {
+    if no_hard_drop {
+        Button::DropSoft
+    } else {
+        Button::DropHard
+    }
+}
+
+/// Finds the shortest sequence of buttons which drops the piece into the target cells.
+///
+/// Without hard drops, the piece is soft dropped row by row instead of sonic dropped.
+fn find_path(
+    board: &Board,
+    piece: ActivePiece,
+    target: [Coord; 4],
+    rotation_system: RotationSystem,
+    no_hard_drop: bool,
+) -> Option<Vec<Button>> {
+    let drop_button = if no_hard_drop {
+        Button::DropSoft
+    } else {
+        Button::DropSonic
+    };
+    let mut visited = HashSet::from([piece]);
+    let mut queue = VecDeque::from([(piece, Vec::new())]);
+    while let Some((piece, buttons)) = queue.pop_front() {
+        let landed_piece = if no_hard_drop {
+            piece
+        } else {
+            piece.well_piece(board)
+        };
+        if landed_piece.fits_at(board, (0, -1)).is_none() && sorted_cells(&landed_piece) == target {
+            let mut buttons = buttons;
+            buttons.push(lock_button(no_hard_drop));
+            return Some(buttons);
+        }
+        for button in [
+            Button::MoveLeft,
+            Button::MoveRight,
+            Button::RotateRight,
+            Button::RotateLeft,
+            Button::RotateAround,
+            drop_button,
+        ] {
+            let next_piece = match button {
+                Button::MoveLeft => piece.fits_at(board, (-1, 0)),
+                Button::MoveRight => piece.fits_at(board, (1, 0)),
+                Button::RotateRight => rotation_system.rotate(&piece, board, 1),
+                Button::RotateLeft => rotation_system.rotate(&piece, board, -1),
+                Button::RotateAround => rotation_system.rotate(&piece, board, 2),
+                Button::DropSoft => piece.fits_at(board, (0, -1)),
+                _ => Some(piece.well_piece(board)),
+            };
+            if let Some(next_piece) = next_piece {
+                if visited.insert(next_piece) {
+                    let mut next_buttons = buttons.clone();
+                    next_buttons.push(button);
+                    queue.push_back((next_piece, next_buttons));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Locks the cells into a copy of the board and clears full lines.
+fn lock(board: &Board, cells: [Coord; 4], piece: Tetromino) -> Board {
+    let mut board = board.clone();
+    for (x, y) in cells {
+        board[y][x] = Some(piece.tiletypeid());
+    }
+    let height = board.len();
+    board.retain(|line| line.iter().any(|cell| cell.is_none()));
+    board.resize(height, Default::default());
+    board
+}
+
+#[cfg(test)]
+mod tests {
+    use tetrs_engine::{ButtonsPressed, GameConfig, GameMode};
+
+    use super::*;
+
+    #[test]
+    fn paths_lock_pieces_into_place_with_and_without_hard_drops() {
+        for no_hard_drop in [false, true] {
+            let config = GameConfig {
+                no_hard_drop,
+                ..GameConfig::default()
+            };
+            let mut game = Game::with_seed(GameMode::marathon(), config, 3);
+            let mut time = Duration::from_millis(1);
+            assert!(game.update(None, time).is_ok());
+            let (piece, _) = game.state().active_piece_data.unwrap();
+            let board = game.state().board.clone();
+            // Two columns to the left, turned once.
+            let target_piece = RotationSystem::Ocular
+                .rotate(&piece.fits_at(&board, (-2, 0)).unwrap(), &board, 1)
+                .unwrap()
+                .well_piece(&board);
+            let target = sorted_cells(&target_piece);
+            let buttons =
+                find_path(&board, piece, target, RotationSystem::Ocular, no_hard_drop).unwrap();
+            assert_eq!(
+                buttons.contains(&Button::DropHard) || buttons.contains(&Button::DropSonic),
+                !no_hard_drop
+            );
+            for button in buttons {
+                let mut buttons_pressed = ButtonsPressed::default();
+                buttons_pressed[button] = true;
+                for buttons_pressed in [buttons_pressed, ButtonsPressed::default()] {
+                    time += Duration::from_millis(1);
+                    assert!(game.update(Some(buttons_pressed), time).is_ok());
+                }
+            }
+            assert_eq!(game.state().pieces_played.iter().sum::<u32>(), 1);
+            assert!(target
+                .iter()
+                .all(|&(x, y)| game.state().board[y][x].is_some()));
+        }
+    }
+}
@@ -0,0 +1,424 @@
+use std::{
+    sync::mpsc::{self, Receiver, RecvError, Sender},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use tetrs_engine::{piece_rotation::RotationSystem, ActivePiece, Board, Button, Game, Tetromino};
+
+use crate::game_input_handlers::InputOrInterrupt;
+
+/// How long the bot waits between two button presses of the same piece.
+const ACTION_IDLE_TIME: Duration = Duration::from_millis(25);
+/// How many of the most promising placements are explored further at each additional search level.
+const BEAM_WIDTH: usize = 6;
+
+#[derive(
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Clone,
+    Copy,
+    Hash,
+    Default,
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum BotDifficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl BotDifficulty {
+    pub const ALL: [Self; 4] = [Self::Easy, Self::Medium, Self::Hard, Self::Expert];
+
+    pub fn pieces_per_second(&self) -> f64 {
+        match self {
+            BotDifficulty::Easy => 0.5,
+            BotDifficulty::Medium => 1.0,
+            BotDifficulty::Hard => 2.0,
+            BotDifficulty::Expert => 3.5,
+        }
+    }
+
+    /// How many pieces (current piece and the upcoming preview pieces) the bot plans ahead with.
+    pub fn search_depth(&self) -> usize {
+        match self {
+            BotDifficulty::Easy | BotDifficulty::Medium => 1,
+            BotDifficulty::Hard => 2,
+            BotDifficulty::Expert => 3,
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct BotState {
+    board: Board,
+    active_piece: ActivePiece,
+    hold_piece: Option<(Tetromino, bool)>,
+    next_pieces: Vec<Tetromino>,
+    rotation_system: RotationSystem,
+    /// Whether pieces must be soft dropped and locked, as hard and sonic drops are disabled.
+    no_hard_drop: bool,
+    /// Whether pressing hold does nothing.
+    no_hold: bool,
+}
+
+#[derive(Debug)]
+pub struct VersusBotHandler {
+    _handle: JoinHandle<()>,
+}
+
+impl VersusBotHandler {
+    /// Spawns a bot which sends its inputs as if it were `player`.
+    pub fn new(
+        button_sender: &Sender<InputOrInterrupt<(usize, Button)>>,
+        player: usize,
+        difficulty: BotDifficulty,
+    ) -> (Self, Sender<BotState>) {
+        let (state_sender, state_receiver) = mpsc::channel();
+        let join_handle = Self::spawn(state_receiver, button_sender.clone(), player, difficulty);
+        let versus_bot_handler = VersusBotHandler {
+            _handle: join_handle,
+        };
+        (versus_bot_handler, state_sender)
+    }
+
+    pub fn encode(game: &Game) -> Option<BotState> {
+        let state = game.state();
+        Some(BotState {
+            board: state.board.clone(),
+            active_piece: state.active_piece_data?.0,
+            hold_piece: state.hold_piece,
+            next_pieces: state.next_pieces.iter().copied().collect(),
+            rotation_system: game.config().rotation_system,
+            no_hard_drop: game.config().no_hard_drop,
+            no_hold: game.config().no_hold,
+        })
+    }
+
+    fn spawn(
+        state_receiver: Receiver<BotState>,
+        button_sender: Sender<InputOrInterrupt<(usize, Button)>>,
+        player: usize,
+        difficulty: BotDifficulty,
+    ) -> JoinHandle<()> {
+        let piece_time = Duration::from_secs_f64(difficulty.pieces_per_second().recip());
+        let press = move |button| {
+            let _ = button_sender.send(Ok((Instant::now(), (player, button), true)));
+            let _ = button_sender.send(Ok((Instant::now(), (player, button), false)));
+        };
+        thread::spawn(move || 'react_to_game: loop {
+            match state_receiver.recv() {
+                Ok(mut state) => {
+                    // Only react to the most recent state if we fell behind.
+                    while let Ok(newer_state) = state_receiver.try_recv() {
+                        state = newer_state;
+                    }
+                    let planning_started = Instant::now();
+                    let Some(buttons) = plan(&state, difficulty.search_depth()) else {
+                        continue 'react_to_game;
+                    };
+                    let Some((&last_button, buttons)) = buttons.split_last() else {
+                        continue 'react_to_game;
+                    };
+                    for &button in buttons {
+                        press(button);
+                        thread::sleep(ACTION_IDLE_TIME);
+                    }
+                    // Holding does not count as placing a piece, so do it immediately.
+                    if last_button != Button::Hold {
+                        thread::sleep(
+                            (planning_started + piece_time)
+                                .saturating_duration_since(Instant::now()),
+                        );
+                    }
+                    press(last_button);
+                }
+                // No more state updates will be received, stop thread.
+                Err(RecvError) => break 'react_to_game,
+            }
+        })
+    }
+}
+
+/// Chooses the buttons to press for the current piece, or only [`Button::Hold`] if keeping the
+/// current piece for later seems better.
+fn plan(state: &BotState, search_depth: usize) -> Option<Vec<Button>> {
+    let BotState {
+        board,
+        active_piece,
+        hold_piece,
+        next_pieces,
+        rotation_system,
+        no_hard_drop,
+        no_hold,
+    } = state;
+    let lookahead = search_depth.saturating_sub(1);
+    let (best_score, best_buttons) =
+        placements(board, *active_piece, *rotation_system, *no_hard_drop)
+            .into_iter()
+            .map(|(piece, buttons)| {
+                let (board, lines) = place(board, &piece);
+                let score = search(&board, lines, next_pieces, lookahead, *rotation_system);
+                (score, buttons)
+            })
+            .max_by(|(score0, _), (score1, _)| score0.total_cmp(score1))?;
+    let hold_alternative = match hold_piece {
+        _ if *no_hold => None,
+        Some((_, false)) => None,
+        Some((held_piece, true)) => Some((*held_piece, &next_pieces[..])),
+        None => next_pieces
+            .split_first()
+            .map(|(next_piece, next_pieces)| (*next_piece, next_pieces)),
+    };
+    if let Some((alt_shape, alt_next_pieces)) = hold_alternative {
+        let alt_score = search(
+            board,
+            0,
+            &[&[alt_shape][..], alt_next_pieces].concat(),
+            search_depth,
+            *rotation_system,
+        );
+        if alt_score > best_score {
+            return Some(vec![Button::Hold]);
+        }
+    }
+    Some(best_buttons)
+}
+
+/// Returns the best score achievable by placing the next `depth` pieces, only considering the most
+/// promising placements of each.
+fn search(
+    board: &Board,
+    lines: usize,
+    next_pieces: &[Tetromino],
+    depth: usize,
+    rotation_system: RotationSystem,
+) -> f64 {
+    let Some((&shape, next_pieces)) = next_pieces.split_first().filter(|_| depth > 0) else {
+        return evaluate(board, lines);
+    };
+    // Only where pieces land matters here, not how they are locked.
+    let mut candidates = placements(
+        board,
+        Game::position_tetromino(shape, false),
+        rotation_system,
+        false,
+    )
+    .into_iter()
+    .map(|(piece, _)| {
+        let (board, new_lines) = place(board, &piece);
+        let score = evaluate(&board, lines + new_lines);
+        (score, board, lines + new_lines)
+    })
+    .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        return f64::NEG_INFINITY;
+    }
+    candidates.sort_by(|(score0, ..), (score1, ..)| score1.total_cmp(score0));
+    if depth == 1 {
+        return candidates[0].0;
+    }
+    candidates
+        .into_iter()
+        .take(BEAM_WIDTH)
+        .map(|(_, board, lines)| search(&board, lines, next_pieces, depth - 1, rotation_system))
+        .fold(f64::NEG_INFINITY, f64::max)
+}
+
+/// All final positions reachable by rotating once, shifting sideways and then dropping hard.
+///
+/// Without hard drops, the piece is soft dropped row by row, and once more to lock it.
+fn placements(
+    board: &Board,
+    piece: ActivePiece,
+    rotation_system: RotationSystem,
+    no_hard_drop: bool,
+) -> Vec<(ActivePiece, Vec<Button>)> {
+    let mut placements: Vec<(ActivePiece, Vec<Button>)> = Vec::new();
+    for (right_turns, rotate_buttons) in [
+        (0, &[][..]),
+        (1, &[Button::RotateRight][..]),
+        (-1, &[Button::RotateLeft][..]),
+        (2, &[Button::RotateAround][..]),
+    ] {
+        let rotated = if right_turns == 0 {
+            Some(piece)
+        } else {
+            rotation_system.rotate(&piece, board, right_turns)
+        };
+        let Some(rotated) = rotated else {
+            continue;
+        };
+        for (dx, move_button) in [(-1, Button::MoveLeft), (1, Button::MoveRight)] {
+            let mut moved = rotated;
+            let mut moves = 0;
+            loop {
+                let landed = moved.well_piece(board);
+                let mut landed_tiles = landed.tiles().map(|(coord, _)| coord);
+                landed_tiles.sort();
+                if !placements.iter().any(|(other, _)| {
+                    let mut other_tiles = other.tiles().map(|(coord, _)| coord);
+                    other_tiles.sort();
+                    other_tiles == landed_tiles
+                }) {
+                    let mut buttons = rotate_buttons.to_vec();
+                    buttons.extend(std::iter::repeat_n(move_button, moves));
+                    if no_hard_drop {
+                        let rows = moved.position.1 - landed.position.1;
+                        buttons.extend(std::iter::repeat_n(Button::DropSoft, rows + 1));
+                    } else {
+                        buttons.push(Button::DropHard);
+                    }
+                    placements.push((landed, buttons));
+                }
+                let Some(next_moved) = moved.fits_at(board, (dx, 0)) else {
+                    break;
+                };
+                moved = next_moved;
+                moves += 1;
+            }
+        }
+    }
+    placements
+}
+
+/// Locks a piece into a copy of the board, returning it together with the number of lines cleared.
+fn place(board: &Board, piece: &ActivePiece) -> (Board, usize) {
+    let mut board = board.clone();
+    for ((x, y), tile_type_id) in piece.tiles() {
+        board[y][x] = Some(tile_type_id);
+    }
+    let height = board.len();
+    board.retain(|line| line.iter().any(|cell| cell.is_none()));
+    let lines_cleared = height - board.len();
+    board.resize(height, Default::default());
+    (board, lines_cleared)
+}
+
+/// A simple weighted board heuristic: prefer low, flat stacks without holes.
+fn evaluate(board: &Board, lines: usize) -> f64 {
+    let mut heights = [0usize; Game::WIDTH];
+    let mut holes = 0usize;
+    for (x, height) in heights.iter_mut().enumerate() {
+        *height = board
+            .iter()
+            .rposition(|line| line[x].is_some())
+            .map_or(0, |y| y + 1);
+        holes += board[..*height]
+            .iter()
+            .filter(|line| line[x].is_none())
+            .count();
+    }
+    let aggregate_height = heights.iter().sum::<usize>();
+    let bumpiness = heights
+        .windows(2)
+        .map(|pair| pair[0].abs_diff(pair[1]))
+        .sum::<usize>();
+    let max_height = heights.iter().copied().max().unwrap_or(0);
+    let danger = max_height.saturating_sub(Game::SKYLINE / 2);
+    -0.51 * aggregate_height as f64 + 0.76 * lines as f64
+        - 0.36 * holes as f64
+        - 0.18 * bumpiness as f64
+        - 2.0 * danger as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use tetrs_engine::{ButtonsPressed, GameConfig, GameMode, Line};
+
+    use super::*;
+
+    /// A board whose lowest rows are full except for the given columns.
+    fn board_with_well(rows: usize, well: &[usize]) -> Board {
+        let mut board = vec![Line::default(); Game::HEIGHT];
+        for line in &mut board[..rows] {
+            *line = [Some(Game::GARBAGE_TILE); Game::WIDTH];
+            for &x in well {
+                line[x] = None;
+            }
+        }
+        board
+    }
+
+    fn bot_state(
+        board: Board,
+        shape: Tetromino,
+        hold_piece: Option<(Tetromino, bool)>,
+    ) -> BotState {
+        BotState {
+            board,
+            active_piece: Game::position_tetromino(shape, false),
+            hold_piece,
+            next_pieces: Vec::new(),
+            rotation_system: RotationSystem::Ocular,
+            no_hard_drop: false,
+            no_hold: false,
+        }
+    }
+
+    #[test]
+    fn placements_filling_a_well_are_chosen() {
+        let board = board_with_well(4, &[9]);
+        let state = bot_state(board.clone(), Tetromino::I, None);
+        let tetris = placements(&board, state.active_piece, state.rotation_system, false)
+            .into_iter()
+            .find(|(piece, _)| place(&board, piece).1 == 4)
+            .unwrap();
+        assert_eq!(plan(&state, 1), Some(tetris.1));
+        assert!(evaluate(&place(&board, &tetris.0).0, 4) > evaluate(&board, 0));
+    }
+
+    #[test]
+    fn hold_is_only_chosen_when_allowed() {
+        let board = board_with_well(4, &[9]);
+        let mut state = bot_state(board, Tetromino::O, Some((Tetromino::I, true)));
+        assert_eq!(plan(&state, 1), Some(vec![Button::Hold]));
+        state.hold_piece = Some((Tetromino::I, false));
+        assert_ne!(plan(&state, 1), Some(vec![Button::Hold]));
+        state.hold_piece = Some((Tetromino::I, true));
+        state.no_hold = true;
+        assert_ne!(plan(&state, 1), Some(vec![Button::Hold]));
+    }
+
+    #[test]
+    fn deeper_searches_find_clears_needing_several_pieces() {
+        let board = board_with_well(4, &[8, 9]);
+        let pieces = [Tetromino::I, Tetromino::I];
+        let shallow = search(&board, 0, &pieces, 1, RotationSystem::Ocular);
+        let deep = search(&board, 0, &pieces, 2, RotationSystem::Ocular);
+        assert!(deep > shallow);
+        assert_eq!(deep, evaluate(&board_with_well(0, &[]), 4));
+    }
+
+    #[test]
+    fn plans_lock_pieces_with_and_without_hard_drops() {
+        for no_hard_drop in [false, true] {
+            let config = GameConfig {
+                no_hard_drop,
+                ..GameConfig::default()
+            };
+            let mut game = Game::with_seed(GameMode::marathon(), config, 3);
+            let mut time = Duration::from_millis(1);
+            assert!(game.update(None, time).is_ok());
+            let state = VersusBotHandler::encode(&game).unwrap();
+            let buttons = plan(&state, 1).unwrap();
+            assert_eq!(buttons.contains(&Button::DropHard), !no_hard_drop);
+            for button in buttons {
+                let mut buttons_pressed = ButtonsPressed::default();
+                buttons_pressed[button] = true;
+                for buttons_pressed in [buttons_pressed, ButtonsPressed::default()] {
+                    time += Duration::from_millis(1);
+                    assert!(game.update(Some(buttons_pressed), time).is_ok());
+                }
+            }
+            assert_eq!(game.state().pieces_played.iter().sum::<u32>(), 1);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::path::Path;
+
+use tetrs_engine::{
+    fumen::{self, FumenPage},
+    FnGameMod, Game, Line, Tetromino,
+};
+
+use crate::game_mods::utils;
+
+/// A named starting position for custom games, as made in the board editor.
+///
+/// Example (`tsd_opener.toml`):
+/// ```toml
+/// name = "TSD opener"
+/// board = [
+///   "LL...SS..I",
+///   "L...SS.JJI",
+///   "L.....ZJOI",
+/// ]
+/// hold = "T"
+/// queue = "TIO"
+/// ```
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BoardLayout {
+    pub name: String,
+    /// The lowest rows of the board, from top to bottom (see [`utils::parse_board`]).
+    pub board: Vec<String>,
+    #[serde(default)]
+    pub hold: Option<char>,
+    /// Pieces coming before the randomly generated ones.
+    #[serde(default)]
+    pub queue: String,
+}
+
+impl BoardLayout {
+    /// Loads all `.toml` and `.json` layouts in a directory, sorted by file name.
+    ///
+    /// Files which could not be loaded are reported as error messages.
+    pub fn load_dir(dir: &Path) -> (Vec<Self>, Vec<String>) {
+        utils::load_data_files(dir, Self::check)
+    }
+
+    /// Checks whether the layout can be used to start a game.
+    pub fn check(&self) -> Result<(), String> {
+        utils::parse_board(&self.board)?;
+        utils::parse_pieces(&self.queue)?;
+        if let Some(hold) = self.hold {
+            utils::parse_tetromino(hold).ok_or(format!("unknown hold piece {hold:?}"))?;
+        }
+        Ok(())
+    }
+
+    /// A game mod setting up the board, hold piece and upcoming pieces at the start of the game.
+    pub fn modifier(&self) -> FnGameMod {
+        let lines = utils::parse_board(&self.board).unwrap_or_default();
+        let hold = self.hold.and_then(utils::parse_tetromino);
+        let queue = utils::parse_pieces(&self.queue).unwrap_or_default();
+        let mut init = false;
+        Box::new(move |_, _, state, _, _| {
+            if !init {
+                for (line, starting_line) in state.board.iter_mut().zip(&lines) {
+                    *line = *starting_line;
+                }
+                if let Some(hold) = hold {
+                    state.hold_piece = Some((hold, true));
+                }
+                for tetromino in queue.iter().rev() {
+                    state.next_pieces.push_front(*tetromino);
+                }
+                init = true;
+            }
+        })
+    }
+
+    /// Creates a layout from a fumen page.
+    ///
+    /// The queue starts with the page's piece, unless a quiz comment (`#Q=[hold](current)next`)
+    /// gives the hold piece and queue.
+    pub fn from_fumen(name: String, page: &FumenPage) -> Self {
+        let mut board = page
+            .board
+            .iter()
+            .take(Game::SKYLINE)
+            .rev()
+            .map(|line| {
+                line.iter()
+                    .map(|cell| {
+                        cell.map_or('.', |tile_type_id| {
+                            Tetromino::SHAPES
+                                .into_iter()
+                                .find(|tet| tet.tiletypeid() == tile_type_id)
+                                .map_or('X', utils::tetromino_char)
+                        })
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        let first_filled = board
+            .iter()
+            .position(|row| row.chars().any(|ch| ch != '.'))
+            .unwrap_or(board.len());
+        board.drain(..first_filled);
+        let (hold, queue) = fumen::parse_quiz(&page.comment)
+            .unwrap_or((None, page.piece.iter().map(|piece| piece.shape).collect()));
+        Self {
+            name,
+            board,
+            hold: hold.map(utils::tetromino_char),
+            queue: queue.into_iter().map(utils::tetromino_char).collect(),
+        }
+    }
+
+    /// The layout as fumen page, with the hold piece and queue given as quiz comment.
+    pub fn to_fumen(&self) -> FumenPage {
+        let mut board = utils::parse_board(&self.board).unwrap_or_default();
+        board.resize(Game::HEIGHT, Line::default());
+        let hold = self.hold.and_then(utils::parse_tetromino);
+        let queue = utils::parse_pieces(&self.queue).unwrap_or_default();
+        FumenPage {
+            board,
+            comment: fumen::quiz_comment(hold, &queue),
+            ..FumenPage::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tetrs_engine::GameMode;
+
+    use super::*;
+
+    fn layout() -> BoardLayout {
+        toml::from_str(
+            r#"
+            name = "TSD opener"
+            board = [
+              "LL...SS..I",
+              "L...SS.JJI",
+              "L.....ZJOX",
+            ]
+            hold = "T"
+            queue = "TIO"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn layouts_are_checked() {
+        assert!(layout().check().is_ok());
+        let mut wide = layout();
+        wide.board[0].push('X');
+        let mut bad_queue = layout();
+        bad_queue.queue.push('W');
+        let mut bad_hold = layout();
+        bad_hold.hold = Some('W');
+        for layout in [wide, bad_queue, bad_hold] {
+            assert!(layout.check().is_err(), "{layout:?}");
+        }
+    }
+
+    #[test]
+    fn layouts_set_up_fresh_games() {
+        let layout = layout();
+        let mut game = Game::new(GameMode::marathon());
+        unsafe { game.add_modifier(layout.modifier()) };
+        assert!(game.update(None, Duration::from_millis(1)).is_ok());
+        let state = game.state();
+        let board = utils::parse_board(&layout.board).unwrap();
+        assert_eq!(state.board[..board.len()], board[..]);
+        assert_eq!(state.hold_piece, Some((Tetromino::T, true)));
+        let (piece, _) = state.active_piece_data.unwrap();
+        assert_eq!(piece.shape, Tetromino::T);
+        assert_eq!(
+            state
+                .next_pieces
+                .iter()
+                .take(2)
+                .copied()
+                .collect::<Vec<_>>(),
+            [Tetromino::I, Tetromino::O]
+        );
+    }
+
+    #[test]
+    fn layouts_survive_fumen_round_trips() {
+        let layout = layout();
+        let pages = fumen::decode(&fumen::encode(&[layout.to_fumen()])).unwrap();
+        let decoded = BoardLayout::from_fumen(layout.name.clone(), &pages[0]);
+        assert_eq!(decoded.board, layout.board);
+        assert_eq!(decoded.hold, layout.hold);
+        assert_eq!(decoded.queue, layout.queue);
+    }
+}
@@ -26,13 +26,13 @@ fn is_cheese_line(line: &Line) -> bool {
         .any(|cell| *cell == Some(NonZeroU8::try_from(254).unwrap()))
 }
 
-pub fn new_game(cheese_limit: Option<NonZeroUsize>, gap_size: usize) -> Game {
+pub fn modifier(cheese_limit: Option<NonZeroUsize>, gap_size: usize) -> FnGameMod {
     let mut line_source =
         random_gap_lines(gap_size).take(cheese_limit.unwrap_or(NonZeroUsize::MAX).get());
     let mut temp_cheese_tally = 0;
     let mut temp_normal_tally = 0;
     let mut init = false;
-    let cheese_mode: FnGameMod = Box::new(
+    Box::new(
         move |_config: &mut GameConfig,
               _mode: &mut GameMode,
               state: &mut GameState,
@@ -63,22 +63,29 @@ pub fn new_game(cheese_limit: Option<NonZeroUsize>, gap_size: usize) -> Game {
             ) {
                 state.lines_cleared -= temp_normal_tally;
                 for cheese in line_source.by_ref().take(temp_cheese_tally) {
-                    state.board.insert(0, cheese);
+                    state.insert_row(0, cheese);
                 }
                 temp_cheese_tally = 0;
                 temp_normal_tally = 0;
             }
         },
-    );
+    )
+}
+
+pub fn new_game(cheese_limit: Option<NonZeroUsize>, gap_size: usize) -> Game {
     let mut game = Game::new(GameMode {
         name: "Cheese".to_string(),
         start_level: NonZeroU32::MIN,
         increment_level: false,
+        level_progression: Default::default(),
+        big: false,
+        cascade: false,
+        zone: false,
         limits: Limits {
             lines: cheese_limit.map(|line_count| (true, line_count.get())),
             ..Default::default()
         },
     });
-    unsafe { game.add_modifier(cheese_mode) };
+    unsafe { game.add_modifier(modifier(cheese_limit, gap_size)) };
     game
 }
@@ -43,10 +43,10 @@ fn four_wide_lines() -> impl Iterator<Item = Line> {
     })
 }
 
-pub fn new_game(initial_layout: u16) -> Game {
+pub fn modifier(initial_layout: u16) -> FnGameMod {
     let mut line_source = four_wide_lines();
     let mut init = false;
-    let combo_mode: FnGameMod = Box::new(
+    Box::new(
         move |_config: &mut GameConfig,
               _mode: &mut GameMode,
               state: &mut GameState,
@@ -72,18 +72,25 @@ pub fn new_game(initial_layout: u16) -> Game {
                     state.end = Some(Err(tetrs_engine::GameOver::ModeLimit));
                 // Combo continues, prepare new line.
                 } else {
-                    state.board.push(line_source.next().unwrap());
+                    state.insert_row(state.board.len(), line_source.next().unwrap());
                 }
             }
         },
-    );
+    )
+}
+
+pub fn new_game(initial_layout: u16) -> Game {
     let mut game = Game::new(GameMode {
         name: "Combo".to_string(),
         start_level: NonZeroU32::MIN,
         increment_level: false,
+        level_progression: Default::default(),
+        big: false,
+        cascade: false,
+        zone: false,
         limits: Limits::default(),
     });
-    unsafe { game.add_modifier(combo_mode) };
+    unsafe { game.add_modifier(modifier(initial_layout)) };
     game
 }
 
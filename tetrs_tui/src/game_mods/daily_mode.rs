@@ -0,0 +1,305 @@
+use std::num::{NonZeroU32, NonZeroUsize};
+
+use chrono::NaiveDate;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tetrs_engine::{
+    piece_generation::TetrominoSource, Game, GameConfig, GameMode, GameState, Limits,
+};
+
+use crate::game_mods::{dig_race_mode, survival_mode};
+
+/// The format in which dates appear in the names of daily games.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The kinds of game a daily challenge may be.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Variant {
+    Sprint,
+    Ultra,
+    Marathon,
+    Cheese,
+    Survival,
+}
+
+impl Variant {
+    pub const ALL: [Self; 5] = [
+        Self::Sprint,
+        Self::Ultra,
+        Self::Marathon,
+        Self::Cheese,
+        Self::Survival,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Sprint => "Sprint",
+            Variant::Ultra => "Ultra",
+            Variant::Marathon => "Marathon",
+            Variant::Cheese => "Cheese",
+            Variant::Survival => "Survival",
+        }
+    }
+}
+
+/// Everything that is decided by the date of a daily challenge.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Challenge {
+    pub date: NaiveDate,
+    pub seed: u64,
+    pub variant: Variant,
+    pub start_level: NonZeroU32,
+    pub preview_count: usize,
+    /// Index into [`Challenge::GENERATORS`].
+    pub generator: usize,
+}
+
+impl Challenge {
+    /// The piece generators a challenge may be played with, with their names.
+    pub const GENERATORS: [(&'static str, TetrominoSource); 3] = [
+        ("bag", TetrominoSource::bag()),
+        ("recency", TetrominoSource::recency()),
+        ("balance", TetrominoSource::balance_relative()),
+    ];
+
+    /// The challenge of the given day, which is the same for everyone playing it.
+    pub fn of(date: NaiveDate) -> Self {
+        let seed = seed(date);
+        // Decide the conditions independently of the game itself, which is seeded directly.
+        let mut rng = StdRng::seed_from_u64(seed ^ 0xDA11_C4A1_1E46_E000);
+        let variant = Variant::ALL[rng.gen_range(0..Variant::ALL.len())];
+        let start_level = match variant {
+            // Level goals should not be skipped.
+            Variant::Marathon | Variant::Survival => NonZeroU32::MIN,
+            _ => NonZeroU32::try_from(rng.gen_range(1..=5)).unwrap(),
+        };
+        Self {
+            date,
+            seed,
+            variant,
+            start_level,
+            preview_count: rng.gen_range(1..=5),
+            generator: rng.gen_range(0..Self::GENERATORS.len()),
+        }
+    }
+
+    /// The challenge a daily game was played in, if its name is exactly that of the challenge.
+    pub fn of_game_name(name: &str) -> Option<Self> {
+        let date = name.strip_prefix("Daily ")?.get(..10)?;
+        let challenge = Self::of(NaiveDate::parse_from_str(date, DATE_FORMAT).ok()?);
+        (challenge.game_name() == name).then_some(challenge)
+    }
+
+    /// The challenge of the current (UTC) day.
+    pub fn today() -> Self {
+        Self::of(chrono::Utc::now().date_naive())
+    }
+
+    /// The name of the games of this challenge, e.g. "Daily 2024-07-01 (Sprint)".
+    ///
+    /// Daily games are thereby ranked against the other games of the same day only.
+    pub fn game_name(&self) -> String {
+        format!(
+            "Daily {} ({})",
+            self.date.format(DATE_FORMAT),
+            self.variant.name()
+        )
+    }
+
+    /// Short description of the variant and rules of the challenge.
+    pub fn describe(&self) -> String {
+        format!(
+            "{}, level {}, {} preview, {} pieces",
+            self.variant.name(),
+            self.start_level,
+            self.preview_count,
+            Self::GENERATORS[self.generator].0
+        )
+    }
+
+    pub fn new_game(&self) -> Game {
+        let mode = match self.variant {
+            Variant::Sprint => GameMode::sprint(self.start_level),
+            Variant::Ultra => GameMode::ultra(self.start_level),
+            Variant::Marathon => GameMode::marathon(),
+            Variant::Cheese => GameMode {
+                start_level: self.start_level,
+                increment_level: false,
+                limits: Limits {
+                    garbage_lines: Some((true, CHEESE_LINES.get())),
+                    ..Default::default()
+                },
+                ..GameMode::marathon()
+            },
+            Variant::Survival => GameMode {
+                limits: Default::default(),
+                ..GameMode::marathon()
+            },
+        };
+        let mut game = Game::with_seed(
+            GameMode {
+                name: self.game_name(),
+                ..mode
+            },
+            GameConfig::default(),
+            self.seed,
+        );
+        match self.variant {
+            Variant::Cheese => unsafe {
+                game.add_modifier(dig_race_mode::modifier(
+                    self.seed,
+                    CHEESE_LINES,
+                    CHEESE_HOLE_CHANGE_PROBABILITY,
+                ))
+            },
+            Variant::Survival => unsafe { game.add_modifier(survival_mode::modifier(self.seed)) },
+            _ => {}
+        }
+        game
+    }
+
+    /// Replaces the player's configuration by the rules of the challenge.
+    ///
+    /// Only personal handling settings are kept, so that everyone plays the day by the same rules.
+    pub fn configure(&self, config: &mut GameConfig) {
+        *config = GameConfig {
+            delayed_auto_shift: config.delayed_auto_shift,
+            auto_repeat_rate: config.auto_repeat_rate,
+            soft_drop_factor: config.soft_drop_factor,
+            no_soft_drop_lock: config.no_soft_drop_lock,
+            preview_count: self.preview_count,
+            tetromino_generator: Self::GENERATORS[self.generator].1.clone(),
+            ..GameConfig::default()
+        };
+    }
+}
+
+/// The number of garbage lines to be cleared in a daily Cheese challenge.
+const CHEESE_LINES: NonZeroUsize = NonZeroUsize::new(20).unwrap();
+
+/// How likely the hole of a daily Cheese garbage line is to move.
+const CHEESE_HOLE_CHANGE_PROBABILITY: f64 = 0.5;
+
+/// The seed of a day's challenge, derived from nothing but its date.
+pub fn seed(date: NaiveDate) -> u64 {
+    // SplitMix64 finalizer, so consecutive days get unrelated seeds.
+    let mut z = u64::from(date.format("%Y%m%d").to_string().parse::<u32>().unwrap())
+        .wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Updates the number of consecutive days a daily challenge was played, given the date of the
+/// last one played (if any) and the date of the one just played.
+pub fn next_streak(streak: u32, last_played: Option<NaiveDate>, played: NaiveDate) -> u32 {
+    match last_played {
+        Some(last_played) if last_played == played => streak.max(1),
+        Some(last_played) if last_played.succ_opt() == Some(played) => streak + 1,
+        _ => 1,
+    }
+}
+
+/// Whether a daily game counts as having taken part in the challenge.
+pub fn played(state: &GameState) -> bool {
+    state.end.is_some_and(|end| end.is_ok()) || state.pieces_played.iter().sum::<u32>() >= 10
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+    use tetrs_engine::Ruleset;
+
+    use super::*;
+
+    fn date(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, DATE_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn challenges_are_fixed_by_their_date() {
+        let challenge = Challenge::of(date("2024-07-01"));
+        assert_eq!(challenge, Challenge::of(date("2024-07-01")));
+        // Everyone must get the same challenge, whichever version they play.
+        assert_eq!(challenge.seed, 6426045417633668298);
+        assert_eq!(
+            challenge.describe(),
+            "Marathon, level 1, 5 preview, recency pieces"
+        );
+        assert_ne!(seed(date("2024-07-02")), challenge.seed);
+    }
+
+    #[test]
+    fn game_names_lead_back_to_their_challenge() {
+        for day in ["2024-07-01", "2024-12-31", "2025-02-28"] {
+            let challenge = Challenge::of(date(day));
+            assert_eq!(
+                Challenge::of_game_name(&challenge.game_name()),
+                Some(challenge)
+            );
+        }
+        let other_variant = Variant::ALL
+            .into_iter()
+            .find(|variant| *variant != Variant::Marathon)
+            .unwrap();
+        for name in [
+            format!("Daily 2024-07-01 ({})", other_variant.name()),
+            "Daily 2024-07-01 (Marathon) 2".to_string(),
+            "Daily 2024-13-01 (Marathon)".to_string(),
+            "Daily 2024-07-01".to_string(),
+            "Marathon".to_string(),
+        ] {
+            assert_eq!(Challenge::of_game_name(&name), None, "{name}");
+        }
+    }
+
+    #[test]
+    fn streaks_continue_on_consecutive_days_only() {
+        let day = date("2024-02-28");
+        // First challenge played.
+        assert_eq!(next_streak(0, None, day), 1);
+        // The same day played again.
+        assert_eq!(next_streak(3, Some(day), day), 3);
+        assert_eq!(next_streak(0, Some(day), day), 1);
+        // The next day, also across the end of a month.
+        assert_eq!(next_streak(3, Some(day), date("2024-02-29")), 4);
+        assert_eq!(
+            next_streak(4, Some(date("2024-02-29")), date("2024-03-01")),
+            5
+        );
+        // A day skipped, or an earlier day played.
+        assert_eq!(next_streak(3, Some(day), date("2024-03-01")), 1);
+        assert_eq!(next_streak(3, Some(day), date("2024-02-27")), 1);
+    }
+
+    #[test]
+    fn challenges_are_played_by_the_same_rules_by_everyone() {
+        let challenge = Challenge::of(date("2024-07-01"));
+        let rules = |player_config: GameConfig| {
+            let mut config = player_config;
+            challenge.configure(&mut config);
+            serde_json::to_value(config).unwrap()
+        };
+        let handling = GameConfig {
+            delayed_auto_shift: Duration::from_millis(100),
+            auto_repeat_rate: Duration::ZERO,
+            soft_drop_factor: 40.0,
+            no_soft_drop_lock: true,
+            ..GameConfig::default()
+        };
+        let mut classic = Ruleset::Classic.game_config();
+        classic.delayed_auto_shift = handling.delayed_auto_shift;
+        classic.auto_repeat_rate = handling.auto_repeat_rate;
+        classic.soft_drop_factor = handling.soft_drop_factor;
+        classic.no_soft_drop_lock = handling.no_soft_drop_lock;
+        let daily_rules = rules(handling);
+        assert_eq!(rules(classic), daily_rules);
+        assert_eq!(
+            daily_rules["delayed_auto_shift"],
+            json!({"secs": 0, "nanos": 100_000_000})
+        );
+        assert_eq!(daily_rules["no_soft_drop_lock"], true);
+        assert_eq!(daily_rules["preview_count"], 5);
+    }
+}
@@ -86,7 +86,7 @@ pub fn new_game() -> Game {
                 }
                 init = true;
             }
-            let Some((active_piece, _)) = &mut state.active_piece_data else {
+            let Some((mut active_piece, _)) = state.active_piece_data else {
                 return;
             };
             let descent_period_elapsed = state.time.saturating_sub(instant_last_descent)
@@ -101,8 +101,8 @@ pub fn new_game() -> Game {
                 instant_camera_adjusted = state.time;
                 depth += 1;
                 active_piece.position.1 += 1;
-                state.board.insert(0, line_source.next().unwrap());
-                state.board.pop();
+                state.insert_row(0, line_source.next().unwrap());
+                state.pop_row();
                 if active_piece.position.1 >= Game::SKYLINE {
                     state.end = Some(Err(tetrs_engine::GameOver::ModeLimit));
                 }
@@ -163,12 +163,19 @@ pub fn new_game() -> Game {
             state.events.remove(&InternalEvent::LockTimer);
             // FIXME: Remove jank.
             active_piece.shape = descent_tetromino;
+            if let Some((piece, _)) = &mut state.active_piece_data {
+                *piece = active_piece;
+            }
         },
     );
     let mut game = Game::new(GameMode {
         name: "Descent".to_string(),
         start_level: NonZeroU32::MIN,
         increment_level: false,
+        level_progression: Default::default(),
+        big: false,
+        cascade: false,
+        zone: false,
         limits: Limits {
             time: Some((true, Duration::from_secs(180))),
             ..Default::default()
@@ -0,0 +1,100 @@
+use std::num::{NonZeroU32, NonZeroUsize};
+
+use tetrs_engine::{
+    FeedbackEvents, FnGameMod, Game, GameConfig, GameMode, GameState, InternalEvent, Limits,
+    ModifierPoint,
+};
+
+use crate::game_mods::utils;
+
+/// The number of garbage lines to be cleared in a standard game of Dig Race.
+pub const LINES: NonZeroUsize = NonZeroUsize::new(40).unwrap();
+
+/// How many garbage lines are on the board at most.
+const VISIBLE_LINES: usize = 10;
+
+/// Fills the bottom of the board with garbage lines, and replaces the garbage lines cleared until
+/// a total of `lines` were put on the board.
+pub fn modifier(seed: u64, lines: NonZeroUsize, hole_change_probability: f64) -> FnGameMod {
+    let mut line_source =
+        utils::messy_garbage_lines(seed, hole_change_probability).take(lines.get());
+    let mut garbage_lines_cleared = 0;
+    let mut init = false;
+    Box::new(
+        move |_config: &mut GameConfig,
+              _mode: &mut GameMode,
+              state: &mut GameState,
+              _feedback_events: &mut FeedbackEvents,
+              modifier_point: &ModifierPoint| {
+            if !init {
+                for (line, garbage) in state
+                    .board
+                    .iter_mut()
+                    .take(VISIBLE_LINES)
+                    .rev()
+                    .zip(&mut line_source)
+                {
+                    *line = garbage;
+                }
+                init = true;
+            } else if matches!(
+                modifier_point,
+                ModifierPoint::AfterEvent(InternalEvent::LineClear)
+            ) {
+                let n_refills = state.garbage_lines_cleared - garbage_lines_cleared;
+                garbage_lines_cleared = state.garbage_lines_cleared;
+                for garbage in line_source.by_ref().take(n_refills) {
+                    state.insert_row(0, garbage);
+                    state.pop_row();
+                }
+            }
+        },
+    )
+}
+
+pub fn new_game(seed: u64, hole_change_probability: f64) -> Game {
+    let mut game = Game::with_seed(
+        GameMode {
+            name: "Dig Race".to_string(),
+            start_level: NonZeroU32::MIN,
+            increment_level: false,
+            level_progression: Default::default(),
+            big: false,
+            cascade: false,
+            zone: false,
+            limits: Limits {
+                garbage_lines: Some((true, LINES.get())),
+                ..Default::default()
+            },
+        },
+        GameConfig::default(),
+        seed,
+    );
+    unsafe { game.add_modifier(modifier(seed, LINES, hole_change_probability)) };
+    game
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tetrs_engine::Line;
+
+    use super::*;
+
+    fn starting_board(seed: u64) -> Vec<Line> {
+        let mut game = new_game(seed, 0.3);
+        assert!(game.update(None, Duration::from_millis(1)).is_ok());
+        game.state().board.clone()
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_garbage() {
+        let board = starting_board(3);
+        for line in &board[..VISIBLE_LINES] {
+            assert_eq!(line.iter().filter(|cell| cell.is_none()).count(), 1);
+        }
+        assert_eq!(board, starting_board(3));
+        assert_ne!(board, starting_board(4));
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use tetrs_engine::{
+    FeedbackEvents, FnGameMod, Game, GameConfig, GameMode, GameState, InternalEvent, ModifierPoint,
+};
+
+use crate::game_mods::utils;
+
+/// The variants of Invisible mode as `(name, fade delay, flash duration on line clears)`.
+pub const VARIANTS: [(&str, Duration, Option<Duration>); 4] = [
+    ("fading", Duration::from_secs(4), None),
+    ("fast fading", Duration::from_secs(1), None),
+    ("invisible", Duration::ZERO, None),
+    (
+        "invisible, flash on clear",
+        Duration::ZERO,
+        Some(Duration::from_millis(500)),
+    ),
+];
+
+/// Makes the renderer hide locked pieces after the given delay, and possibly reveal the whole
+/// stack for a moment after each line clear.
+pub fn modifier(fade_delay: Duration, flash: Option<Duration>) -> FnGameMod {
+    Box::new(
+        move |_config: &mut GameConfig,
+              _mode: &mut GameMode,
+              state: &mut GameState,
+              _feedback_events: &mut FeedbackEvents,
+              modifier_point: &ModifierPoint| {
+            state
+                .mod_stats
+                .entry(utils::STACK_FADE_DELAY.to_string())
+                .or_insert(fade_delay.as_millis() as i64);
+            if let (Some(flash), ModifierPoint::AfterEvent(InternalEvent::LineClear)) =
+                (flash, modifier_point)
+            {
+                state.mod_stats.insert(
+                    utils::STACK_REVEALED_UNTIL.to_string(),
+                    (state.time + flash).as_millis() as i64,
+                );
+            }
+        },
+    )
+}
+
+/// The name of the games of a variant, e.g. "Invisible (fading)".
+pub fn game_name(variant: usize) -> String {
+    format!("Invisible ({})", VARIANTS[variant % VARIANTS.len()].0)
+}
+
+/// Starts a game of Invisible mode, named after the variant played (see [`game_name`]).
+pub fn new_game(variant: usize) -> Game {
+    let (_, fade_delay, flash) = VARIANTS[variant % VARIANTS.len()];
+    let mut game = Game::new(GameMode {
+        name: game_name(variant),
+        ..GameMode::marathon()
+    });
+    unsafe { game.add_modifier(modifier(fade_delay, flash)) };
+    game
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variants_set_their_fade_delay() {
+        for (variant, (name, fade_delay, _)) in VARIANTS.iter().enumerate() {
+            let mut game = new_game(variant);
+            assert_eq!(game.mode().name, format!("Invisible ({name})"));
+            assert!(game.update(None, Duration::from_millis(1)).is_ok());
+            assert_eq!(
+                game.state().mod_stats[utils::STACK_FADE_DELAY],
+                fade_delay.as_millis() as i64
+            );
+        }
+    }
+
+    #[test]
+    fn line_clears_reveal_the_stack_if_flashing() {
+        let game = Game::new(GameMode::marathon());
+        let (mut config, mut mode) = (game.config().clone(), game.mode().clone());
+        let mut state = game.state().clone();
+        state.time = Duration::from_secs(3);
+        let line_clear = ModifierPoint::AfterEvent(InternalEvent::LineClear);
+        let flash = Duration::from_millis(500);
+        let mut flashing = modifier(Duration::ZERO, Some(flash));
+        flashing(
+            &mut config,
+            &mut mode,
+            &mut state,
+            &mut Vec::new(),
+            &line_clear,
+        );
+        assert_eq!(
+            state.mod_stats[utils::STACK_REVEALED_UNTIL],
+            (state.time + flash).as_millis() as i64
+        );
+        let mut state = game.state().clone();
+        let mut fading = modifier(Duration::from_secs(1), None);
+        fading(
+            &mut config,
+            &mut mode,
+            &mut state,
+            &mut Vec::new(),
+            &line_clear,
+        );
+        assert!(!state.mod_stats.contains_key(utils::STACK_REVEALED_UNTIL));
+    }
+}
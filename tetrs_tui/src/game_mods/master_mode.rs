@@ -0,0 +1,318 @@
+use std::time::Duration;
+
+use tetrs_engine::{
+    Feedback, FeedbackEvents, FnGameMod, Game, GameConfig, GameMode, GameOver, GameState,
+    InternalEvent, ModifierPoint,
+};
+
+use crate::game_mods::utils;
+
+// Mod stats kept about a Master game (see `GameState::mod_stats`).
+const INTERNAL_GRADE: &str = "master_internal_grade";
+const GRADE_POINTS: &str = "master_grade_points";
+const COOLS: &str = "master_cools";
+const REGRETS: &str = "master_regrets";
+/// 0 before the credit roll, 1 during (or after surviving) it, -1 if the player topped out in it.
+const ROLL: &str = "master_roll";
+
+const SECTION_LINES: usize = 10;
+/// Section times at or below which a section is 'cool', by section.
+const COOL_SECS: [u64; 10] = [32, 32, 30, 30, 28, 28, 26, 26, 24, 24];
+/// Section times above which a section is a 'regret', by section.
+const REGRET_SECS: [u64; 10] = [60, 60, 55, 55, 50, 50, 45, 45, 40, 40];
+const ROLL_DURATION: Duration = Duration::from_secs(55);
+/// The highest grade reachable without the credit roll (S9).
+const MAX_GRADE: i64 = 17;
+
+/// Formats a grade as shown to the player: 9 (lowest) to 1, then S1 to S9 and GM.
+pub fn fmt_grade(grade: i64) -> String {
+    match grade {
+        ..=8 => (9 - grade.max(0)).to_string(),
+        9..=17 => format!("S{}", grade - 8),
+        _ => "GM".to_string(),
+    }
+}
+
+/// The grade achieved in a Master game so far (0 being grade 9, 18 being GM).
+///
+/// Surviving the credit roll raises the grade by one; only a player at S9 without any regrets is
+/// awarded GM.
+pub fn grade(state: &GameState) -> i64 {
+    let stat = |name: &str| state.mod_stats.get(name).copied().unwrap_or(0);
+    let grade = (stat(INTERNAL_GRADE) + stat(COOLS) - stat(REGRETS)).clamp(0, MAX_GRADE);
+    let roll_survived = stat(ROLL) == 1 && state.end.is_some_and(|end| end.is_ok());
+    if !roll_survived {
+        grade
+    } else if grade == MAX_GRADE && stat(REGRETS) == 0 {
+        MAX_GRADE + 1
+    } else {
+        (grade + 1).min(MAX_GRADE)
+    }
+}
+
+/// Short account of how a grade came about, e.g. `"S4 (2 cool, 1 regret, roll cleared)"`.
+pub fn grade_details(state: &GameState) -> String {
+    let stat = |name: &str| state.mod_stats.get(name).copied().unwrap_or(0);
+    let roll = match stat(ROLL) {
+        0 => "",
+        -1 => ", roll failed",
+        _ if state.end.is_some_and(|end| end.is_ok()) => ", roll cleared",
+        _ => ", in roll",
+    };
+    format!(
+        "{} ({} cool, {} regret{})",
+        fmt_grade(grade(state)),
+        stat(COOLS),
+        stat(REGRETS),
+        roll
+    )
+}
+
+/// Points awarded for a line clear, given the current internal grade, combo and section.
+fn grade_points(lines: usize, internal_grade: i64, combo: u32, section: usize) -> i64 {
+    let base = [10, 20, 40, 60][lines.clamp(1, 4) - 1] * 10 / (10 + internal_grade);
+    let combo_bonus = if lines == 1 {
+        (0.05 * f64::from(combo.saturating_sub(1))).min(0.5)
+    } else {
+        (0.1 * f64::from(combo.saturating_sub(1))).min(1.0)
+    };
+    let level_multiplier = 1 + section as i64 * 4 / 10;
+    (base.max(1) as f64 * (1.0 + combo_bonus)).ceil() as i64 * level_multiplier
+}
+
+/// How a finished section is judged by its time.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum SectionVerdict {
+    /// Fast enough, and not much slower than the previous section.
+    Cool,
+    /// Too slow.
+    Regret,
+    Neither,
+}
+
+/// Judges a section by its time and that of the previous section, if any.
+fn judge_section(
+    section: usize,
+    section_time: Duration,
+    prev_section_time: Option<Duration>,
+) -> SectionVerdict {
+    let cool = section_time <= Duration::from_secs(COOL_SECS[section])
+        && prev_section_time
+            .is_none_or(|prev_time| section_time <= prev_time + Duration::from_secs(2));
+    if cool {
+        SectionVerdict::Cool
+    } else if section_time > Duration::from_secs(REGRET_SECS[section]) {
+        SectionVerdict::Regret
+    } else {
+        SectionVerdict::Neither
+    }
+}
+
+/// How long it takes for one grade point to decay while not comboing.
+fn decay_interval(internal_grade: i64) -> Duration {
+    Duration::from_millis(2000u64.saturating_sub(100 * internal_grade as u64).max(250))
+}
+
+pub fn modifier(credit_roll: bool) -> FnGameMod {
+    let mut section_start = Duration::ZERO;
+    let mut prev_section_time: Option<Duration> = None;
+    let mut decay_time = Duration::ZERO;
+    let mut last_time = Duration::ZERO;
+    let mut lines_to_clear = 0;
+    Box::new(
+        move |_config: &mut GameConfig,
+              mode: &mut GameMode,
+              state: &mut GameState,
+              feedback_events: &mut FeedbackEvents,
+              modifier_point: &ModifierPoint| {
+            let in_roll = state.mod_stats.get(ROLL).is_some_and(|roll| *roll != 0);
+            // Grade points decay while no combo is going on.
+            let elapsed = state.time.saturating_sub(last_time);
+            last_time = state.time;
+            if !in_roll && state.consecutive_line_clears == 0 {
+                let internal_grade = state.mod_stats.get(INTERNAL_GRADE).copied().unwrap_or(0);
+                let points = state.mod_stats.entry(GRADE_POINTS.to_string()).or_insert(0);
+                decay_time += elapsed;
+                while decay_time >= decay_interval(internal_grade) {
+                    decay_time -= decay_interval(internal_grade);
+                    *points = (*points - 1).max(0);
+                }
+            } else {
+                decay_time = Duration::ZERO;
+            }
+            match modifier_point {
+                ModifierPoint::BeforeEvent(InternalEvent::LineClear) => {
+                    lines_to_clear = state
+                        .board
+                        .iter()
+                        .filter(|line| line.iter().all(|cell| cell.is_some()))
+                        .count();
+                }
+                ModifierPoint::AfterEvent(InternalEvent::LineClear) if !in_roll => {
+                    let section = (state.lines_cleared - lines_to_clear) / SECTION_LINES;
+                    // Award grade points.
+                    let internal_grade = state.mod_stats.get(INTERNAL_GRADE).copied().unwrap_or(0);
+                    let points = state.mod_stats.entry(GRADE_POINTS.to_string()).or_insert(0);
+                    *points += grade_points(
+                        lines_to_clear,
+                        internal_grade,
+                        state.consecutive_line_clears,
+                        section,
+                    );
+                    if *points >= 100 && internal_grade < MAX_GRADE {
+                        *points = 0;
+                        state
+                            .mod_stats
+                            .insert(INTERNAL_GRADE.to_string(), internal_grade + 1);
+                        feedback_events.push((
+                            state.time,
+                            Feedback::Message(format!("Grade up: {}", fmt_grade(grade(state)))),
+                        ));
+                    }
+                    // Evaluate finished section.
+                    if state.lines_cleared / SECTION_LINES > section && section < COOL_SECS.len() {
+                        let section_time = state.time.saturating_sub(section_start);
+                        section_start = state.time;
+                        let verdict = match judge_section(section, section_time, prev_section_time)
+                        {
+                            SectionVerdict::Cool => {
+                                *state.mod_stats.entry(COOLS.to_string()).or_insert(0) += 1;
+                                " COOL!!"
+                            }
+                            SectionVerdict::Regret => {
+                                *state.mod_stats.entry(REGRETS.to_string()).or_insert(0) += 1;
+                                " REGRET!"
+                            }
+                            SectionVerdict::Neither => "",
+                        };
+                        prev_section_time = Some(section_time);
+                        feedback_events.push((
+                            state.time,
+                            Feedback::Message(format!(
+                                "Section {}: {:.1}s{verdict}",
+                                section + 1,
+                                section_time.as_secs_f64()
+                            )),
+                        ));
+                    }
+                    // Start the credit roll instead of ending the game.
+                    let last_line = mode
+                        .limits
+                        .lines
+                        .is_some_and(|(_, lines)| lines <= state.lines_cleared);
+                    if credit_roll && last_line {
+                        mode.limits.lines = None;
+                        mode.limits.time = Some((true, state.time + ROLL_DURATION));
+                        state.mod_stats.insert(ROLL.to_string(), 1);
+                        state
+                            .mod_stats
+                            .insert(utils::INVISIBLE_STACK.to_string(), 1);
+                        feedback_events.push((
+                            state.time,
+                            Feedback::Message("Credit roll - survive!".to_string()),
+                        ));
+                    }
+                }
+                // Topping out during the credit roll still completes the game.
+                ModifierPoint::AfterEvent(_)
+                    if in_roll
+                        && matches!(
+                            state.end,
+                            Some(Err(GameOver::LockOut | GameOver::BlockOut))
+                        ) =>
+                {
+                    state.end = Some(Ok(()));
+                    state.mod_stats.insert(ROLL.to_string(), -1);
+                    state.mod_stats.remove(utils::INVISIBLE_STACK);
+                }
+                _ => {}
+            }
+        },
+    )
+}
+
+pub fn new_game(credit_roll: bool) -> Game {
+    let mut game = Game::new(GameMode::master());
+    unsafe { game.add_modifier(modifier(credit_roll)) };
+    game
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_with(stats: &[(&str, i64)], end: Option<Result<(), GameOver>>) -> GameState {
+        let mut state = Game::new(GameMode::master()).state().clone();
+        for (name, value) in stats {
+            state.mod_stats.insert(name.to_string(), *value);
+        }
+        state.end = end;
+        state
+    }
+
+    #[test]
+    fn grades_are_formatted_from_9_to_s9_and_gm() {
+        let grades = (-1..=19).map(fmt_grade).collect::<Vec<_>>();
+        assert_eq!(grades[..3], ["9", "9", "8"]);
+        assert_eq!(grades[9..11], ["1", "S1"]);
+        assert_eq!(grades[18..], ["S9", "GM", "GM"]);
+    }
+
+    #[test]
+    fn grades_count_cools_and_regrets_and_the_credit_roll() {
+        let grade_of = |stats: &[(&str, i64)], end| grade(&state_with(stats, end));
+        assert_eq!(grade_of(&[], None), 0);
+        assert_eq!(
+            grade_of(&[(INTERNAL_GRADE, 10), (COOLS, 3), (REGRETS, 1)], None),
+            12
+        );
+        assert_eq!(grade_of(&[(INTERNAL_GRADE, 1), (REGRETS, 4)], None), 0);
+        assert_eq!(
+            grade_of(&[(INTERNAL_GRADE, 17), (COOLS, 5)], None),
+            MAX_GRADE
+        );
+        // Surviving the credit roll raises the grade by one, to GM only without regrets.
+        let roll_survived = Some(Ok(()));
+        assert_eq!(
+            grade_of(&[(INTERNAL_GRADE, 12), (ROLL, 1)], roll_survived),
+            13
+        );
+        assert_eq!(
+            grade_of(&[(INTERNAL_GRADE, 17), (ROLL, 1)], roll_survived),
+            MAX_GRADE + 1
+        );
+        assert_eq!(
+            grade_of(
+                &[(INTERNAL_GRADE, 18), (REGRETS, 1), (ROLL, 1)],
+                roll_survived
+            ),
+            MAX_GRADE
+        );
+        assert_eq!(
+            grade_of(&[(INTERNAL_GRADE, 17), (ROLL, -1)], roll_survived),
+            MAX_GRADE
+        );
+    }
+
+    #[test]
+    fn sections_are_cool_if_fast_and_regrets_if_slow() {
+        let secs = Duration::from_secs;
+        assert_eq!(judge_section(0, secs(32), None), SectionVerdict::Cool);
+        assert_eq!(judge_section(0, secs(33), None), SectionVerdict::Neither);
+        assert_eq!(judge_section(0, secs(61), None), SectionVerdict::Regret);
+        assert_eq!(
+            judge_section(9, secs(24), Some(secs(30))),
+            SectionVerdict::Cool
+        );
+        assert_eq!(judge_section(9, secs(41), None), SectionVerdict::Regret);
+        // A fast section is not cool if it is much slower than the previous one.
+        assert_eq!(
+            judge_section(2, secs(29), Some(secs(27))),
+            SectionVerdict::Cool
+        );
+        assert_eq!(
+            judge_section(2, secs(30), Some(secs(27))),
+            SectionVerdict::Neither
+        );
+    }
+}
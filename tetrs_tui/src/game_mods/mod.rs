@@ -1,5 +1,13 @@
+pub mod board_layouts;
 pub mod cheese_mode;
 pub mod combo_mode;
+pub mod daily_mode;
 pub mod descent_mode;
+pub mod dig_race_mode;
+pub mod invisible_mode;
+pub mod master_mode;
+pub mod mode_files;
 pub mod puzzle_mode;
+pub mod survival_mode;
 pub mod utils;
+pub mod versus_mode;
@@ -0,0 +1,610 @@
+use std::{
+    num::{NonZeroU32, NonZeroUsize},
+    path::Path,
+    time::Duration,
+};
+
+use serde_with::{serde_as, DurationMilliSeconds};
+use tetrs_engine::{
+    piece_generation::TetrominoSource, FnGameMod, Game, GameConfig, GameMode, LevelProgression,
+    LimitCombination, Limits, Line, RotationSystem, SpinDetection, Tetromino,
+};
+
+use crate::{
+    game_mods::{self, utils},
+    mode_registry,
+};
+
+/// A game mode defined by a TOML or JSON file.
+///
+/// Example (`sprint_20.toml`):
+/// ```toml
+/// name = "Sprint 20"
+/// description = "20 lines, no preview, dirty start."
+/// start_level = 3
+/// pieces = "IJLOSTZ"
+/// board = [
+///   "X.XXXXXXXX",
+///   "XXXX.XXXXX",
+/// ]
+///
+/// [[limits]]
+/// lines = 20
+///
+/// [[limits]]
+/// time = 120.0
+/// win = false
+///
+/// [[limits]]
+/// stack_height = 18
+/// win = false
+///
+/// [config]
+/// preview_count = 0
+/// line_clear_delay = 0
+///
+/// [[modifiers]]
+/// type = "cheese"
+/// lines = 4
+/// ```
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModeFile {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_start_level")]
+    start_level: NonZeroU32,
+    #[serde(default)]
+    increment_level: bool,
+    /// How the level is incremented (every 10 lines by default).
+    #[serde(default)]
+    level_progression: Option<LevelProgressionEntry>,
+    /// Whether pieces are twice as large (TGM-style 'Big' mode).
+    #[serde(default)]
+    big: bool,
+    /// Whether blocks fall in connected groups after line clears, possibly chaining clears.
+    #[serde(default)]
+    cascade: bool,
+    /// Whether line clears fill a zone meter, which suspends gravity while it is active.
+    #[serde(default)]
+    zone: bool,
+    #[serde(default)]
+    limits: Vec<LimitEntry>,
+    /// Whether all positive (resp. negative) limits must be reached for the game to end.
+    #[serde(default)]
+    limit_combination: LimitCombinationEntry,
+    /// The lowest rows of the starting board, from top to bottom.
+    #[serde(default)]
+    board: Vec<String>,
+    /// A sequence of pieces that is repeated instead of generating random pieces.
+    #[serde(default)]
+    pieces: Option<String>,
+    /// Makes the game and its garbage modifiers (except cheese) behave the same every time.
+    #[serde(default)]
+    seed: Option<u64>,
+    #[serde(default)]
+    config: ConfigOverrides,
+    #[serde(default)]
+    modifiers: Vec<BuiltinModifier>,
+}
+
+/// One or several limits under which the game ends, successfully if `win` (the default).
+///
+/// Each kind of limit may only be set by one entry.
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LimitEntry {
+    /// In seconds.
+    time: Option<f64>,
+    pieces: Option<u32>,
+    lines: Option<usize>,
+    level: Option<NonZeroU32>,
+    score: Option<u64>,
+    holes: Option<usize>,
+    stack_height: Option<usize>,
+    pieces_without_clear: Option<u32>,
+    /// In seconds.
+    piece_time: Option<f64>,
+    garbage_lines: Option<usize>,
+    #[serde(default = "default_win")]
+    win: bool,
+}
+
+/// Overrides of the player's [`GameConfig`]; all durations are given in milliseconds.
+#[serde_as]
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigOverrides {
+    rotation_system: Option<RotationSystem>,
+    piece_generator: Option<PieceGenerator>,
+    preview_count: Option<usize>,
+    #[serde_as(as = "Option<DurationMilliSeconds<u64>>")]
+    #[serde(default)]
+    delayed_auto_shift: Option<Duration>,
+    #[serde_as(as = "Option<DurationMilliSeconds<u64>>")]
+    #[serde(default)]
+    auto_repeat_rate: Option<Duration>,
+    soft_drop_factor: Option<f64>,
+    #[serde_as(as = "Option<DurationMilliSeconds<u64>>")]
+    #[serde(default)]
+    hard_drop_delay: Option<Duration>,
+    #[serde_as(as = "Option<DurationMilliSeconds<u64>>")]
+    #[serde(default)]
+    ground_time_max: Option<Duration>,
+    #[serde_as(as = "Option<DurationMilliSeconds<u64>>")]
+    #[serde(default)]
+    line_clear_delay: Option<Duration>,
+    #[serde_as(as = "Option<DurationMilliSeconds<u64>>")]
+    #[serde(default)]
+    appearance_delay: Option<Duration>,
+    no_soft_drop_lock: Option<bool>,
+    spin_detection: Option<SpinDetection>,
+    /// In frames per second.
+    tick_rate: Option<NonZeroU32>,
+}
+
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum LevelProgressionEntry {
+    Lines {
+        lines: NonZeroU32,
+    },
+    VariableGoal,
+    Sections {
+        points: NonZeroU32,
+    },
+    Time {
+        /// In seconds.
+        interval: f64,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum LimitCombinationEntry {
+    #[default]
+    Any,
+    All,
+}
+
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PieceGenerator {
+    Uniform,
+    Bag,
+    Recency,
+    BalanceRelative,
+    Classic,
+}
+
+/// The game mods of the built-in modes which can be enabled by mode files.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BuiltinModifier {
+    /// Garbage lines to dig through, as in Cheese mode (endless if no `lines` are given).
+    Cheese {
+        lines: Option<NonZeroUsize>,
+        #[serde(default = "default_gap_size")]
+        gap_size: usize,
+    },
+    /// A four-wide well, as in Combo mode (the game ends once a piece clears no line).
+    Combo {
+        #[serde(default)]
+        layout: u16,
+    },
+    /// Messages about which pieces are likely to come next (only for the recency generator).
+    TetrominoLikelihood,
+    /// Locked pieces disappear after `fade_delay` seconds (immediately by default), with the stack
+    /// revealed for `flash` seconds after line clears.
+    Invisible {
+        #[serde(default)]
+        fade_delay: f64,
+        flash: Option<f64>,
+    },
+    /// Garbage lines rising periodically, faster with every level, as in Survival mode.
+    Survival,
+    /// Messy garbage lines to dig through, as in Dig Race mode, whose holes move with the given
+    /// probability from one line to the next.
+    DigRace {
+        lines: NonZeroUsize,
+        #[serde(default = "default_hole_change")]
+        hole_change: f64,
+    },
+}
+
+impl LimitEntry {
+    /// The names of the kinds of limits set by the entry.
+    fn kinds(&self) -> Vec<&'static str> {
+        let Self {
+            time,
+            pieces,
+            lines,
+            level,
+            score,
+            holes,
+            stack_height,
+            pieces_without_clear,
+            piece_time,
+            garbage_lines,
+            win: _,
+        } = self;
+        [
+            ("time", time.is_some()),
+            ("pieces", pieces.is_some()),
+            ("lines", lines.is_some()),
+            ("level", level.is_some()),
+            ("score", score.is_some()),
+            ("holes", holes.is_some()),
+            ("stack_height", stack_height.is_some()),
+            ("pieces_without_clear", pieces_without_clear.is_some()),
+            ("piece_time", piece_time.is_some()),
+            ("garbage_lines", garbage_lines.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(kind, set)| set.then_some(kind))
+        .collect()
+    }
+}
+
+fn default_start_level() -> NonZeroU32 {
+    NonZeroU32::MIN
+}
+
+fn default_win() -> bool {
+    true
+}
+
+fn default_gap_size() -> usize {
+    1
+}
+
+fn default_hole_change() -> f64 {
+    0.3
+}
+
+impl ModeFile {
+    /// Loads all `.toml` and `.json` mode files in a directory, sorted by file name.
+    ///
+    /// Files which could not be loaded, or whose name is taken already, are reported as error
+    /// messages. The names of the modes loaded are added to those taken.
+    pub fn load_dir(dir: &Path, taken: &mut Vec<String>) -> (Vec<Self>, Vec<String>) {
+        utils::load_data_files(dir, |mode_file: &Self| {
+            mode_file.check(taken)?;
+            taken.push(mode_file.name.clone());
+            Ok(())
+        })
+    }
+
+    /// Checks whether the mode is playable and does not take the name of another mode.
+    pub fn check(&self, taken: &[String]) -> Result<(), String> {
+        mode_registry::check_mode_name(&self.name, taken)?;
+        self.board_lines()?;
+        self.piece_sequence()?;
+        let mut kinds = Vec::new();
+        for kind in self.limits.iter().flat_map(LimitEntry::kinds) {
+            if kinds.contains(&kind) {
+                return Err(format!("limit {kind:?} is given more than once"));
+            }
+            kinds.push(kind);
+        }
+        let level_interval = match self.level_progression {
+            Some(LevelProgressionEntry::Time { interval }) => Some(interval),
+            _ => None,
+        };
+        let modifier_delays = self.modifiers.iter().flat_map(|modifier| match modifier {
+            BuiltinModifier::Invisible { fade_delay, flash } => [Some(*fade_delay), *flash],
+            _ => [None, None],
+        });
+        for secs in self
+            .limits
+            .iter()
+            .flat_map(|entry| [entry.time, entry.piece_time])
+            .chain([level_interval])
+            .chain(modifier_delays)
+            .flatten()
+        {
+            seconds(secs)?;
+        }
+        Ok(())
+    }
+
+    /// Starts a game of the mode, from the given seed unless the mode has one of its own.
+    pub fn new_game(&self, seed: u64) -> Game {
+        let mut limits = Limits::default();
+        for entry in &self.limits {
+            let win = entry.win;
+            if let Some(secs) = entry.time {
+                limits.time = Some((win, seconds(secs).unwrap_or_default()));
+            }
+            if let Some(pieces) = entry.pieces {
+                limits.pieces = Some((win, pieces));
+            }
+            if let Some(lines) = entry.lines {
+                limits.lines = Some((win, lines));
+            }
+            if let Some(level) = entry.level {
+                limits.level = Some((win, level));
+            }
+            if let Some(score) = entry.score {
+                limits.score = Some((win, score));
+            }
+            if let Some(holes) = entry.holes {
+                limits.holes = Some((win, holes));
+            }
+            if let Some(height) = entry.stack_height {
+                limits.stack_height = Some((win, height));
+            }
+            if let Some(pieces) = entry.pieces_without_clear {
+                limits.pieces_without_clear = Some((win, pieces));
+            }
+            if let Some(secs) = entry.piece_time {
+                limits.piece_time = Some((win, seconds(secs).unwrap_or_default()));
+            }
+            if let Some(lines) = entry.garbage_lines {
+                limits.garbage_lines = Some((win, lines));
+            }
+        }
+        limits.combination = match self.limit_combination {
+            LimitCombinationEntry::Any => LimitCombination::Any,
+            LimitCombinationEntry::All => LimitCombination::All,
+        };
+        let seed = self.seed.unwrap_or(seed);
+        let mode = GameMode {
+            name: self.name.clone(),
+            start_level: self.start_level,
+            increment_level: self.increment_level,
+            level_progression: match self.level_progression {
+                None => LevelProgression::default(),
+                Some(LevelProgressionEntry::Lines { lines }) => LevelProgression::Lines(lines),
+                Some(LevelProgressionEntry::VariableGoal) => LevelProgression::VariableGoal,
+                Some(LevelProgressionEntry::Sections { points }) => {
+                    LevelProgression::Sections(points)
+                }
+                Some(LevelProgressionEntry::Time { interval }) => {
+                    LevelProgression::Time(seconds(interval).unwrap_or_default())
+                }
+            },
+            big: self.big,
+            cascade: self.cascade,
+            zone: self.zone,
+            limits,
+        };
+        let mut game = Game::with_seed(mode, GameConfig::default(), seed);
+        if let Ok(lines) = self.board_lines() {
+            if !lines.is_empty() {
+                unsafe { game.add_modifier(starting_board(lines)) };
+            }
+        }
+        for modifier in &self.modifiers {
+            let game_mod = match modifier {
+                BuiltinModifier::Cheese { lines, gap_size } => {
+                    game_mods::cheese_mode::modifier(*lines, *gap_size)
+                }
+                BuiltinModifier::Combo { layout } => game_mods::combo_mode::modifier(*layout),
+                BuiltinModifier::TetrominoLikelihood => {
+                    game_mods::utils::display_tetromino_likelihood()
+                }
+                BuiltinModifier::Invisible { fade_delay, flash } => {
+                    game_mods::invisible_mode::modifier(
+                        seconds(*fade_delay).unwrap_or_default(),
+                        flash.map(|secs| seconds(secs).unwrap_or_default()),
+                    )
+                }
+                BuiltinModifier::Survival => game_mods::survival_mode::modifier(seed),
+                BuiltinModifier::DigRace { lines, hole_change } => {
+                    game_mods::dig_race_mode::modifier(seed, *lines, *hole_change)
+                }
+            };
+            unsafe { game.add_modifier(game_mod) };
+        }
+        game
+    }
+
+    /// Whether the mode's games are plain games of the engine, without any game mods.
+    pub fn is_plain(&self) -> bool {
+        self.board.is_empty() && self.modifiers.is_empty()
+    }
+
+    /// Applies the mode's overrides on top of the player's configuration.
+    pub fn configure(&self, config: &mut GameConfig) {
+        let ConfigOverrides {
+            rotation_system,
+            piece_generator,
+            preview_count,
+            delayed_auto_shift,
+            auto_repeat_rate,
+            soft_drop_factor,
+            hard_drop_delay,
+            ground_time_max,
+            line_clear_delay,
+            appearance_delay,
+            no_soft_drop_lock,
+            spin_detection,
+            tick_rate,
+        } = self.config.clone();
+        if let Some(rotation_system) = rotation_system {
+            config.rotation_system = rotation_system;
+        }
+        if let Some(piece_generator) = piece_generator {
+            config.tetromino_generator = match piece_generator {
+                PieceGenerator::Uniform => TetrominoSource::uniform(),
+                PieceGenerator::Bag => TetrominoSource::bag(),
+                PieceGenerator::Recency => TetrominoSource::recency(),
+                PieceGenerator::BalanceRelative => TetrominoSource::balance_relative(),
+                PieceGenerator::Classic => TetrominoSource::classic(),
+            };
+        }
+        if let Ok(Some(pattern)) = self.piece_sequence() {
+            config.tetromino_generator = TetrominoSource::cycle(pattern);
+        }
+        config.preview_count = preview_count.unwrap_or(config.preview_count);
+        config.delayed_auto_shift = delayed_auto_shift.unwrap_or(config.delayed_auto_shift);
+        config.auto_repeat_rate = auto_repeat_rate.unwrap_or(config.auto_repeat_rate);
+        config.soft_drop_factor = soft_drop_factor.unwrap_or(config.soft_drop_factor);
+        config.hard_drop_delay = hard_drop_delay.unwrap_or(config.hard_drop_delay);
+        config.ground_time_max = ground_time_max.unwrap_or(config.ground_time_max);
+        config.line_clear_delay = line_clear_delay.unwrap_or(config.line_clear_delay);
+        config.appearance_delay = appearance_delay.unwrap_or(config.appearance_delay);
+        config.no_soft_drop_lock = no_soft_drop_lock.unwrap_or(config.no_soft_drop_lock);
+        config.spin_detection = spin_detection.unwrap_or(config.spin_detection);
+        config.tick_rate = tick_rate.or(config.tick_rate);
+    }
+
+    /// Parses the starting board, bottom line first.
+    fn board_lines(&self) -> Result<Vec<Line>, String> {
+        utils::parse_board(&self.board)
+    }
+
+    fn piece_sequence(&self) -> Result<Option<Vec<Tetromino>>, String> {
+        let Some(pieces) = &self.pieces else {
+            return Ok(None);
+        };
+        let pattern = utils::parse_pieces(pieces)?;
+        if pattern.is_empty() {
+            return Err("piece sequence is empty".to_string());
+        }
+        Ok(Some(pattern))
+    }
+}
+
+/// Converts a number of seconds given in a mode file, negative numbers counting as zero.
+fn seconds(secs: f64) -> Result<Duration, String> {
+    if !secs.is_finite() {
+        return Err(format!("{secs} is not a number of seconds"));
+    }
+    Duration::try_from_secs_f64(secs.max(0.0)).map_err(|e| format!("{secs} seconds: {e}"))
+}
+
+fn starting_board(lines: Vec<Line>) -> FnGameMod {
+    let mut init = false;
+    Box::new(move |_, _, state, _, _| {
+        if !init {
+            for (line, starting_line) in state.board.iter_mut().zip(&lines) {
+                *line = *starting_line;
+            }
+            init = true;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mode_file(name: &str) -> ModeFile {
+        toml::from_str(&format!("name = {name:?}")).unwrap()
+    }
+
+    #[test]
+    fn built_in_mode_names_are_rejected() {
+        for name in ["Marathon", "Invisible", "Invisible (fading)", "Combo (Bot)"] {
+            assert!(mode_file(name).check(&[]).is_err(), "{name}");
+        }
+        assert!(mode_file("Marathon 2").check(&[]).is_ok());
+        assert!(mode_file("Marathon 2")
+            .check(&["Marathon 2".to_string()])
+            .is_err());
+    }
+
+    #[test]
+    fn mode_files_set_up_games_as_given() {
+        let mode_file: ModeFile = toml::from_str(
+            r#"
+            name = "Dig 20"
+            pieces = "TL"
+            board = [
+              "X.XXXXXXXX",
+              "XXXX.XXXXX",
+            ]
+            limit_combination = "all"
+
+            [[limits]]
+            lines = 20
+
+            [[limits]]
+            time = 120.0
+            stack_height = 18
+            win = false
+
+            [config]
+            preview_count = 2
+            line_clear_delay = 0
+            rotation_system = "Super"
+
+            [[modifiers]]
+            type = "tetromino_likelihood"
+            "#,
+        )
+        .unwrap();
+        assert!(mode_file.check(&[]).is_ok());
+        let mut game = mode_file.new_game(0);
+        assert_eq!(
+            game.mode().limits,
+            Limits {
+                lines: Some((true, 20)),
+                time: Some((false, Duration::from_secs(120))),
+                stack_height: Some((false, 18)),
+                combination: LimitCombination::All,
+                ..Default::default()
+            }
+        );
+        let player_config = GameConfig {
+            delayed_auto_shift: Duration::from_millis(100),
+            ..GameConfig::default()
+        };
+        game.config_mut().clone_from(&player_config);
+        mode_file.configure(game.config_mut());
+        let config = game.config();
+        assert_eq!(config.preview_count, 2);
+        assert_eq!(config.line_clear_delay, Duration::ZERO);
+        assert_eq!(config.rotation_system, RotationSystem::Super);
+        assert_eq!(config.delayed_auto_shift, player_config.delayed_auto_shift);
+        assert_eq!(config.appearance_delay, player_config.appearance_delay);
+        assert!(game.update(None, Duration::from_millis(1)).is_ok());
+        let state = game.state();
+        let rows = state.board[..2]
+            .iter()
+            .map(|line| line.iter().map(|tile| tile.is_some()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let row = |hole| (0..Game::WIDTH).map(|x| x != hole).collect::<Vec<_>>();
+        assert_eq!(rows, [row(4), row(1)]);
+        assert!(state.board[2..].iter().flatten().all(|tile| tile.is_none()));
+        let (piece, _) = state.active_piece_data.unwrap();
+        assert_eq!(piece.shape, Tetromino::T);
+        assert_eq!(
+            state.next_pieces.iter().copied().collect::<Vec<_>>(),
+            [Tetromino::L, Tetromino::T]
+        );
+    }
+
+    #[test]
+    fn limits_of_the_same_kind_are_rejected() {
+        let mode_file: ModeFile = toml::from_str(
+            "name = \"Timed\"\n[[limits]]\ntime = 60.0\n[[limits]]\nlines = 40\ntime = 90.0",
+        )
+        .unwrap();
+        assert!(mode_file.check(&[]).is_err());
+        let mode_file: ModeFile =
+            toml::from_str("name = \"Timed\"\n[[limits]]\ntime = 60.0\n[[limits]]\nlines = 40")
+                .unwrap();
+        assert!(mode_file.check(&[]).is_ok());
+    }
+
+    #[test]
+    fn durations_out_of_range_are_rejected() {
+        for (key, secs) in [("time", "inf"), ("piece_time", "nan"), ("time", "1e300")] {
+            let mode_file: ModeFile =
+                toml::from_str(&format!("name = \"Timed\"\n[[limits]]\n{key} = {secs}")).unwrap();
+            assert!(mode_file.check(&[]).is_err(), "{key} = {secs}");
+        }
+        let mode_file: ModeFile = toml::from_str(
+            "name = \"Timed\"\n[[modifiers]]\ntype = \"invisible\"\nfade_delay = inf",
+        )
+        .unwrap();
+        assert!(mode_file.check(&[]).is_err());
+        let mode_file: ModeFile =
+            toml::from_str("name = \"Timed\"\n[[limits]]\ntime = -1.0").unwrap();
+        assert!(mode_file.check(&[]).is_ok());
+    }
+}
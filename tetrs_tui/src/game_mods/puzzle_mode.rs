@@ -1,26 +1,168 @@
-use std::{
-    collections::VecDeque,
-    num::{NonZeroU32, NonZeroU8},
-};
+use std::{collections::VecDeque, num::NonZeroU32, path::Path};
 
 use tetrs_engine::{
     Feedback, FeedbackEvents, FnGameMod, Game, GameConfig, GameMode, GameOver, GameState,
     InternalEvent, Limits, ModifierPoint, Tetromino,
 };
 
+use crate::{game_mods::utils, mode_registry};
+
 const MAX_STAGE_ATTEMPTS: usize = 5;
 const PUZZLE_SPEED_LEVEL: u32 = 1;
 
+/// A collection of puzzle stages, which can be shared as a TOML or JSON file.
+///
+/// Example (`spins.toml`):
+/// ```toml
+/// name = "Spins"
+/// description = "a few spins to warm up."
+///
+/// [[puzzles]]
+/// name = "T-spin"
+/// board = [
+///   "XXXX....XX",
+///   "XXX...XXXX",
+///   "XXXX.XXXXX",
+/// ]
+/// pieces = "TI"
+///
+/// [[puzzles]]
+/// name = "S-spin"
+/// board = [
+///   "XXXX..XXXX",
+///   "XXX..XXXXX",
+/// ]
+/// pieces = "S"
+/// attempts = 3
+/// goal = { lines = 2 }
+/// ```
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PuzzlePack {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub puzzles: Vec<Puzzle>,
+}
+
+/// A single stage of a [`PuzzlePack`].
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Puzzle {
+    pub name: String,
+    /// The lowest rows of the board, from top to bottom (see [`utils::parse_board`]).
+    pub board: Vec<String>,
+    /// The pieces available to solve the puzzle, such as `"TLJ"`.
+    pub pieces: String,
+    #[serde(default = "default_attempts")]
+    pub attempts: usize,
+    #[serde(default)]
+    pub goal: PuzzleGoal,
+}
+
+/// What has to be achieved with the given pieces to solve a [`Puzzle`].
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PuzzleGoal {
+    /// Clear the entire board.
+    #[default]
+    PerfectClear,
+    /// Clear at least this many lines.
+    Lines(usize),
+}
+
+fn default_attempts() -> usize {
+    MAX_STAGE_ATTEMPTS
+}
+
+impl PuzzlePack {
+    /// The 24 stages of the built-in Puzzle mode.
+    pub fn builtin() -> Self {
+        let puzzles = puzzle_list()
+            .into_iter()
+            .map(|(name, rows, pieces)| Puzzle {
+                name: name.to_string(),
+                board: rows
+                    .into_iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|b| if *b == b' ' { '.' } else { 'X' })
+                            .collect()
+                    })
+                    .collect(),
+                pieces: pieces.iter().map(|tet| format!("{tet:?}")).collect(),
+                attempts: MAX_STAGE_ATTEMPTS,
+                goal: PuzzleGoal::PerfectClear,
+            })
+            .collect();
+        Self {
+            name: "Puzzle".to_string(),
+            description: "24 stages of perfect clears!".to_string(),
+            puzzles,
+        }
+    }
+
+    /// Loads all `.toml` and `.json` puzzle packs in a directory, sorted by file name.
+    ///
+    /// Files which could not be loaded, or whose name is taken already, are reported as error
+    /// messages. The names of the packs loaded are added to those taken.
+    pub fn load_dir(dir: &Path, taken: &mut Vec<String>) -> (Vec<Self>, Vec<String>) {
+        utils::load_data_files(dir, |pack: &Self| {
+            pack.check(taken)?;
+            taken.push(pack.name.clone());
+            Ok(())
+        })
+    }
+
+    /// Checks whether the pack is playable and does not take the name of another mode.
+    pub fn check(&self, taken: &[String]) -> Result<(), String> {
+        mode_registry::check_mode_name(&self.name, taken)?;
+        if self.puzzles.is_empty() {
+            return Err("pack contains no puzzles".to_string());
+        }
+        for puzzle in &self.puzzles {
+            let name = &puzzle.name;
+            utils::parse_board(&puzzle.board).map_err(|e| format!("puzzle {name:?}: {e}"))?;
+            let pieces =
+                utils::parse_pieces(&puzzle.pieces).map_err(|e| format!("puzzle {name:?}: {e}"))?;
+            if pieces.is_empty() {
+                return Err(format!("puzzle {name:?} has no pieces"));
+            }
+            if puzzle.attempts == 0 {
+                return Err(format!("puzzle {name:?} allows no attempts"));
+            }
+        }
+        Ok(())
+    }
+}
+
 pub fn new_game() -> Game {
-    #[rustfmt::skip]
-    let puzzles = puzzle_list();
+    new_pack_game(&PuzzlePack::builtin())
+}
+
+/// Creates a game going through all stages of a pack, which has to pass [`PuzzlePack::check`].
+pub fn new_pack_game(pack: &PuzzlePack) -> Game {
+    let puzzles = pack
+        .puzzles
+        .iter()
+        .map(|puzzle| {
+            (
+                puzzle.name.clone(),
+                utils::parse_board(&puzzle.board).unwrap(),
+                VecDeque::from(utils::parse_pieces(&puzzle.pieces).unwrap()),
+                puzzle.attempts,
+                puzzle.goal,
+            )
+        })
+        .collect::<Vec<_>>();
     let puzzles_len = puzzles.len();
     let load_puzzle = move |state: &mut GameState,
                             attempt: usize,
                             current_puzzle_idx: usize,
                             feedback_events: &mut FeedbackEvents|
-          -> usize {
-        let (puzzle_name, puzzle_lines, puzzle_pieces) = &puzzles[current_puzzle_idx];
+          -> (usize, usize, PuzzleGoal) {
+        let (puzzle_name, puzzle_lines, puzzle_pieces, puzzle_attempts, puzzle_goal) =
+            &puzzles[current_puzzle_idx];
         // Game message.
         feedback_events.push((
             state.time,
@@ -33,7 +175,7 @@ pub fn new_game() -> Game {
             } else {
                 format!(
                     "{} ATT. LEFT ({})",
-                    MAX_STAGE_ATTEMPTS + 1 - attempt,
+                    puzzle_attempts + 1 - attempt,
                     puzzle_name.to_ascii_uppercase()
                 )
             }),
@@ -43,27 +185,21 @@ pub fn new_game() -> Game {
         // Load in pieces.
         for (puzzle_line, board_line) in puzzle_lines
             .iter()
-            .rev()
-            .map(|line| {
-                line.map(|b| {
-                    if b == b' ' {
-                        None
-                    } else {
-                        Some(unsafe { NonZeroU8::new_unchecked(254) })
-                    }
-                })
-            })
+            .copied()
             .chain(std::iter::repeat(Default::default()))
             .zip(state.board.iter_mut())
         {
             *board_line = puzzle_line;
         }
-        puzzle_pieces.len()
+        (puzzle_pieces.len(), *puzzle_attempts, *puzzle_goal)
     };
     let mut init = false;
     let mut current_puzzle_idx = 0;
     let mut current_puzzle_attempt = 1;
     let mut current_puzzle_piececnt_limit = 0;
+    let mut current_puzzle_lines_start = 0;
+    let mut current_puzzle_attempts = 0;
+    let mut current_puzzle_goal = PuzzleGoal::PerfectClear;
     let puzzle_mode: FnGameMod = Box::new(
         move |config: &mut GameConfig,
               _mode: &mut GameMode,
@@ -72,25 +208,32 @@ pub fn new_game() -> Game {
               modifier_point: &ModifierPoint| {
             let game_piececnt = usize::try_from(state.pieces_played.iter().sum::<u32>()).unwrap();
             if !init {
-                let piececnt = load_puzzle(
+                let (piececnt, attempts, goal) = load_puzzle(
                     state,
                     current_puzzle_attempt,
                     current_puzzle_idx,
                     feedback_events,
                 );
                 current_puzzle_piececnt_limit = game_piececnt + piececnt;
+                current_puzzle_lines_start = state.lines_cleared;
+                (current_puzzle_attempts, current_puzzle_goal) = (attempts, goal);
                 init = true;
             } else if matches!(
                 modifier_point,
                 ModifierPoint::BeforeEvent(InternalEvent::Spawn)
             ) && game_piececnt == current_puzzle_piececnt_limit
             {
-                let puzzle_done = state
-                    .board
-                    .iter()
-                    .all(|line| line.iter().all(|cell| cell.is_none()));
+                let puzzle_done = match current_puzzle_goal {
+                    PuzzleGoal::PerfectClear => state
+                        .board
+                        .iter()
+                        .all(|line| line.iter().all(|cell| cell.is_none())),
+                    PuzzleGoal::Lines(lines) => {
+                        state.lines_cleared - current_puzzle_lines_start >= lines
+                    }
+                };
                 // Run out of attempts, game over.
-                if !puzzle_done && current_puzzle_attempt == MAX_STAGE_ATTEMPTS {
+                if !puzzle_done && current_puzzle_attempt >= current_puzzle_attempts {
                     state.end = Some(Err(GameOver::ModeLimit));
                 } else {
                     if puzzle_done {
@@ -104,13 +247,15 @@ pub fn new_game() -> Game {
                         state.end = Some(Ok(()));
                     } else {
                         // Load in new puzzle.
-                        let piececnt = load_puzzle(
+                        let (piececnt, attempts, goal) = load_puzzle(
                             state,
                             current_puzzle_attempt,
                             current_puzzle_idx,
                             feedback_events,
                         );
                         current_puzzle_piececnt_limit = game_piececnt + piececnt;
+                        current_puzzle_lines_start = state.lines_cleared;
+                        (current_puzzle_attempts, current_puzzle_goal) = (attempts, goal);
                     }
                 }
             }
@@ -143,9 +288,13 @@ pub fn new_game() -> Game {
         },
     );
     let mut game = Game::new(GameMode {
-        name: "Puzzle".to_string(),
+        name: pack.name.clone(),
         start_level: NonZeroU32::MIN.saturating_add(1),
         increment_level: false,
+        level_progression: Default::default(),
+        big: false,
+        cascade: false,
+        zone: false,
         limits: Limits {
             level: Some((
                 true,
@@ -406,3 +555,79 @@ fn puzzle_list() -> [(&'static str, Vec<&'static [u8; 10]>, VecDeque<Tetromino>)
         //     ], VecDeque::from([Tetromino::T,Tetromino::O,Tetromino::L,Tetromino::I,Tetromino::J,Tetromino::Z,Tetromino::S])),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tetrs_engine::{Button, ButtonsPressed};
+
+    use super::*;
+
+    const PACK: &str = r#"
+        name = "Singles"
+
+        [[puzzles]]
+        name = "Single"
+        board = [
+          "XXX....XXX",
+          "XXXXXXXXX.",
+        ]
+        pieces = "I"
+        attempts = 1
+        goal = { lines = 1 }
+    "#;
+
+    /// Hard drops every piece where it spawns, until the game ends.
+    fn play(pack: &PuzzlePack) -> Result<(), GameOver> {
+        let mut game = new_pack_game(pack);
+        let mut time = Duration::from_millis(1);
+        while game.state().end.is_none() {
+            let mut buttons_pressed = ButtonsPressed::default();
+            buttons_pressed[Button::DropHard] = true;
+            for buttons_pressed in [buttons_pressed, ButtonsPressed::default()] {
+                time += Duration::from_millis(500);
+                assert!(game.update(Some(buttons_pressed), time).is_ok());
+            }
+        }
+        game.state().end.unwrap()
+    }
+
+    #[test]
+    fn packs_are_parsed_and_checked() {
+        let pack: PuzzlePack = toml::from_str(PACK).unwrap();
+        assert_eq!(pack.puzzles[0].goal, PuzzleGoal::Lines(1));
+        assert!(pack.check(&[]).is_ok());
+        assert!(pack.check(&["Singles".to_string()]).is_err());
+        assert!(toml::from_str::<PuzzlePack>(&PACK.replace("attempts", "tries")).is_err());
+        let defaults: PuzzlePack =
+            toml::from_str("name = \"P\"\n[[puzzles]]\nname = \"S\"\nboard = []\npieces = \"T\"")
+                .unwrap();
+        assert_eq!(defaults.puzzles[0].attempts, MAX_STAGE_ATTEMPTS);
+        assert_eq!(defaults.puzzles[0].goal, PuzzleGoal::PerfectClear);
+        for (from, to) in [
+            (r#"name = "Singles""#, r#"name = "Puzzle""#),
+            ("XXXXXXXXX.", "XXXXXXXXX.X"),
+            (r#"pieces = "I""#, r#"pieces = """#),
+            (r#"pieces = "I""#, r#"pieces = "W""#),
+            ("attempts = 1", "attempts = 0"),
+        ] {
+            let pack: PuzzlePack = toml::from_str(&PACK.replace(from, to)).unwrap();
+            assert!(pack.check(&[]).is_err(), "{to}");
+        }
+        let mut pack = pack;
+        pack.puzzles.clear();
+        assert!(pack.check(&[]).is_err());
+    }
+
+    #[test]
+    fn stages_are_solved_by_reaching_their_goal() {
+        let mut pack: PuzzlePack = toml::from_str(PACK).unwrap();
+        assert_eq!(play(&pack), Ok(()));
+        // The single leaves a line behind, so it is no perfect clear.
+        pack.puzzles[0].goal = PuzzleGoal::PerfectClear;
+        assert_eq!(play(&pack), Err(GameOver::ModeLimit));
+        pack.puzzles[0].board.pop();
+        assert_eq!(play(&pack), Ok(()));
+    }
+}
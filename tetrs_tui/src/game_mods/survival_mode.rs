@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use tetrs_engine::{
+    FeedbackEvents, FnGameMod, Game, GameConfig, GameMode, GameOver, GameState, InternalEvent,
+    Line, ModifierPoint,
+};
+
+use crate::game_mods::utils;
+
+/// How likely the hole of a rising garbage line is to differ from the one below it.
+pub const HOLE_CHANGE_PROBABILITY: f64 = 0.3;
+
+/// The time between two garbage lines rising at a given level: 10 seconds at level 1, getting
+/// 10% faster every level, but never faster than a line per second.
+pub fn rise_interval(level: u32) -> Duration {
+    Duration::from_secs(10)
+        .mul_f64(0.9f64.powi(i32::try_from(level.saturating_sub(1)).unwrap_or(i32::MAX)))
+        .max(Duration::from_secs(1))
+}
+
+/// Pushes a garbage line into the bottom of the board, ending the game if the stack is pushed out
+/// of the top.
+pub fn rise(state: &mut GameState, line: Line) {
+    state.insert_row(0, line);
+    if state
+        .pop_row()
+        .is_some_and(|line| line.iter().any(|cell| cell.is_some()))
+    {
+        state.end = Some(Err(GameOver::BlockOut));
+    }
+}
+
+/// Lets garbage lines rise periodically, as fast as the current level demands.
+///
+/// Lines which became due while a piece was in play rise right before the next piece appears.
+pub fn modifier(seed: u64) -> FnGameMod {
+    let mut line_source = utils::messy_garbage_lines(seed, HOLE_CHANGE_PROBABILITY);
+    let mut next_rise = None;
+    Box::new(
+        move |_config: &mut GameConfig,
+              _mode: &mut GameMode,
+              state: &mut GameState,
+              _feedback_events: &mut FeedbackEvents,
+              modifier_point: &ModifierPoint| {
+            if !matches!(
+                modifier_point,
+                ModifierPoint::BeforeEvent(InternalEvent::Spawn)
+            ) {
+                return;
+            }
+            let next_rise =
+                next_rise.get_or_insert_with(|| state.time + rise_interval(state.level.get()));
+            while *next_rise <= state.time && state.end.is_none() {
+                rise(state, line_source.next().unwrap());
+                *next_rise += rise_interval(state.level.get());
+            }
+        },
+    )
+}
+
+pub fn new_game(seed: u64) -> Game {
+    let mut game = Game::with_seed(
+        GameMode {
+            name: "Survival".to_string(),
+            limits: Default::default(),
+            ..GameMode::marathon()
+        },
+        GameConfig::default(),
+        seed,
+    );
+    unsafe { game.add_modifier(modifier(seed)) };
+    game
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board_after(seed: u64, time: Duration) -> Vec<Line> {
+        let mut game = new_game(seed);
+        assert!(game.update(None, time).is_ok());
+        game.state().board.clone()
+    }
+
+    #[test]
+    fn rising_lines_get_faster_with_the_level() {
+        assert_eq!(rise_interval(1), Duration::from_secs(10));
+        assert_eq!(rise_interval(2), Duration::from_secs(9));
+        assert_eq!(rise_interval(23), Duration::from_secs(1));
+        assert_eq!(rise_interval(100), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn same_seed_rises_the_same_lines() {
+        let time = Duration::from_secs(90);
+        let board = board_after(3, time);
+        let garbage_lines = board
+            .iter()
+            .take_while(|line| line.contains(&Some(Game::GARBAGE_TILE)))
+            .count();
+        assert!(garbage_lines > 0);
+        assert_eq!(board, board_after(3, time));
+        assert_ne!(board, board_after(4, time));
+    }
+}
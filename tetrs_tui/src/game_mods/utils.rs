@@ -1,8 +1,43 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use tetrs_engine::{
-    piece_generation::TetrominoSource, Feedback, FeedbackEvents, FnGameMod, GameConfig, GameMode,
-    GameState, InternalEvent, ModifierPoint, Tetromino,
+    piece_generation::TetrominoSource, Feedback, FeedbackEvents, FnGameMod, Game, GameConfig,
+    GameMode, GameState, InternalEvent, Line, ModifierPoint, Tetromino,
 };
 
+/// Name of a mod stat (see `GameState::mod_stats`) telling the renderer to hide the locked tiles
+/// of the board while it is nonzero.
+pub const INVISIBLE_STACK: &str = "invisible_stack";
+
+/// Name of a mod stat telling the renderer to hide tiles locked by the player once they have been
+/// on the board for that many milliseconds (see `GameState::board_lock_times`).
+pub const STACK_FADE_DELAY: &str = "stack_fade_delay";
+
+/// Name of a mod stat telling the renderer to show the entire stack until the given in-game time,
+/// in milliseconds.
+pub const STACK_REVEALED_UNTIL: &str = "stack_revealed_until";
+
+/// An endless, seeded source of garbage lines with a single hole each, ordered from top to bottom.
+///
+/// The hole of each line moves to a random column with the given probability, and is otherwise
+/// directly below the hole of the previous line.
+pub fn messy_garbage_lines(seed: u64, hole_change_probability: f64) -> impl Iterator<Item = Line> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut hole = rng.gen_range(0..Game::WIDTH);
+    std::iter::from_fn(move || {
+        if rng.gen_bool(hole_change_probability.clamp(0.0, 1.0)) {
+            hole = rng.gen_range(0..Game::WIDTH);
+        }
+        let mut line = [Some(Game::GARBAGE_TILE); Game::WIDTH];
+        line[hole] = None;
+        Some(line)
+    })
+}
+
 #[allow(dead_code)]
 pub fn custom_starting_board(mut layout_bits: u128) -> FnGameMod {
     let grey_tile = Some(std::num::NonZeroU8::try_from(254).unwrap());
@@ -88,3 +123,192 @@ pub fn display_tetromino_likelihood() -> FnGameMod {
         },
     )
 }
+
+/// Loads all `.toml` and `.json` files in a directory, sorted by file name.
+///
+/// Files which could not be loaded or fail the given check are reported as error messages.
+pub fn load_data_files<F: serde::de::DeserializeOwned>(
+    dir: &Path,
+    mut check: impl FnMut(&F) -> Result<(), String>,
+) -> (Vec<F>, Vec<String>) {
+    let mut paths = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "toml" || extension == "json")
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+    for path in paths {
+        let loaded = load_data_file(&path).and_then(|file| check(&file).map(|()| file));
+        match loaded {
+            Ok(file) => files.push(file),
+            Err(e) => errors.push(format!(
+                "{}: {e}",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            )),
+        }
+    }
+    (files, errors)
+}
+
+/// Writes a file as TOML into a directory, naming it after `name`; returns the file's path.
+///
+/// Different names may share a file name (such as `My Board` and `my-board`), so a file saved
+/// under another name is never replaced.
+pub fn save_data_file<F: serde::Serialize>(
+    dir: &Path,
+    name: &str,
+    file: &F,
+) -> io::Result<PathBuf> {
+    let contents =
+        toml::to_string_pretty(file).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let path = file_path(dir, name, "toml");
+    if let Ok(saved) = fs::read_to_string(&path) {
+        let saved_name = toml::from_str::<toml::Table>(&saved)
+            .ok()
+            .and_then(|table| table.get("name")?.as_str().map(String::from));
+        if saved_name.as_deref() != Some(name) {
+            let holder = saved_name.map_or("a file without a name".to_string(), |saved_name| {
+                format!("{saved_name:?}")
+            });
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{path:?} already holds {holder}, choose another name"),
+            ));
+        }
+    }
+    fs::create_dir_all(dir)?;
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+/// Writes text into a directory, naming the file after `name`; returns the file's path.
+pub fn save_text_file(
+    dir: &Path,
+    name: &str,
+    extension: &str,
+    contents: &str,
+) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = file_path(dir, name, extension);
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+fn file_path(dir: &Path, name: &str, extension: &str) -> PathBuf {
+    let file_stem = name
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() {
+                ch.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    dir.join(format!("{file_stem}.{extension}"))
+}
+
+fn load_data_file<F: serde::de::DeserializeOwned>(path: &Path) -> Result<F, String> {
+    let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&contents).map_err(|e| e.to_string())
+    } else {
+        toml::from_str(&contents).map_err(|e| e.message().to_string())
+    }
+}
+
+/// Parses board rows given from top to bottom, returning the lines bottom first.
+///
+/// Spaces, `.` and `_` are empty cells, piece letters are tiles of that piece and any other
+/// character is a grey tile.
+pub fn parse_board(rows: &[String]) -> Result<Vec<Line>, String> {
+    if rows.len() > Game::SKYLINE {
+        return Err(format!("board has more than {} rows", Game::SKYLINE));
+    }
+    rows.iter()
+        .rev()
+        .map(|row| {
+            if row.chars().count() > Game::WIDTH {
+                return Err(format!("board row {row:?} is wider than {}", Game::WIDTH));
+            }
+            let mut line = Line::default();
+            for (cell, ch) in line.iter_mut().zip(row.chars()) {
+                *cell = match ch {
+                    ' ' | '.' | '_' => None,
+                    _ => {
+                        Some(parse_tetromino(ch).map_or(Game::GARBAGE_TILE, |tet| tet.tiletypeid()))
+                    }
+                };
+            }
+            Ok(line)
+        })
+        .collect()
+}
+
+/// Parses a sequence of piece letters such as `"TLJ"`, ignoring whitespace.
+pub fn parse_pieces(pieces: &str) -> Result<Vec<Tetromino>, String> {
+    pieces
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .map(|ch| parse_tetromino(ch).ok_or(format!("unknown piece {ch:?}")))
+        .collect()
+}
+
+pub fn parse_tetromino(ch: char) -> Option<Tetromino> {
+    Some(match ch.to_ascii_uppercase() {
+        'O' => Tetromino::O,
+        'I' => Tetromino::I,
+        'S' => Tetromino::S,
+        'Z' => Tetromino::Z,
+        'T' => Tetromino::T,
+        'L' => Tetromino::L,
+        'J' => Tetromino::J,
+        _ => return None,
+    })
+}
+
+pub fn tetromino_char(tetromino: Tetromino) -> char {
+    match tetromino {
+        Tetromino::O => 'O',
+        Tetromino::I => 'I',
+        Tetromino::S => 'S',
+        Tetromino::Z => 'Z',
+        Tetromino::T => 'T',
+        Tetromino::L => 'L',
+        Tetromino::J => 'J',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    struct Named {
+        name: String,
+    }
+
+    #[test]
+    fn data_files_are_not_saved_over_files_of_another_name() {
+        let dir = std::env::temp_dir().join(format!("tetrs_data_files_{}", std::process::id()));
+        let save = |name: &str| {
+            let file = Named {
+                name: name.to_string(),
+            };
+            save_data_file(&dir, name, &file)
+        };
+        let path = save("My Layout").unwrap();
+        assert_eq!(path, save("My Layout").unwrap());
+        for name in ["my-layout", "MY_LAYOUT"] {
+            assert!(save(name).is_err(), "{name}");
+        }
+        assert!(fs::read_to_string(&path).unwrap().contains("My Layout"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,235 @@
+use std::{cell::RefCell, collections::VecDeque, num::NonZeroU32, rc::Rc};
+
+use rand::Rng;
+
+use tetrs_engine::{
+    Feedback, FeedbackEvents, FnGameMod, Game, GameConfig, GameMode, GameOver, GameState,
+    InternalEvent, Limits, Line, ModifierPoint,
+};
+
+/// How many garbage lines may rise into a board after a single piece locked down.
+const MAX_GARBAGE_PER_LOCK: u32 = 8;
+
+#[derive(Clone, Default, Debug)]
+pub struct GarbageExchange {
+    /// Garbage waiting to rise into the board, as batches of `(line count, hole column)`.
+    pub incoming: VecDeque<(u32, usize)>,
+    /// Total number of garbage lines sent to the opponent so far.
+    pub lines_sent: u32,
+}
+
+pub type SharedGarbage = Rc<RefCell<GarbageExchange>>;
+
+impl GarbageExchange {
+    pub fn pending(&self) -> u32 {
+        self.incoming.iter().map(|(lines, _)| lines).sum()
+    }
+
+    /// Uses up an attack to cancel out incoming garbage first, returning what is left over.
+    pub fn cancel(&mut self, mut attack: u32) -> u32 {
+        while attack > 0 {
+            let Some((lines, _)) = self.incoming.front_mut() else {
+                break;
+            };
+            let cancelled = attack.min(*lines);
+            attack -= cancelled;
+            *lines -= cancelled;
+            if *lines == 0 {
+                self.incoming.pop_front();
+            }
+        }
+        attack
+    }
+}
+
+/// Number of garbage lines caused by a line clear, following common modern conventions.
+pub fn attack_lines(
+    lineclears: u32,
+    spin: bool,
+    perfect_clear: bool,
+    combo: u32,
+    back_to_back: u32,
+) -> u32 {
+    let base = if spin {
+        2 * lineclears
+    } else {
+        match lineclears {
+            0 | 1 => 0,
+            2 => 1,
+            3 => 2,
+            n => n,
+        }
+    };
+    let combo_bonus = [0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 4, 5]
+        [usize::try_from(combo.saturating_sub(1)).unwrap().min(11)];
+    let back_to_back_bonus = if back_to_back > 1 { 1 } else { 0 };
+    let perfect_clear_bonus = if perfect_clear { 10 } else { 0 };
+    base + combo_bonus + back_to_back_bonus + perfect_clear_bonus
+}
+
+pub fn garbage_line(hole: usize) -> Line {
+    let mut line = [Some(Game::GARBAGE_TILE); Game::WIDTH];
+    line[hole.min(Game::WIDTH - 1)] = None;
+    line
+}
+
+pub fn new_game(own_garbage: SharedGarbage, opponent_garbage: SharedGarbage) -> Game {
+    let mut rng = rand::thread_rng();
+    let versus_mode: FnGameMod = Box::new(
+        move |_config: &mut GameConfig,
+              _mode: &mut GameMode,
+              state: &mut GameState,
+              feedback_events: &mut FeedbackEvents,
+              modifier_point: &ModifierPoint| {
+            if state.end.is_some()
+                || !matches!(
+                    modifier_point,
+                    ModifierPoint::AfterEvent(InternalEvent::Lock)
+                )
+            {
+                return;
+            }
+            // Look at the feedback caused by the piece that just locked.
+            let attack = feedback_events
+                .iter()
+                .rev()
+                .take_while(|(_, feedback)| !matches!(feedback, Feedback::PieceLocked(_)))
+                .find_map(|(_, feedback)| match feedback {
+                    Feedback::Accolade {
+                        spin,
+                        mini,
+                        lineclears,
+                        perfect_clear,
+                        combo,
+                        back_to_back,
+                        ..
+                    } => Some(attack_lines(
+                        *lineclears,
+                        // Mini spins attack like ordinary clears.
+                        *spin && !*mini,
+                        *perfect_clear,
+                        *combo,
+                        *back_to_back,
+                    )),
+                    _ => None,
+                });
+            let mut own_garbage = own_garbage.borrow_mut();
+            if let Some(attack) = attack {
+                // Lines were cleared: counter incoming garbage and send the rest.
+                let attack = own_garbage.cancel(attack);
+                if attack > 0 {
+                    own_garbage.lines_sent += attack;
+                    opponent_garbage
+                        .borrow_mut()
+                        .incoming
+                        .push_back((attack, rng.gen_range(0..Game::WIDTH)));
+                }
+            } else {
+                // No lines cleared: let incoming garbage rise.
+                let mut budget = MAX_GARBAGE_PER_LOCK;
+                while budget > 0 {
+                    let Some((lines, hole)) = own_garbage.incoming.front_mut() else {
+                        break;
+                    };
+                    let n_rising = budget.min(*lines);
+                    for _ in 0..n_rising {
+                        state.insert_row(0, garbage_line(*hole));
+                        // Stack pushed out of the board entirely, game over.
+                        if state
+                            .pop_row()
+                            .is_some_and(|line| line.iter().any(|cell| cell.is_some()))
+                        {
+                            state.end = Some(Err(GameOver::BlockOut));
+                        }
+                    }
+                    budget -= n_rising;
+                    *lines -= n_rising;
+                    if *lines == 0 {
+                        own_garbage.incoming.pop_front();
+                    }
+                }
+            }
+        },
+    );
+    let mut game = Game::new(GameMode {
+        name: "Versus".to_string(),
+        start_level: NonZeroU32::MIN,
+        increment_level: true,
+        level_progression: Default::default(),
+        big: false,
+        cascade: false,
+        zone: false,
+        limits: Limits::default(),
+    });
+    unsafe { game.add_modifier(versus_mode) };
+    game
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tetrs_engine::{Button, ButtonsPressed};
+
+    use super::*;
+
+    #[test]
+    fn clears_attack_by_the_usual_table() {
+        // Singles, doubles, triples and quads.
+        assert_eq!(
+            (1..=4)
+                .map(|lines| attack_lines(lines, false, false, 1, 0))
+                .collect::<Vec<_>>(),
+            [0, 1, 2, 4]
+        );
+        // T-spin double, with back-to-back, then continuing a combo.
+        assert_eq!(attack_lines(2, true, false, 1, 1), 4);
+        assert_eq!(attack_lines(2, true, false, 1, 2), 5);
+        assert_eq!(attack_lines(1, false, false, 5, 0), 2);
+        assert_eq!(attack_lines(1, false, false, 100, 0), 5);
+        assert_eq!(attack_lines(4, false, true, 1, 0), 14);
+    }
+
+    #[test]
+    fn attacks_cancel_incoming_garbage_first() {
+        let mut garbage = GarbageExchange {
+            incoming: VecDeque::from([(2, 0), (3, 5)]),
+            lines_sent: 0,
+        };
+        assert_eq!(garbage.cancel(3), 0);
+        assert_eq!(garbage.incoming, [(2, 5)]);
+        assert_eq!(garbage.cancel(4), 2);
+        assert_eq!(garbage.pending(), 0);
+    }
+
+    #[test]
+    fn garbage_rises_in_batches_until_the_game_is_lost() {
+        let own_garbage = SharedGarbage::default();
+        own_garbage
+            .borrow_mut()
+            .incoming
+            .push_back((u32::try_from(Game::HEIGHT).unwrap(), 0));
+        let mut game = new_game(own_garbage.clone(), SharedGarbage::default());
+        let mut time = Duration::from_millis(1);
+        let mut pending = vec![own_garbage.borrow().pending()];
+        while !game.ended() {
+            let mut buttons_pressed = ButtonsPressed::default();
+            buttons_pressed[Button::DropHard] = true;
+            for buttons_pressed in [buttons_pressed, ButtonsPressed::default()] {
+                time += Duration::from_millis(500);
+                if game.update(Some(buttons_pressed), time).is_err() {
+                    break;
+                }
+            }
+            pending.push(own_garbage.borrow().pending());
+        }
+        let rising = pending.windows(2).map(|w| w[0] - w[1]).collect::<Vec<_>>();
+        assert_eq!(rising[..2], [MAX_GARBAGE_PER_LOCK, MAX_GARBAGE_PER_LOCK]);
+        assert!(rising.iter().all(|lines| *lines <= MAX_GARBAGE_PER_LOCK));
+        let risen = usize::try_from(pending[0] - pending.last().unwrap()).unwrap();
+        assert!(game.state().board[..risen]
+            .iter()
+            .all(|line| *line == garbage_line(0)));
+        assert!(matches!(game.state().end, Some(Err(_))));
+    }
+}
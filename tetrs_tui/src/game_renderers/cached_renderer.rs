@@ -18,6 +18,7 @@ use tetrs_engine::{
 };
 
 use crate::{
+    game_mods::utils,
     game_renderers::Renderer,
     terminal_app::{
         fmt_duration, fmt_key, fmt_keybinds, GraphicsColor, GraphicsStyle, RunningGameStats,
@@ -26,7 +27,7 @@ use crate::{
 };
 
 #[derive(Clone, Default, Debug)]
-struct ScreenBuf {
+pub struct ScreenBuf {
     prev: Vec<Vec<(char, Option<Color>)>>,
     next: Vec<Vec<(char, Option<Color>)>>,
     x_draw: usize,
@@ -34,19 +35,19 @@ struct ScreenBuf {
 }
 
 impl ScreenBuf {
-    fn buffer_reset(&mut self, (x, y): (usize, usize)) {
+    pub fn buffer_reset(&mut self, (x, y): (usize, usize)) {
         self.prev.clear();
         (self.x_draw, self.y_draw) = (x, y);
     }
 
-    fn buffer_from(&mut self, base_screen: Vec<String>) {
+    pub fn buffer_from(&mut self, base_screen: Vec<String>) {
         self.next = base_screen
             .iter()
             .map(|str| str.chars().zip(std::iter::repeat(None)).collect())
             .collect();
     }
 
-    fn buffer_str(&mut self, str: &str, fg_color: Option<Color>, (x, y): (usize, usize)) {
+    pub fn buffer_str(&mut self, str: &str, fg_color: Option<Color>, (x, y): (usize, usize)) {
         for (x_c, c) in str.chars().enumerate() {
             // Lazy: just fill up until desired starting row and column exist.
             while y >= self.next.len() {
@@ -84,7 +85,7 @@ impl ScreenBuf {
         Ok(())
     }
 
-    fn flush(&mut self, term: &mut impl Write) -> io::Result<()> {
+    pub fn flush(&mut self, term: &mut impl Write) -> io::Result<()> {
         // Begin frame update.
         term.queue(terminal::BeginSynchronizedUpdate)?;
         if self.prev.is_empty() {
@@ -200,6 +201,72 @@ impl ScreenBuf {
     }
 }
 
+pub fn fmt_tetromino_small(tetromino: &Tetromino) -> &'static str {
+    match tetromino {
+        Tetromino::O => "██",
+        Tetromino::I => "▄▄▄▄",
+        Tetromino::S => "▄█▀",
+        Tetromino::Z => "▀█▄",
+        Tetromino::T => "▄█▄",
+        Tetromino::L => "▄▄█",
+        Tetromino::J => "█▄▄",
+    }
+}
+
+#[rustfmt::skip]
+pub fn get_color(mode: GraphicsColor) -> fn(TileTypeID) -> Option<Color> {
+    match mode {
+        GraphicsColor::Monochrome => {
+            |_tile: TileTypeID| None
+        },
+        GraphicsColor::Color16 => {
+            |tile: TileTypeID| Some(match tile.get() {
+                    1 => Color::Yellow,
+                    2 => Color::DarkCyan,
+                    3 => Color::Green,
+                    4 => Color::DarkRed,
+                    5 => Color::DarkMagenta,
+                    6 => Color::Red,
+                    7 => Color::Blue,
+                253 => Color::Black,
+                254 => Color::DarkGrey,
+                255 => Color::White,
+                t => unimplemented!("formatting unknown tile id {t}"),
+            })
+        },
+        GraphicsColor::Fullcolor => {
+            |tile: TileTypeID| Some(match tile.get() {
+                    1 => Color::Rgb { r:254, g:203, b:  0 },
+                    2 => Color::Rgb { r:  0, g:159, b:218 },
+                    3 => Color::Rgb { r:105, g:190, b: 40 },
+                    4 => Color::Rgb { r:237, g: 41, b: 57 },
+                    5 => Color::Rgb { r:149, g: 45, b:152 },
+                    6 => Color::Rgb { r:255, g:121, b:  0 },
+                    7 => Color::Rgb { r:  0, g:101, b:189 },
+                253 => Color::Rgb { r:  0, g:  0, b:  0 },
+                254 => Color::Rgb { r:127, g:127, b:127 },
+                255 => Color::Rgb { r:255, g:255, b:255 },
+                t => unimplemented!("formatting unknown tile id {t}"),
+            })
+        }
+        GraphicsColor::Experimental => {
+            |tile: TileTypeID| Some(match tile.get() {
+                    1 => Color::Rgb { r: 14, g:198, b:244 },
+                    2 => Color::Rgb { r:242, g:192, b: 29 },
+                    3 => Color::Rgb { r: 70, g:201, b: 50 },
+                    4 => Color::Rgb { r:230, g: 53, b:197 },
+                    5 => Color::Rgb { r:147, g: 41, b:229 },
+                    6 => Color::Rgb { r: 36, g:118, b:242 },
+                    7 => Color::Rgb { r:244, g: 50, b: 48 },
+                  253 => Color::Rgb { r:  0, g:  0, b:  0 },
+                  254 => Color::Rgb { r:127, g:127, b:127 },
+                  255 => Color::Rgb { r:255, g:255, b:255 },
+                t => unimplemented!("formatting unknown tile id {t}"),
+            })
+        },
+    }
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct CachedRenderer {
     screen: ScreenBuf,
@@ -222,7 +289,7 @@ impl Renderer for CachedRenderer {
         T: Write,
     {
         if screen_resized {
-            let (x_main, y_main) = TerminalApp::<T>::fetch_main_xy();
+            let (x_main, y_main) = app.fetch_main_xy();
             self.screen
                 .buffer_reset((usize::from(x_main), usize::from(y_main)));
         }
@@ -241,6 +308,17 @@ impl Renderer for CachedRenderer {
             score,
             consecutive_line_clears: _,
             back_to_back_special_clears: _,
+            mod_stats,
+            pieces_since_line_clear,
+            piece_spawn_time,
+            garbage_lines_cleared,
+            level_progress: _,
+            gravity_suspended: _,
+            zone_meter,
+            zone_end,
+            zone_lines,
+            last_rotation: _,
+            board_lock_times,
         } = game.state();
         // Screen: some titles.
         let mode_name = game.mode().name.to_ascii_uppercase();
@@ -278,18 +356,44 @@ impl Renderer for CachedRenderer {
                     max_pts.saturating_sub(*score).to_string(),
                 )
             }),
+            game.mode()
+                .limits
+                .holes
+                .map(|(_, max_holes)| ("Holes:", format!("{}/{max_holes}", game.state().holes()))),
+            game.mode().limits.stack_height.map(|(_, max_height)| {
+                (
+                    "Stack height:",
+                    format!("{}/{max_height}", game.state().stack_height()),
+                )
+            }),
+            game.mode().limits.pieces_without_clear.map(|(_, max_pcs)| {
+                (
+                    "Pieces without clear:",
+                    format!("{pieces_since_line_clear}/{max_pcs}"),
+                )
+            }),
+            game.mode().limits.piece_time.map(|(_, max_dur)| {
+                (
+                    "Piece time left:",
+                    fmt_duration(
+                        max_dur.saturating_sub(game_time.saturating_sub(*piece_spawn_time)),
+                    ),
+                )
+            }),
+            game.mode().limits.garbage_lines.map(|(_, max_lns)| {
+                (
+                    "Garbage left to clear:",
+                    max_lns.saturating_sub(*garbage_lines_cleared).to_string(),
+                )
+            }),
         ]
         .into_iter()
         .find_map(|limit_text| limit_text)
         .unwrap_or_default();
-        let (focus_name, focus_value) = match game.mode().name.as_str() {
-            "Marathon" => ("Score:", score.to_string()),
-            "40-Lines" => ("Time taken:", fmt_duration(*game_time)),
-            "Time Trial" => ("Score:", score.to_string()),
-            "Master" => ("", "".to_string()),
-            "Puzzle" => ("", "".to_string()),
-            _ => ("Lines cleared:", lines_cleared.to_string()),
-        };
+        let (focus_name, focus_value) = app
+            .modes()
+            .get(&game.mode().name)
+            .focus_stat(game.mode(), game.state());
         let key_icons_moveleft = fmt_keybinds(Button::MoveLeft, &app.settings().keybinds);
         let key_icons_moveright = fmt_keybinds(Button::MoveRight, &app.settings().keybinds);
         let mut key_icons_move = format!("{key_icons_moveleft}{key_icons_moveright}");
@@ -427,58 +531,7 @@ impl Renderer for CachedRenderer {
         let (x_preview_minuscule, y_preview_minuscule) = (50, 16);
         let (x_messages, y_messages) = (47, 18);
         let pos_board = |(x, y)| (x_board + 2 * x, y_board + Game::SKYLINE - y);
-        // Board: helpers.
-        #[rustfmt::skip]
-        let get_color = |mode: GraphicsColor| match mode {
-            GraphicsColor::Monochrome => {
-                |_tile: TileTypeID| None
-            },
-            GraphicsColor::Color16 => {
-                |tile: TileTypeID| Some(match tile.get() {
-                        1 => Color::Yellow,
-                        2 => Color::DarkCyan,
-                        3 => Color::Green,
-                        4 => Color::DarkRed,
-                        5 => Color::DarkMagenta,
-                        6 => Color::Red,
-                        7 => Color::Blue,
-                    253 => Color::Black,
-                    254 => Color::DarkGrey,
-                    255 => Color::White,
-                    t => unimplemented!("formatting unknown tile id {t}"),
-                })
-            },
-            GraphicsColor::Fullcolor => {
-                |tile: TileTypeID| Some(match tile.get() {
-                        1 => Color::Rgb { r:254, g:203, b:  0 },
-                        2 => Color::Rgb { r:  0, g:159, b:218 },
-                        3 => Color::Rgb { r:105, g:190, b: 40 },
-                        4 => Color::Rgb { r:237, g: 41, b: 57 },
-                        5 => Color::Rgb { r:149, g: 45, b:152 },
-                        6 => Color::Rgb { r:255, g:121, b:  0 },
-                        7 => Color::Rgb { r:  0, g:101, b:189 },
-                    253 => Color::Rgb { r:  0, g:  0, b:  0 },
-                    254 => Color::Rgb { r:127, g:127, b:127 },
-                    255 => Color::Rgb { r:255, g:255, b:255 },
-                    t => unimplemented!("formatting unknown tile id {t}"),
-                })
-            }
-            GraphicsColor::Experimental => {
-                |tile: TileTypeID| Some(match tile.get() {
-                        1 => Color::Rgb { r: 14, g:198, b:244 },
-                        2 => Color::Rgb { r:242, g:192, b: 29 },
-                        3 => Color::Rgb { r: 70, g:201, b: 50 },
-                        4 => Color::Rgb { r:230, g: 53, b:197 },
-                        5 => Color::Rgb { r:147, g: 41, b:229 },
-                        6 => Color::Rgb { r: 36, g:118, b:242 },
-                        7 => Color::Rgb { r:244, g: 50, b: 48 },
-                      253 => Color::Rgb { r:  0, g:  0, b:  0 },
-                      254 => Color::Rgb { r:127, g:127, b:127 },
-                      255 => Color::Rgb { r:255, g:255, b:255 },
-                    t => unimplemented!("formatting unknown tile id {t}"),
-                })
-            },
-        };
+        let big = game.mode().big;
         let color = get_color(app.settings().graphics_color);
         let color_board = get_color(app.settings().graphics_color_board);
         // Board: draw hard drop trail.
@@ -511,28 +564,72 @@ impl Renderer for CachedRenderer {
                 GraphicsStyle::ASCII => ("##", "::", "[]", "[]"),
                 GraphicsStyle::Unicode => ("██", "░░", "▓▓", "▒▒"),
             };
-        for (y, line) in board.iter().enumerate().take(21).rev() {
-            for (x, cell) in line.iter().enumerate() {
-                if let Some(tile_type_id) = cell {
-                    self.screen.buffer_str(
-                        tile_ground,
-                        color_board(*tile_type_id),
-                        pos_board((x, y)),
-                    );
+        // Game mods may hide the stack (e.g. during Master mode's credit roll), or let locked
+        // tiles fade away, unless they currently reveal it.
+        let stack_revealed = mod_stats
+            .get(utils::STACK_REVEALED_UNTIL)
+            .is_some_and(|until| game_time.as_millis() < *until as u128);
+        let invisible_stack = !stack_revealed
+            && mod_stats
+                .get(utils::INVISIBLE_STACK)
+                .is_some_and(|invisible| *invisible != 0);
+        let fade_delay = mod_stats
+            .get(utils::STACK_FADE_DELAY)
+            .filter(|_| !stack_revealed)
+            .map(|ms| Duration::from_millis((*ms).max(0) as u64));
+        let tiles_fading = match app.settings().graphics_style {
+            GraphicsStyle::Electronika60 => ["▮▮", "▮▮", "▮▮"],
+            GraphicsStyle::ASCII => ["..", "::", "##"],
+            GraphicsStyle::Unicode => ["░░", "▒▒", "▓▓"],
+        };
+        if !invisible_stack {
+            for (y, line) in board.iter().enumerate().take(21).rev() {
+                for (x, cell) in line.iter().enumerate() {
+                    if let Some(tile_type_id) = cell {
+                        let lock_time = board_lock_times.get(y).and_then(|times| times[x]);
+                        let tile = match (fade_delay, lock_time) {
+                            (Some(fade_delay), Some(lock_time)) => {
+                                let time_left = (lock_time + fade_delay).saturating_sub(*game_time);
+                                if time_left.is_zero() {
+                                    continue;
+                                }
+                                tiles_fading
+                                    .get((time_left.as_millis() / 200) as usize)
+                                    .unwrap_or(&tile_ground)
+                            }
+                            _ => tile_ground,
+                        };
+                        // Lines collected during the zone light up until it ends.
+                        let tile_type_id = if y < *zone_lines as usize {
+                            NonZeroU8::MAX
+                        } else {
+                            *tile_type_id
+                        };
+                        self.screen
+                            .buffer_str(tile, color_board(tile_type_id), pos_board((x, y)));
+                    }
                 }
             }
         }
         // If a piece is in play.
         if let Some((active_piece, _)) = active_piece_data {
             // Draw ghost piece.
-            for (tile_pos, tile_type_id) in active_piece.well_piece(board).tiles() {
-                if tile_pos.1 <= Game::SKYLINE {
-                    self.screen
-                        .buffer_str(tile_ghost, color(tile_type_id), pos_board(tile_pos));
+            if !game.config().no_ghost_piece {
+                for (tile_pos, tile_type_id) in active_piece
+                    .well_piece(&Game::piece_board(board, big))
+                    .board_tiles(big)
+                {
+                    if tile_pos.1 <= Game::SKYLINE {
+                        self.screen.buffer_str(
+                            tile_ghost,
+                            color(tile_type_id),
+                            pos_board(tile_pos),
+                        );
+                    }
                 }
             }
             // Draw active piece.
-            for (tile_pos, tile_type_id) in active_piece.tiles() {
+            for (tile_pos, tile_type_id) in active_piece.board_tiles(big) {
                 if tile_pos.1 <= Game::SKYLINE {
                     self.screen
                         .buffer_str(tile_active, color(tile_type_id), pos_board(tile_pos));
@@ -547,19 +644,10 @@ impl Renderer for CachedRenderer {
                 self.screen.buffer_str(tile_preview, color, pos);
             }
         }
-        let preview_small = |t: &Tetromino| match t {
-            Tetromino::O => "██",
-            Tetromino::I => "▄▄▄▄",
-            Tetromino::S => "▄█▀",
-            Tetromino::Z => "▀█▄",
-            Tetromino::T => "▄█▄",
-            Tetromino::L => "▄▄█",
-            Tetromino::J => "█▄▄",
-        };
         // Draw small preview pieces 2,3,4.
         let mut x_offset_small = 0;
         for tet in next_pieces.iter().skip(1).take(3) {
-            let str = preview_small(tet);
+            let str = fmt_tetromino_small(tet);
             self.screen.buffer_str(
                 str,
                 color(tet.tiletypeid()),
@@ -591,9 +679,48 @@ impl Renderer for CachedRenderer {
             );
             x_offset_minuscule += str.chars().count() + 1;
         }
+        // Draw zone meter.
+        if game.mode().zone {
+            let (x_zone, y_zone) = (3, 14);
+            let color_zone = match app.settings().graphics_color {
+                GraphicsColor::Monochrome => None,
+                GraphicsColor::Color16 | GraphicsColor::Fullcolor | GraphicsColor::Experimental => {
+                    Some(Color::Cyan)
+                }
+            };
+            if let Some(zone_end) = zone_end {
+                let zone_str = format!(
+                    "ZONE {:.1}s +{} lns",
+                    zone_end.saturating_sub(*game_time).as_secs_f64(),
+                    zone_lines
+                );
+                self.screen
+                    .buffer_str(&zone_str, color_zone, (x_zone, y_zone));
+            } else {
+                let (tile_full, tile_empty) = match app.settings().graphics_style {
+                    GraphicsStyle::Electronika60 => ("▮", "."),
+                    GraphicsStyle::ASCII => ("#", "."),
+                    GraphicsStyle::Unicode => ("█", "░"),
+                };
+                // One segment for every eighth of the meter.
+                let segments_full = (*zone_meter * 8 / Game::ZONE_METER_MAX) as usize;
+                let zone_str = format!(
+                    "Zone [{}{}] {}",
+                    tile_full.repeat(segments_full),
+                    tile_empty.repeat(8 - segments_full),
+                    fmt_keybinds(Button::ActivateZone, &app.settings().keybinds)
+                );
+                let activatable = *zone_meter >= Game::ZONE_METER_MAX / 4;
+                self.screen.buffer_str(
+                    &zone_str,
+                    color_zone.filter(|_| activatable),
+                    (x_zone, y_zone),
+                );
+            }
+        }
         // Draw held piece.
         if let Some((tet, swap_allowed)) = hold_piece {
-            let str = preview_small(tet);
+            let str = fmt_tetromino_small(tet);
             let color = color(if *swap_allowed {
                 tet.tiletypeid()
             } else {
@@ -657,7 +784,7 @@ impl Renderer for CachedRenderer {
                         *relevant = false;
                         continue;
                     };
-                    for (tile_pos, _tile_type_id) in piece.tiles() {
+                    for (tile_pos, _tile_type_id) in piece.board_tiles(big) {
                         if tile_pos.1 <= Game::SKYLINE {
                             self.screen
                                 .buffer_str(tile, color_locking, pos_board(tile_pos));
@@ -728,7 +855,7 @@ impl Renderer for CachedRenderer {
                     }
                 }
                 Feedback::HardDrop(_top_piece, bottom_piece) => {
-                    for ((x_tile, y_tile), tile_type_id) in bottom_piece.tiles() {
+                    for ((x_tile, y_tile), tile_type_id) in bottom_piece.board_tiles(big) {
                         for y in y_tile..Game::SKYLINE {
                             self.hard_drop_tiles.push((
                                 *event_time,
@@ -745,6 +872,7 @@ impl Renderer for CachedRenderer {
                     score_bonus,
                     shape,
                     spin,
+                    mini,
                     lineclears,
                     perfect_clear,
                     combo,
@@ -757,7 +885,10 @@ impl Renderer for CachedRenderer {
                         strs.push("Perfect".to_string());
                     }
                     if *spin {
-                        strs.push(format!("{shape:?}-Spin"));
+                        strs.push(format!(
+                            "{}{shape:?}-Spin",
+                            if *mini { "Mini " } else { "" }
+                        ));
                         running_game_stats.0[0] += 1;
                     }
                     let clear_action = match lineclears {
@@ -800,6 +931,25 @@ impl Renderer for CachedRenderer {
                     self.messages.push((*event_time, strs.join(" ")));
                     *relevant = false;
                 }
+                Feedback::LevelUp(new_level) => {
+                    // Let the level stat blink for a moment.
+                    if elapsed >= Duration::from_millis(1200) {
+                        *relevant = false;
+                        continue;
+                    }
+                    let color_levelup = match app.settings().graphics_color {
+                        GraphicsColor::Monochrome => None,
+                        GraphicsColor::Color16
+                        | GraphicsColor::Fullcolor
+                        | GraphicsColor::Experimental => Some(Color::Yellow),
+                    };
+                    let levelup_str = if elapsed.as_millis() / 150 % 2 == 0 {
+                        format!("Level: {:<12}", format!("{new_level} UP!"))
+                    } else {
+                        format!("Level: {:<12}", new_level)
+                    };
+                    self.screen.buffer_str(&levelup_str, color_levelup, (3, 4));
+                }
                 Feedback::Message(msg) => {
                     self.messages.push((*event_time, msg.clone()));
                     *relevant = false;
@@ -90,6 +90,7 @@ impl Renderer for DebugRenderer {
                     score_bonus,
                     shape,
                     spin,
+                    mini,
                     lineclears,
                     perfect_clear,
                     combo,
@@ -97,7 +98,10 @@ impl Renderer for DebugRenderer {
                 } => {
                     let mut strs = Vec::new();
                     if *spin {
-                        strs.push(format!("{shape:?}-Spin"));
+                        strs.push(format!(
+                            "{}{shape:?}-Spin",
+                            if *mini { "Mini " } else { "" }
+                        ));
                     }
                     let clear_action = match lineclears {
                         1 => "Single",
@@ -124,6 +128,7 @@ impl Renderer for DebugRenderer {
                 Feedback::PieceLocked(_) => continue,
                 Feedback::LineClears(..) => continue,
                 Feedback::HardDrop(_, _) => continue,
+                Feedback::LevelUp(level) => format!("Level {level}"),
                 Feedback::Message(s) => s.clone(),
             });
         }
@@ -1,5 +1,6 @@
 pub mod cached_renderer;
 pub mod debug_renderer;
+pub mod versus_renderer;
 
 use std::io::{self, Write};
 
@@ -0,0 +1,237 @@
+use std::{
+    io::{self, Write},
+    num::NonZeroU8,
+    time::Duration,
+};
+
+use crossterm::style::Color;
+use tetrs_engine::{Feedback, FeedbackEvents, Game, GameState, GameTime};
+
+use crate::{
+    game_mods::versus_mode::GarbageExchange,
+    game_renderers::cached_renderer::{fmt_tetromino_small, get_color, ScreenBuf},
+    terminal_app::{GraphicsColor, GraphicsStyle, TerminalApp},
+};
+
+/// Renders two games next to each other, each within one half of the main screen.
+#[derive(Clone, Default, Debug)]
+pub struct VersusRenderer {
+    screen: ScreenBuf,
+    messages: [Vec<(GameTime, String)>; 2],
+}
+
+impl VersusRenderer {
+    pub const W_PLAYER: usize = 40;
+
+    pub fn render<T: Write>(
+        &mut self,
+        app: &mut TerminalApp<T>,
+        players: [(&str, &GameState, &GarbageExchange); 2],
+        new_feedback_events: [FeedbackEvents; 2],
+        screen_resized: bool,
+    ) -> io::Result<()> {
+        if screen_resized {
+            let (x_main, y_main) = app.fetch_main_xy();
+            self.screen
+                .buffer_reset((usize::from(x_main), usize::from(y_main)));
+        }
+        let graphics_style = app.settings().graphics_style;
+        let graphics_color = app.settings().graphics_color;
+        // Screen: draw both halves.
+        let (board_top, board_row, board_bottom) = match graphics_style {
+            GraphicsStyle::Electronika60 => (
+                "! . . . . . . . . . .!",
+                "! . . . . . . . . . .!",
+                "<!====================!>",
+            ),
+            GraphicsStyle::ASCII => (
+                "|- - - - - - - - - - |",
+                "|                    |",
+                "~#====================#~",
+            ),
+            GraphicsStyle::Unicode => (
+                "╓╶╶╶╶╶╶╶╶╶╶╶╶╶╶╶╶╶╶╶╶╖",
+                "║                    ║",
+                "░▒▓█▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀█▓▒░",
+            ),
+        };
+        let base_half = |row: usize| match row {
+            1 => format!("{:11}{board_top}{:7}", "", ""),
+            2..=21 => format!("{:11}{board_row}{:7}", "", ""),
+            22 => match graphics_style {
+                GraphicsStyle::Unicode => format!("{:8}{board_bottom}{:4}", "", ""),
+                _ => format!("{:10}{board_bottom}{:6}", "", ""),
+            },
+            _ => " ".repeat(Self::W_PLAYER),
+        };
+        let base_screen = (0..24)
+            .map(|row| format!("{}{}", base_half(row), base_half(row)))
+            .collect();
+        self.screen.buffer_from(base_screen);
+        let color = get_color(graphics_color);
+        let color_board = get_color(app.settings().graphics_color_board);
+        let (tile_ground, tile_ghost, tile_active) = match graphics_style {
+            GraphicsStyle::Electronika60 => ("▮▮", " .", "▮▮"),
+            GraphicsStyle::ASCII => ("##", "::", "[]"),
+            GraphicsStyle::Unicode => ("██", "░░", "▓▓"),
+        };
+        let tile_garbage_meter = match graphics_style {
+            GraphicsStyle::Electronika60 | GraphicsStyle::ASCII => "*",
+            GraphicsStyle::Unicode => "▐",
+        };
+        let color_garbage_meter = match graphics_color {
+            GraphicsColor::Monochrome => None,
+            _ => Some(Color::Red),
+        };
+        for (player, ((name, game_state, garbage), new_feedback_events)) in
+            players.into_iter().zip(new_feedback_events).enumerate()
+        {
+            let x_player = player * Self::W_PLAYER;
+            let (x_board, y_board) = (x_player + 12, 1);
+            let pos_board = |(x, y)| (x_board + 2 * x, y_board + Game::SKYLINE - y);
+            let GameState {
+                time: game_time,
+                board,
+                active_piece_data,
+                hold_piece,
+                next_pieces,
+                lines_cleared,
+                level,
+                ..
+            } = game_state;
+            // Draw stats.
+            let stats = [
+                (1, name.to_ascii_uppercase()),
+                (3, "hold:".to_string()),
+                (7, "lines:".to_string()),
+                (8, lines_cleared.to_string()),
+                (10, "sent:".to_string()),
+                (11, garbage.lines_sent.to_string()),
+                (13, "level:".to_string()),
+                (14, level.to_string()),
+                (16, "time:".to_string()),
+                (
+                    17,
+                    format!(
+                        "{}:{:02}.{:02}",
+                        game_time.as_secs() / 60,
+                        game_time.as_secs() % 60,
+                        game_time.as_millis() % 1000 / 10
+                    ),
+                ),
+            ];
+            for (y, stat) in stats {
+                self.screen.buffer_str(&stat, None, (x_player + 1, y));
+            }
+            self.screen.buffer_str("next:", None, (x_player + 34, 1));
+            // Draw board.
+            for (y, line) in board.iter().enumerate().take(21).rev() {
+                for (x, cell) in line.iter().enumerate() {
+                    if let Some(tile_type_id) = cell {
+                        self.screen.buffer_str(
+                            tile_ground,
+                            color_board(*tile_type_id),
+                            pos_board((x, y)),
+                        );
+                    }
+                }
+            }
+            // Draw ghost and active piece.
+            if let Some((active_piece, _)) = active_piece_data {
+                for (tile_pos, tile_type_id) in active_piece.well_piece(board).tiles() {
+                    if tile_pos.1 <= Game::SKYLINE {
+                        self.screen.buffer_str(
+                            tile_ghost,
+                            color(tile_type_id),
+                            pos_board(tile_pos),
+                        );
+                    }
+                }
+                for (tile_pos, tile_type_id) in active_piece.tiles() {
+                    if tile_pos.1 <= Game::SKYLINE {
+                        self.screen.buffer_str(
+                            tile_active,
+                            color(tile_type_id),
+                            pos_board(tile_pos),
+                        );
+                    }
+                }
+            }
+            // Draw incoming garbage meter.
+            for i in 0..garbage.pending().min(20) {
+                self.screen.buffer_str(
+                    tile_garbage_meter,
+                    color_garbage_meter,
+                    (x_player + 10, 21 - usize::try_from(i).unwrap()),
+                );
+            }
+            // Draw held piece.
+            if let Some((tet, swap_allowed)) = hold_piece {
+                let color = color(if *swap_allowed {
+                    tet.tiletypeid()
+                } else {
+                    NonZeroU8::try_from(254).unwrap()
+                });
+                self.screen
+                    .buffer_str(fmt_tetromino_small(tet), color, (x_player + 2, 4));
+            }
+            // Draw preview.
+            for (i, tet) in next_pieces.iter().take(5).enumerate() {
+                self.screen.buffer_str(
+                    fmt_tetromino_small(tet),
+                    color(tet.tiletypeid()),
+                    (x_player + 34, 3 + 2 * i),
+                );
+            }
+            // Draw messages.
+            let messages = &mut self.messages[player];
+            for (event_time, feedback) in new_feedback_events {
+                if let Feedback::Accolade {
+                    shape,
+                    spin,
+                    mini,
+                    lineclears,
+                    perfect_clear,
+                    combo,
+                    ..
+                } = feedback
+                {
+                    let mut strs = Vec::new();
+                    if perfect_clear {
+                        strs.push("Perfect".to_string());
+                    }
+                    if spin {
+                        strs.push(format!("{}{shape:?}-Spin", if mini { "Mini " } else { "" }));
+                    }
+                    strs.push(
+                        match lineclears {
+                            1 => "Single",
+                            2 => "Double",
+                            3 => "Triple",
+                            _ => "Quadruple",
+                        }
+                        .to_string(),
+                    );
+                    if combo > 1 {
+                        strs.push(format!("({combo}.combo)"));
+                    }
+                    messages.push((event_time, strs.join(" ")));
+                } else if let Feedback::Message(msg) = feedback {
+                    messages.push((event_time, msg));
+                }
+            }
+            messages.retain(|(timestamp, _message)| {
+                game_time.saturating_sub(*timestamp) < Duration::from_millis(2000)
+            });
+            if let Some((_event_time, message)) = messages.last() {
+                let message = message.chars().take(Self::W_PLAYER - 2).collect::<String>();
+                self.screen.buffer_str(
+                    &format!("{message:^w$}", w = Self::W_PLAYER - 2),
+                    None,
+                    (x_player + 1, 23),
+                );
+            }
+        }
+        self.screen.flush(&mut app.term)
+    }
+}
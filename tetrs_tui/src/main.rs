@@ -1,11 +1,22 @@
 mod game_input_handlers;
 mod game_mods;
 mod game_renderers;
+mod mode_registry;
+mod netplay;
+mod tas;
+mod telemetry;
 mod terminal_app;
 
-use std::io::{self, Write};
+use std::{
+    io::{self, Write},
+    net::TcpListener,
+    thread,
+};
 
 use clap::Parser;
+use tetrs_engine::fumen;
+
+use crate::game_mods::board_layouts::BoardLayout;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -20,20 +31,126 @@ struct Args {
     ///          => `./tetrs_tui --custom_start=982815`.
     #[arg(long)]
     custom_start: Option<u128>,
+    /// A starting board for **Custom** mode, given as fumen (the first page is used).
+    /// The hold piece and queue are read from a quiz comment (`#Q=[hold](current)next`).
+    /// Example: `./tetrs_tui --fumen v115@9gF8DeF8DeF8DeF8NeAgH`.
+    #[arg(long)]
+    fumen: Option<String>,
     /// Whether to enable the combo bot in combo mode.
     #[arg(short, long)]
     enable_combo_bot: bool,
+    /// Host a networked versus match, waiting for an opponent on the given address.
+    /// Example: `./tetrs_tui --host 0.0.0.0:7878`.
+    #[arg(long, conflicts_with = "join")]
+    host: Option<String>,
+    /// Join a networked versus match hosted at the given address.
+    /// Example: `./tetrs_tui --join 127.0.0.1:7878`.
+    #[arg(long)]
+    join: Option<String>,
+    /// Stream game feedback and state snapshots as JSON lines while playing.
+    /// The target is a file path, `tcp:ADDR` or `unix:PATH`.
+    /// Example: `./tetrs_tui --telemetry tcp:127.0.0.1:9000`.
+    #[arg(long)]
+    telemetry: Option<String>,
+    /// Serve the game to telnet or raw TCP clients on the given address instead of playing locally.
+    /// Every connection gets its own session with default settings.
+    /// Example: `./tetrs_tui --serve 0.0.0.0:2323`.
+    #[arg(long, conflicts_with_all = ["host", "join"])]
+    serve: Option<String>,
+    /// Let an external bot play single-player games, talking the Tetris Bot Protocol over its standard input/output.
+    /// Example: `./tetrs_tui --bot ./target/release/tbp_reference_bot`.
+    #[arg(long)]
+    bot: Option<String>,
+}
+
+/// Size assumed for remote terminals which do not report their window size.
+const DEFAULT_REMOTE_SIZE: (u16, u16) = (80, 24);
+
+/// The layout given by the `--fumen` argument.
+fn fumen_layout(args: &Args) -> Result<Option<BoardLayout>, fumen::FumenError> {
+    let Some(fumen) = &args.fumen else {
+        return Ok(None);
+    };
+    let pages = fumen::decode(fumen)?;
+    Ok(Some(BoardLayout::from_fumen(
+        "Fumen".to_string(),
+        &pages[0],
+    )))
+}
+
+fn serve(addr: &str, args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let fumen_layout = fumen_layout(args)?;
+    let listener = TcpListener::bind(addr)?;
+    println!("Serving tetrs on {}", listener.local_addr()?);
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                println!("Failed to accept connection: {e}");
+                continue;
+            }
+        };
+        let (combo_layout, custom_start, enable_combo_bot) =
+            (args.combo_layout, args.custom_start, args.enable_combo_bot);
+        let fumen_layout = fumen_layout.clone();
+        thread::spawn(move || {
+            let peer = stream
+                .peer_addr()
+                .map_or("unknown peer".to_string(), |addr| addr.to_string());
+            println!("{peer} connected");
+            let result = stream.set_nodelay(true).and_then(|()| {
+                let session = game_input_handlers::socket::SocketSession::new(
+                    stream.try_clone()?,
+                    DEFAULT_REMOTE_SIZE,
+                )?;
+                let mut app = terminal_app::TerminalApp::new(
+                    io::BufWriter::new(stream),
+                    combo_layout,
+                    custom_start,
+                    enable_combo_bot,
+                    None,
+                    None,
+                    Some(session),
+                    None,
+                );
+                if let Some(layout) = fumen_layout {
+                    app.import_board_layout(layout);
+                }
+                app.run()
+            });
+            match result {
+                Ok(_) => println!("{peer} disconnected"),
+                Err(e) => println!("{peer} disconnected ({e})"),
+            }
+        });
+    }
+    Ok(())
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
+    if let Some(addr) = &args.serve {
+        return serve(addr, &args);
+    }
+    let fumen_layout = fumen_layout(&args)?;
+    let netplay_role = args
+        .host
+        .map(netplay::NetplayRole::Host)
+        .or(args.join.map(netplay::NetplayRole::Join));
     let stdout = io::BufWriter::new(io::stdout());
     let mut app = terminal_app::TerminalApp::new(
         stdout,
         args.combo_layout,
         args.custom_start,
         args.enable_combo_bot,
+        netplay_role,
+        args.telemetry,
+        None,
+        args.bot,
     );
+    if let Some(layout) = fumen_layout {
+        app.import_board_layout(layout);
+    }
     std::panic::set_hook(Box::new(|panic_info| {
         if let Ok(mut file) = std::fs::File::create("tetrs_tui_error_message.txt") {
             let _ = file.write(panic_info.to_string().as_bytes());
@@ -0,0 +1,1192 @@
+use std::{
+    cmp::Ordering,
+    fmt::Debug,
+    num::{NonZeroU32, NonZeroUsize},
+    rc::Rc,
+};
+
+use rand::Rng;
+use tetrs_engine::{Game, GameConfig, GameMode, GameState, LimitCombination, Limits};
+
+use crate::{
+    game_mods::{
+        self,
+        daily_mode::{Challenge, Variant},
+        mode_files::ModeFile,
+        puzzle_mode::PuzzlePack,
+    },
+    terminal_app::{fmt_duration, GameModeStore},
+};
+
+/// Everything the application needs to know about a single-player game mode: how to start it,
+/// how to display and rank its games, and when it becomes available.
+pub trait ModeEntry: Debug {
+    /// The name of the mode, i.e. the [`GameMode::name`] of the games it creates.
+    fn name(&self) -> &str;
+
+    /// Whether a finished game with the given [`GameMode::name`] belongs to this mode.
+    fn matches(&self, name: &str) -> bool {
+        name == self.name()
+    }
+
+    /// Short description for the new game menu.
+    fn description(&self, store: &GameModeStore) -> String;
+
+    /// Whether the mode is listed in the upper (instead of the special) section of the menu.
+    fn is_standard(&self) -> bool {
+        false
+    }
+
+    /// Whether the mode is available for selection.
+    fn is_unlocked(&self, _store: &GameModeStore) -> bool {
+        true
+    }
+
+    /// Changes the mode's option, if it has one (left/right in the new game menu).
+    fn adjust(&self, _store: &mut GameModeStore, _increase: bool) {}
+
+    fn new_game(&self, store: &GameModeStore) -> Game;
+
+    /// Starts a game from an explicit seed to be played tool-assisted, if the mode allows it.
+    ///
+    /// This requires the game to be determined by its mode, configuration and seed alone, without
+    /// game mods whose state a savestate could not restore.
+    fn new_tas_game(&self, _store: &GameModeStore, _seed: u64) -> Option<Game> {
+        None
+    }
+
+    /// Applies mode-specific changes on top of the player's configuration.
+    fn configure(&self, _config: &mut GameConfig) {}
+
+    /// The puzzle pack the mode goes through, if it is one.
+    fn puzzle_pack(&self) -> Option<&PuzzlePack> {
+        None
+    }
+
+    /// Whether games of this mode are played by the built-in combo bot instead of the player.
+    fn played_by_combo_bot(&self) -> bool {
+        false
+    }
+
+    /// The statistic displayed most prominently while playing.
+    fn focus_stat(&self, _mode: &GameMode, state: &GameState) -> (String, String) {
+        (
+            "Lines cleared:".to_string(),
+            state.lines_cleared.to_string(),
+        )
+    }
+
+    /// Orders two games of this mode which ended equally (un)successfully, best first.
+    fn compare(&self, _mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        // Sort desc by lines.
+        state1.lines_cleared.cmp(&state2.lines_cleared).reverse()
+    }
+
+    /// One-line summary of a game for the scoreboard.
+    fn summary(&self, mode: &GameMode, state: &GameState) -> String {
+        let limit_texts = fmt_limits(&mode.limits, state);
+        format!(
+            "{}: {} lns, {} pts, {}{}",
+            mode.name,
+            state.lines_cleared,
+            state.score,
+            fmt_duration(state.time),
+            if limit_texts.is_empty() {
+                String::new()
+            } else {
+                format!(" ({limit_texts})")
+            }
+        )
+    }
+
+    /// An additional line describing a finished game on the game over screen.
+    fn result(&self, _mode: &GameMode, _state: &GameState) -> Option<String> {
+        None
+    }
+
+    /// Whether a finished game is worth keeping in the savefile.
+    fn keep_record(&self, state: &GameState) -> bool {
+        state.end.is_some_and(|end| end.is_ok()) || state.lines_cleared > 0
+    }
+
+    /// Called once a game of this mode has ended.
+    fn on_game_ended(&self, _store: &mut GameModeStore, _mode: &GameMode, _state: &GameState) {}
+}
+
+/// Formats the progress towards all limits of a game, joined according to their combination.
+fn fmt_limits(limits: &Limits, state: &GameState) -> String {
+    [
+        limits.time.map(|(_, max_dur)| {
+            format!("{} / {}", fmt_duration(state.time), fmt_duration(max_dur))
+        }),
+        limits.pieces.map(|(_, max_pcs)| {
+            format!(
+                "{}/{} pcs",
+                state.pieces_played.iter().sum::<u32>(),
+                max_pcs
+            )
+        }),
+        limits
+            .lines
+            .map(|(_, max_lns)| format!("{}/{} lns", state.lines_cleared, max_lns)),
+        limits
+            .level
+            .map(|(_, max_lvl)| format!("{}/{} lvl", state.level, max_lvl)),
+        limits
+            .score
+            .map(|(_, max_pts)| format!("{}/{} pts", state.score, max_pts)),
+        limits
+            .holes
+            .map(|(_, max_holes)| format!("{}/{} holes", state.holes(), max_holes)),
+        limits
+            .stack_height
+            .map(|(_, max_height)| format!("{}/{} high", state.stack_height(), max_height)),
+        limits.pieces_without_clear.map(|(_, max_pcs)| {
+            format!(
+                "{}/{} pcs w/o clear",
+                state.pieces_since_line_clear, max_pcs
+            )
+        }),
+        limits.piece_time.map(|(_, max_dur)| {
+            format!(
+                "{} / {} per pc",
+                fmt_duration(state.time.saturating_sub(state.piece_spawn_time)),
+                fmt_duration(max_dur)
+            )
+        }),
+        limits
+            .garbage_lines
+            .map(|(_, max_lns)| format!("{}/{} garbage", state.garbage_lines_cleared, max_lns)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(match limits.combination {
+        LimitCombination::Any => " or ",
+        LimitCombination::All => " and ",
+    })
+}
+
+/// The list of known game modes.
+///
+/// Lookups of unknown mode names (such as custom games) fall back to generic behavior.
+#[derive(Clone, Debug)]
+pub struct ModeRegistry {
+    modes: Vec<Rc<dyn ModeEntry>>,
+    n_builtin: usize,
+}
+
+impl ModeRegistry {
+    /// All built-in modes, in the order they appear in the menu.
+    pub fn builtin(combo_bot_enabled: bool) -> Self {
+        let modes: Vec<Rc<dyn ModeEntry>> = vec![
+            Rc::new(Sprint),
+            Rc::new(Marathon),
+            Rc::new(TimeTrial),
+            Rc::new(Master),
+            Rc::new(Puzzle),
+            Rc::new(Descent),
+            Rc::new(Invisible),
+            Rc::new(Big),
+            Rc::new(Cascade),
+            Rc::new(Zone),
+            Rc::new(Cheese),
+            Rc::new(Survival),
+            Rc::new(DigRace),
+            Rc::new(Daily),
+            Rc::new(Combo {
+                bot: combo_bot_enabled,
+            }),
+        ];
+        Self {
+            n_builtin: modes.len(),
+            modes,
+        }
+    }
+
+    /// Adds a mode, replacing a previously registered (non-builtin) mode of the same name.
+    pub fn register(&mut self, mode: impl ModeEntry + 'static) {
+        let mode = Rc::new(mode);
+        if let Some(existing) = self.modes[self.n_builtin..]
+            .iter_mut()
+            .find(|existing| existing.name() == mode.name())
+        {
+            *existing = mode;
+        } else {
+            self.modes.push(mode);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn ModeEntry> {
+        self.modes.iter().map(|mode| mode.as_ref())
+    }
+
+    /// The modes registered in addition to the built-in ones, i.e. mode files and puzzle packs.
+    pub fn user_modes(&self) -> impl Iterator<Item = &dyn ModeEntry> {
+        self.modes[self.n_builtin..]
+            .iter()
+            .map(|mode| mode.as_ref())
+    }
+
+    pub fn get(&self, name: &str) -> &dyn ModeEntry {
+        self.iter()
+            .find(|mode| mode.matches(name))
+            .unwrap_or(&Custom)
+    }
+}
+
+/// Checks that a mode file or puzzle pack may be registered under its name, which must be neither
+/// that of a built-in mode nor one of the names already taken by other modes.
+pub fn check_mode_name(name: &str, taken: &[String]) -> Result<(), String> {
+    if ModeRegistry::builtin(true)
+        .iter()
+        .any(|mode| mode.name() == name || mode.matches(name))
+    {
+        return Err(format!("name {name:?} is taken by a built-in mode"));
+    }
+    if taken.iter().any(|taken_name| taken_name == name) {
+        return Err(format!("name {name:?} is taken by another mode"));
+    }
+    Ok(())
+}
+
+/// Games whose mode is not registered, e.g. those set up in the custom section of the menu.
+#[derive(Debug)]
+struct Custom;
+
+impl ModeEntry for Custom {
+    fn name(&self) -> &str {
+        "Custom"
+    }
+
+    fn description(&self, _store: &GameModeStore) -> String {
+        "".to_string()
+    }
+
+    fn new_game(&self, store: &GameModeStore) -> Game {
+        Game::new(GameMode {
+            name: store.name.clone(),
+            start_level: store.start_level,
+            increment_level: store.increment_level,
+            level_progression: store.level_progression,
+            big: false,
+            cascade: false,
+            zone: false,
+            limits: Limits::default(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Sprint;
+
+impl ModeEntry for Sprint {
+    fn name(&self) -> &str {
+        "40-Lines"
+    }
+
+    fn description(&self, _store: &GameModeStore) -> String {
+        "how fast can you clear?".to_string()
+    }
+
+    fn is_standard(&self) -> bool {
+        true
+    }
+
+    fn new_game(&self, _store: &GameModeStore) -> Game {
+        Game::new(GameMode::sprint(NonZeroU32::try_from(3).unwrap()))
+    }
+
+    fn new_tas_game(&self, _store: &GameModeStore, seed: u64) -> Option<Game> {
+        let mode = GameMode::sprint(NonZeroU32::try_from(3).unwrap());
+        Some(Game::with_seed(mode, GameConfig::default(), seed))
+    }
+
+    fn focus_stat(&self, _mode: &GameMode, state: &GameState) -> (String, String) {
+        ("Time taken:".to_string(), fmt_duration(state.time))
+    }
+
+    fn compare(&self, _mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        // Sort desc by lines.
+        state1
+            .lines_cleared
+            .cmp(&state2.lines_cleared)
+            .reverse()
+            .then_with(||
+            // Sort asc by time.
+            state1.time.cmp(&state2.time))
+    }
+
+    fn summary(&self, mode: &GameMode, state: &GameState) -> String {
+        format!(
+            "40-Lines: {}{}",
+            fmt_duration(state.time),
+            match mode.limits.lines {
+                Some((_, max_lns)) if !matches!(state.end, Some(Ok(_))) => {
+                    format!(" ({}/{} lns)", state.lines_cleared, max_lns)
+                }
+                _ => String::new(),
+            },
+        )
+    }
+}
+
+#[derive(Debug)]
+struct Marathon;
+
+impl ModeEntry for Marathon {
+    fn name(&self) -> &str {
+        "Marathon"
+    }
+
+    fn description(&self, _store: &GameModeStore) -> String {
+        "can you reach speed level 15?".to_string()
+    }
+
+    fn is_standard(&self) -> bool {
+        true
+    }
+
+    fn new_game(&self, _store: &GameModeStore) -> Game {
+        Game::new(GameMode::marathon())
+    }
+
+    fn new_tas_game(&self, _store: &GameModeStore, seed: u64) -> Option<Game> {
+        Some(Game::with_seed(
+            GameMode::marathon(),
+            GameConfig::default(),
+            seed,
+        ))
+    }
+
+    fn focus_stat(&self, _mode: &GameMode, state: &GameState) -> (String, String) {
+        ("Score:".to_string(), state.score.to_string())
+    }
+
+    fn compare(&self, _mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        // Sort desc by level.
+        state1.level.cmp(&state2.level).reverse().then_with(||
+            // Sort desc by score.
+            state1.score.cmp(&state2.score).reverse())
+    }
+
+    fn summary(&self, mode: &GameMode, state: &GameState) -> String {
+        format!(
+            "Marathon: {} pts{}",
+            state.score,
+            match mode.limits.level {
+                Some((_, max_lvl)) if !matches!(state.end, Some(Ok(_))) => {
+                    format!(" ({}/{} lvl)", state.level, max_lvl)
+                }
+                _ => String::new(),
+            },
+        )
+    }
+}
+
+#[derive(Debug)]
+struct TimeTrial;
+
+impl ModeEntry for TimeTrial {
+    fn name(&self) -> &str {
+        "Time Trial"
+    }
+
+    fn description(&self, _store: &GameModeStore) -> String {
+        "get a highscore in 3 minutes!".to_string()
+    }
+
+    fn is_standard(&self) -> bool {
+        true
+    }
+
+    fn new_game(&self, _store: &GameModeStore) -> Game {
+        Game::new(GameMode::ultra(NonZeroU32::MIN))
+    }
+
+    fn new_tas_game(&self, _store: &GameModeStore, seed: u64) -> Option<Game> {
+        let mode = GameMode::ultra(NonZeroU32::MIN);
+        Some(Game::with_seed(mode, GameConfig::default(), seed))
+    }
+
+    fn focus_stat(&self, _mode: &GameMode, state: &GameState) -> (String, String) {
+        ("Score:".to_string(), state.score.to_string())
+    }
+
+    fn compare(&self, _mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        // Sort asc by time.
+        state1.time.cmp(&state2.time).then_with(||
+            // Sort by desc score.
+            state1.score.cmp(&state2.score).reverse())
+    }
+
+    fn summary(&self, mode: &GameMode, state: &GameState) -> String {
+        format!(
+            "Time Trial: {} pts{}",
+            state.score,
+            match mode.limits.time {
+                Some((_, max_dur)) if !matches!(state.end, Some(Ok(_))) => {
+                    format!(
+                        " ({} / {})",
+                        fmt_duration(state.time),
+                        fmt_duration(max_dur)
+                    )
+                }
+                _ => String::new(),
+            },
+        )
+    }
+}
+
+#[derive(Debug)]
+struct Master;
+
+impl ModeEntry for Master {
+    fn name(&self) -> &str {
+        "Master"
+    }
+
+    fn description(&self, store: &GameModeStore) -> String {
+        format!(
+            "clear 100 lines starting at instant gravity, graded. (credit roll: {})",
+            if store.master_credit_roll {
+                "on"
+            } else {
+                "off"
+            }
+        )
+    }
+
+    fn is_standard(&self) -> bool {
+        true
+    }
+
+    fn adjust(&self, store: &mut GameModeStore, _increase: bool) {
+        store.master_credit_roll = !store.master_credit_roll;
+    }
+
+    fn new_game(&self, store: &GameModeStore) -> Game {
+        game_mods::master_mode::new_game(store.master_credit_roll)
+    }
+
+    fn focus_stat(&self, _mode: &GameMode, state: &GameState) -> (String, String) {
+        (
+            "Grade:".to_string(),
+            game_mods::master_mode::fmt_grade(game_mods::master_mode::grade(state)),
+        )
+    }
+
+    fn compare(&self, _mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        use game_mods::master_mode::grade;
+        // Sort desc by grade, then desc by lines, then asc by time.
+        grade(state1)
+            .cmp(&grade(state2))
+            .reverse()
+            .then_with(|| state1.lines_cleared.cmp(&state2.lines_cleared).reverse())
+            .then_with(|| state1.time.cmp(&state2.time))
+    }
+
+    fn summary(&self, _mode: &GameMode, state: &GameState) -> String {
+        format!(
+            "Master: {}, {} lns, {}",
+            game_mods::master_mode::grade_details(state),
+            state.lines_cleared,
+            fmt_duration(state.time)
+        )
+    }
+
+    fn result(&self, _mode: &GameMode, state: &GameState) -> Option<String> {
+        Some(format!(
+            "Grade: {}",
+            game_mods::master_mode::grade_details(state)
+        ))
+    }
+}
+
+#[derive(Debug)]
+struct Puzzle;
+
+impl ModeEntry for Puzzle {
+    fn name(&self) -> &str {
+        "Puzzle"
+    }
+
+    fn description(&self, _store: &GameModeStore) -> String {
+        "24 stages of perfect clears!".to_string()
+    }
+
+    fn new_game(&self, _store: &GameModeStore) -> Game {
+        game_mods::puzzle_mode::new_game()
+    }
+
+    fn focus_stat(&self, _mode: &GameMode, _state: &GameState) -> (String, String) {
+        ("".to_string(), "".to_string())
+    }
+
+    fn compare(&self, _mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        // Sort desc by level.
+        state1.level.cmp(&state2.level).reverse().then_with(||
+            // Sort asc by time.
+            state1.time.cmp(&state2.time))
+    }
+
+    fn summary(&self, mode: &GameMode, state: &GameState) -> String {
+        format!(
+            "{}: {}{}",
+            mode.name,
+            fmt_duration(state.time),
+            match mode.limits.level {
+                Some((_, max_lvl)) if !matches!(state.end, Some(Ok(_))) => {
+                    format!(" ({}/{} lvl)", state.level, max_lvl)
+                }
+                _ => String::new(),
+            },
+        )
+    }
+
+    fn on_game_ended(&self, store: &mut GameModeStore, _mode: &GameMode, state: &GameState) {
+        // Completing all puzzles unlocks Descent.
+        if state.end.is_some_and(|end| end.is_ok()) {
+            store.descent_mode = true;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Descent;
+
+impl ModeEntry for Descent {
+    fn name(&self) -> &str {
+        "Descent"
+    }
+
+    fn description(&self, _store: &GameModeStore) -> String {
+        "spin the piece and collect gems by touching them.".to_string()
+    }
+
+    fn is_unlocked(&self, store: &GameModeStore) -> bool {
+        store.descent_mode
+    }
+
+    fn new_game(&self, _store: &GameModeStore) -> Game {
+        game_mods::descent_mode::new_game()
+    }
+
+    fn compare(&self, _mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        // Sort desc by score.
+        state1.score.cmp(&state2.score).reverse().then_with(||
+            // Sort desc by depth.
+            state1.lines_cleared.cmp(&state2.lines_cleared).reverse())
+    }
+
+    fn summary(&self, _mode: &GameMode, state: &GameState) -> String {
+        format!(
+            "Descent: {} gems, depth {}",
+            state.score, state.lines_cleared,
+        )
+    }
+}
+
+/// Invisible mode, whose games are named after the variant played, e.g. "Invisible (fading)".
+#[derive(Debug)]
+struct Invisible;
+
+impl ModeEntry for Invisible {
+    fn name(&self) -> &str {
+        "Invisible"
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        (0..game_mods::invisible_mode::VARIANTS.len())
+            .any(|variant| game_mods::invisible_mode::game_name(variant) == name)
+    }
+
+    fn description(&self, store: &GameModeStore) -> String {
+        let variants = game_mods::invisible_mode::VARIANTS;
+        format!(
+            "reach level 15 with a disappearing stack. ({})",
+            variants[store.invisible_mode_variant % variants.len()].0
+        )
+    }
+
+    fn adjust(&self, store: &mut GameModeStore, increase: bool) {
+        let n_variants = game_mods::invisible_mode::VARIANTS.len();
+        store.invisible_mode_variant = if increase {
+            (store.invisible_mode_variant + 1) % n_variants
+        } else {
+            (store.invisible_mode_variant + n_variants - 1) % n_variants
+        };
+    }
+
+    fn new_game(&self, store: &GameModeStore) -> Game {
+        game_mods::invisible_mode::new_game(store.invisible_mode_variant)
+    }
+
+    fn focus_stat(&self, mode: &GameMode, state: &GameState) -> (String, String) {
+        Marathon.focus_stat(mode, state)
+    }
+
+    fn compare(&self, mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        Marathon.compare(mode, state1, state2)
+    }
+}
+
+#[derive(Debug)]
+struct Big;
+
+impl Big {
+    fn mode() -> GameMode {
+        GameMode {
+            name: "Big".to_string(),
+            big: true,
+            ..GameMode::marathon()
+        }
+    }
+}
+
+impl ModeEntry for Big {
+    fn name(&self) -> &str {
+        "Big"
+    }
+
+    fn description(&self, _store: &GameModeStore) -> String {
+        "reach level 15 with double-size pieces.".to_string()
+    }
+
+    fn new_game(&self, _store: &GameModeStore) -> Game {
+        Game::new(Self::mode())
+    }
+
+    fn new_tas_game(&self, _store: &GameModeStore, seed: u64) -> Option<Game> {
+        Some(Game::with_seed(Self::mode(), GameConfig::default(), seed))
+    }
+
+    fn focus_stat(&self, mode: &GameMode, state: &GameState) -> (String, String) {
+        Marathon.focus_stat(mode, state)
+    }
+
+    fn compare(&self, mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        Marathon.compare(mode, state1, state2)
+    }
+}
+
+#[derive(Debug)]
+struct Cascade;
+
+impl Cascade {
+    fn mode() -> GameMode {
+        GameMode {
+            name: "Cascade".to_string(),
+            cascade: true,
+            ..GameMode::marathon()
+        }
+    }
+}
+
+impl ModeEntry for Cascade {
+    fn name(&self) -> &str {
+        "Cascade"
+    }
+
+    fn description(&self, _store: &GameModeStore) -> String {
+        "reach level 15 while blocks fall after clears, chaining more clears.".to_string()
+    }
+
+    fn new_game(&self, _store: &GameModeStore) -> Game {
+        Game::new(Self::mode())
+    }
+
+    fn new_tas_game(&self, _store: &GameModeStore, seed: u64) -> Option<Game> {
+        Some(Game::with_seed(Self::mode(), GameConfig::default(), seed))
+    }
+
+    fn focus_stat(&self, mode: &GameMode, state: &GameState) -> (String, String) {
+        Marathon.focus_stat(mode, state)
+    }
+
+    fn compare(&self, mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        Marathon.compare(mode, state1, state2)
+    }
+}
+
+#[derive(Debug)]
+struct Zone;
+
+impl Zone {
+    fn mode() -> GameMode {
+        GameMode {
+            name: "Zone".to_string(),
+            zone: true,
+            ..GameMode::marathon()
+        }
+    }
+}
+
+impl ModeEntry for Zone {
+    fn name(&self) -> &str {
+        "Zone"
+    }
+
+    fn description(&self, _store: &GameModeStore) -> String {
+        "reach level 15, freezing time to stack up huge clears.".to_string()
+    }
+
+    fn new_game(&self, _store: &GameModeStore) -> Game {
+        Game::new(Self::mode())
+    }
+
+    fn new_tas_game(&self, _store: &GameModeStore, seed: u64) -> Option<Game> {
+        Some(Game::with_seed(Self::mode(), GameConfig::default(), seed))
+    }
+
+    fn focus_stat(&self, mode: &GameMode, state: &GameState) -> (String, String) {
+        Marathon.focus_stat(mode, state)
+    }
+
+    fn compare(&self, mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        Marathon.compare(mode, state1, state2)
+    }
+}
+
+#[derive(Debug)]
+struct Cheese;
+
+impl ModeEntry for Cheese {
+    fn name(&self) -> &str {
+        "Cheese"
+    }
+
+    fn description(&self, store: &GameModeStore) -> String {
+        format!(
+            "eat your way through! (limit: {:?})",
+            store.cheese_mode_limit
+        )
+    }
+
+    fn adjust(&self, store: &mut GameModeStore, increase: bool) {
+        store.cheese_mode_limit = match (store.cheese_mode_limit, increase) {
+            (Some(limit), true) => limit.checked_add(1),
+            (Some(limit), false) => NonZeroUsize::try_from(limit.get() - 1).ok(),
+            (None, true) => Some(NonZeroUsize::MIN),
+            (None, false) => None,
+        };
+    }
+
+    fn new_game(&self, store: &GameModeStore) -> Game {
+        game_mods::cheese_mode::new_game(store.cheese_mode_limit, store.cheese_mode_gap_size)
+    }
+
+    fn compare(&self, _mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        // Sort desc by lines.
+        state1
+            .lines_cleared
+            .cmp(&state2.lines_cleared)
+            .reverse()
+            .then_with(||
+            // Sort asc by number of pieces played.
+            state1.pieces_played.iter().sum::<u32>().cmp(&state2.pieces_played.iter().sum::<u32>()))
+    }
+
+    fn summary(&self, mode: &GameMode, state: &GameState) -> String {
+        format!(
+            "Cheese: {} ({}/{} lns)",
+            state.pieces_played.iter().sum::<u32>(),
+            state.lines_cleared,
+            mode.limits
+                .lines
+                .map_or("∞".to_string(), |(_, max_lns)| max_lns.to_string())
+        )
+    }
+}
+
+#[derive(Debug)]
+struct Survival;
+
+impl ModeEntry for Survival {
+    fn name(&self) -> &str {
+        "Survival"
+    }
+
+    fn description(&self, _store: &GameModeStore) -> String {
+        "hold out while garbage keeps rising faster!".to_string()
+    }
+
+    fn new_game(&self, _store: &GameModeStore) -> Game {
+        game_mods::survival_mode::new_game(rand::thread_rng().gen())
+    }
+
+    fn focus_stat(&self, _mode: &GameMode, state: &GameState) -> (String, String) {
+        ("Time survived:".to_string(), fmt_duration(state.time))
+    }
+
+    fn compare(&self, _mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        // Sort desc by time.
+        state1.time.cmp(&state2.time).reverse()
+    }
+
+    fn summary(&self, _mode: &GameMode, state: &GameState) -> String {
+        format!(
+            "Survival: {} ({} lns, lvl {})",
+            fmt_duration(state.time),
+            state.lines_cleared,
+            state.level
+        )
+    }
+
+    fn keep_record(&self, state: &GameState) -> bool {
+        state.pieces_played.iter().sum::<u32>() > 0
+    }
+}
+
+#[derive(Debug)]
+struct DigRace;
+
+impl ModeEntry for DigRace {
+    fn name(&self) -> &str {
+        "Dig Race"
+    }
+
+    fn description(&self, store: &GameModeStore) -> String {
+        format!(
+            "dig through {} messy lines! (hole change: {}%)",
+            game_mods::dig_race_mode::LINES,
+            store.dig_race_hole_change
+        )
+    }
+
+    fn adjust(&self, store: &mut GameModeStore, increase: bool) {
+        store.dig_race_hole_change = if increase {
+            (store.dig_race_hole_change + 10).min(100)
+        } else {
+            store.dig_race_hole_change.saturating_sub(10)
+        };
+    }
+
+    fn new_game(&self, store: &GameModeStore) -> Game {
+        game_mods::dig_race_mode::new_game(
+            rand::thread_rng().gen(),
+            f64::from(store.dig_race_hole_change) / 100.0,
+        )
+    }
+
+    fn focus_stat(&self, _mode: &GameMode, state: &GameState) -> (String, String) {
+        ("Time taken:".to_string(), fmt_duration(state.time))
+    }
+
+    fn compare(&self, _mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        // Sort desc by garbage lines.
+        state1
+            .garbage_lines_cleared
+            .cmp(&state2.garbage_lines_cleared)
+            .reverse()
+            .then_with(||
+            // Sort asc by time.
+            state1.time.cmp(&state2.time))
+    }
+
+    fn summary(&self, mode: &GameMode, state: &GameState) -> String {
+        format!(
+            "Dig Race: {} ({})",
+            fmt_duration(state.time),
+            fmt_limits(&mode.limits, state)
+        )
+    }
+
+    fn keep_record(&self, state: &GameState) -> bool {
+        state.end.is_some_and(|end| end.is_ok()) || state.garbage_lines_cleared > 0
+    }
+}
+
+/// Daily challenge, whose games are named after the day and variant played, e.g.
+/// "Daily 2024-07-01 (Sprint)".
+#[derive(Debug)]
+struct Daily;
+
+impl Daily {
+    /// The mode whose games the given daily game is ranked like.
+    fn ranked_like(mode: &GameMode) -> &'static dyn ModeEntry {
+        match Challenge::of_game_name(&mode.name).map(|challenge| challenge.variant) {
+            Some(Variant::Sprint) => &Sprint,
+            Some(Variant::Ultra) => &TimeTrial,
+            Some(Variant::Marathon) | None => &Marathon,
+            Some(Variant::Cheese) => &DigRace,
+            Some(Variant::Survival) => &Survival,
+        }
+    }
+}
+
+impl ModeEntry for Daily {
+    fn name(&self) -> &str {
+        "Daily"
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        Challenge::of_game_name(name).is_some()
+    }
+
+    fn description(&self, store: &GameModeStore) -> String {
+        let challenge = Challenge::today();
+        format!(
+            "today: {}. (streak: {})",
+            challenge.describe(),
+            store.daily_streak(challenge.date)
+        )
+    }
+
+    fn new_game(&self, _store: &GameModeStore) -> Game {
+        Challenge::today().new_game()
+    }
+
+    fn configure(&self, config: &mut GameConfig) {
+        Challenge::today().configure(config);
+    }
+
+    fn focus_stat(&self, mode: &GameMode, state: &GameState) -> (String, String) {
+        Self::ranked_like(mode).focus_stat(mode, state)
+    }
+
+    fn compare(&self, mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        Self::ranked_like(mode).compare(mode, state1, state2)
+    }
+
+    fn summary(&self, mode: &GameMode, state: &GameState) -> String {
+        let (_, focus_stat) = self.focus_stat(mode, state);
+        let limit_texts = fmt_limits(&mode.limits, state);
+        format!(
+            "{}: {}{}",
+            mode.name,
+            focus_stat,
+            if state.end.is_some_and(|end| end.is_ok()) || limit_texts.is_empty() {
+                String::new()
+            } else {
+                format!(" ({limit_texts})")
+            }
+        )
+    }
+
+    fn keep_record(&self, state: &GameState) -> bool {
+        game_mods::daily_mode::played(state)
+    }
+
+    fn on_game_ended(&self, store: &mut GameModeStore, mode: &GameMode, state: &GameState) {
+        let Some(challenge) = Challenge::of_game_name(&mode.name) else {
+            return;
+        };
+        if game_mods::daily_mode::played(state) {
+            store.daily_streak = game_mods::daily_mode::next_streak(
+                store.daily_streak,
+                store.daily_last_played,
+                challenge.date,
+            );
+            store.daily_last_played = Some(challenge.date);
+        }
+    }
+}
+
+/// Combo mode, whose games are named "Combo (Bot)" if they are played by the combo bot.
+#[derive(Debug)]
+struct Combo {
+    bot: bool,
+}
+
+impl ModeEntry for Combo {
+    fn name(&self) -> &str {
+        "Combo"
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        name == "Combo" || name == "Combo (Bot)"
+    }
+
+    fn description(&self, store: &GameModeStore) -> String {
+        format!(
+            "how long can you chain? (start: {:b})",
+            store.combo_starting_layout
+        )
+    }
+
+    fn adjust(&self, store: &mut GameModeStore, increase: bool) {
+        let layouts = game_mods::combo_mode::LAYOUTS;
+        let new_layout_idx = if let Some(i) = layouts
+            .iter()
+            .position(|lay| *lay == store.combo_starting_layout)
+        {
+            if increase {
+                (i + 1) % layouts.len()
+            } else {
+                (i + layouts.len() - 1) % layouts.len()
+            }
+        } else {
+            0
+        };
+        store.combo_starting_layout = layouts[new_layout_idx];
+    }
+
+    fn new_game(&self, store: &GameModeStore) -> Game {
+        let mut combo_game = game_mods::combo_mode::new_game(store.combo_starting_layout);
+        if self.bot {
+            // SAFETY: We only add the information that this will be botted.
+            unsafe {
+                combo_game.mode_mut().name.push_str(" (Bot)");
+            }
+        }
+        combo_game
+    }
+
+    fn played_by_combo_bot(&self) -> bool {
+        self.bot
+    }
+
+    fn summary(&self, mode: &GameMode, state: &GameState) -> String {
+        format!("{}: {} lns", mode.name, state.lines_cleared)
+    }
+
+    fn keep_record(&self, state: &GameState) -> bool {
+        state.end.is_some_and(|end| end.is_ok()) || state.lines_cleared > 9
+    }
+}
+
+impl ModeEntry for ModeFile {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self, _store: &GameModeStore) -> String {
+        self.description.clone()
+    }
+
+    fn new_game(&self, _store: &GameModeStore) -> Game {
+        ModeFile::new_game(self, rand::thread_rng().gen())
+    }
+
+    fn new_tas_game(&self, _store: &GameModeStore, seed: u64) -> Option<Game> {
+        self.is_plain().then(|| ModeFile::new_game(self, seed))
+    }
+
+    fn configure(&self, config: &mut GameConfig) {
+        ModeFile::configure(self, config)
+    }
+}
+
+impl ModeEntry for PuzzlePack {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self, _store: &GameModeStore) -> String {
+        if self.description.is_empty() {
+            format!("{} puzzles.", self.puzzles.len())
+        } else {
+            self.description.clone()
+        }
+    }
+
+    fn new_game(&self, _store: &GameModeStore) -> Game {
+        game_mods::puzzle_mode::new_pack_game(self)
+    }
+
+    fn puzzle_pack(&self) -> Option<&PuzzlePack> {
+        Some(self)
+    }
+
+    fn focus_stat(&self, mode: &GameMode, state: &GameState) -> (String, String) {
+        Puzzle.focus_stat(mode, state)
+    }
+
+    fn compare(&self, mode: &GameMode, state1: &GameState, state2: &GameState) -> Ordering {
+        Puzzle.compare(mode, state1, state2)
+    }
+
+    fn summary(&self, mode: &GameMode, state: &GameState) -> String {
+        Puzzle.summary(mode, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tetrs_engine::GameOver;
+
+    use super::*;
+
+    fn state(edit: impl FnOnce(&mut GameState)) -> GameState {
+        let mut state = Game::new(GameMode::marathon()).state().clone();
+        edit(&mut state);
+        state
+    }
+
+    #[test]
+    fn sprints_are_ranked_by_time_and_descents_by_score() {
+        let modes = ModeRegistry::builtin(false);
+        let mode = GameMode::marathon();
+        let fast = state(|state| state.time = Duration::from_secs(50));
+        let slow = state(|state| state.time = Duration::from_secs(60));
+        let sprint = modes.get("40-Lines");
+        assert_eq!(sprint.compare(&mode, &fast, &slow), Ordering::Less);
+        assert_eq!(sprint.compare(&mode, &slow, &fast), Ordering::Greater);
+        let rich = state(|state| state.score = 20);
+        let poor = state(|state| {
+            state.score = 10;
+            state.lines_cleared = 100;
+        });
+        let descent = modes.get("Descent");
+        assert_eq!(descent.compare(&mode, &rich, &poor), Ordering::Less);
+        assert_eq!(descent.compare(&mode, &poor, &rich), Ordering::Greater);
+    }
+
+    #[test]
+    fn descent_is_unlocked_by_completing_puzzle_mode() {
+        let modes = ModeRegistry::builtin(false);
+        let mut store = GameModeStore::default();
+        let (puzzle, descent) = (modes.get("Puzzle"), modes.get("Descent"));
+        let mode = GameMode::marathon();
+        assert!(!descent.is_unlocked(&store));
+        puzzle.on_game_ended(
+            &mut store,
+            &mode,
+            &state(|state| state.end = Some(Err(GameOver::BlockOut))),
+        );
+        assert!(!descent.is_unlocked(&store));
+        puzzle.on_game_ended(&mut store, &mode, &state(|state| state.end = Some(Ok(()))));
+        assert!(descent.is_unlocked(&store));
+    }
+
+    #[test]
+    fn combo_games_are_played_by_the_bot_only_if_enabled() {
+        assert!(ModeRegistry::builtin(true)
+            .get("Combo (Bot)")
+            .played_by_combo_bot());
+        assert!(!ModeRegistry::builtin(false)
+            .get("Combo")
+            .played_by_combo_bot());
+        assert!(!ModeRegistry::builtin(true)
+            .get("Marathon")
+            .played_by_combo_bot());
+    }
+
+    #[test]
+    fn limits_are_joined_by_their_combination() {
+        let state = state(|state| state.lines_cleared = 3);
+        let mut limits = Limits {
+            lines: Some((true, 10)),
+            score: Some((false, 100)),
+            ..Default::default()
+        };
+        assert_eq!(fmt_limits(&limits, &state), "3/10 lns or 0/100 pts");
+        limits.combination = LimitCombination::All;
+        assert_eq!(fmt_limits(&limits, &state), "3/10 lns and 0/100 pts");
+    }
+
+    #[test]
+    fn invisible_matches_exactly_its_variants() {
+        let modes = ModeRegistry::builtin(false);
+        for variant in 0..game_mods::invisible_mode::VARIANTS.len() {
+            let name = game_mods::invisible_mode::game_name(variant);
+            assert_eq!(modes.get(&name).name(), "Invisible");
+        }
+        for name in ["Invisible", "Invisible (fading) 2", "Invisible Sprint"] {
+            assert_eq!(modes.get(name).name(), "Custom", "{name}");
+        }
+    }
+}
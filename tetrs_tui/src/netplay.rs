@@ -0,0 +1,386 @@
+use std::{
+    collections::BTreeSet,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use tetrs_engine::{Game, GameConfig, GameMode, GameState};
+
+/// Bumped whenever the messages exchanged between two instances change.
+pub const PROTOCOL_VERSION: u32 = 1;
+/// How long to wait for the other side to connect (when joining) and complete the handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug)]
+pub enum NetplayRole {
+    Host(String),
+    Join(String),
+}
+
+impl std::fmt::Display for NetplayRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetplayRole::Host(addr) => write!(f, "hosting on {addr}"),
+            NetplayRole::Join(addr) => write!(f, "joining {addr}"),
+        }
+    }
+}
+
+/// Messages sent between two instances, one JSON object per line.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum NetMessage {
+    /// Sent by the joining side to open the handshake.
+    Hello {
+        version: u32,
+        rules: serde_json::Value,
+    },
+    /// Sent by the host if the handshake succeeded.
+    Accept,
+    /// Sent by the host if the handshake failed, with a reason.
+    Reject(String),
+    /// Snapshot of the sender's game for the opponent to render.
+    State {
+        state: Box<GameState>,
+        garbage_pending: u32,
+        lines_sent: u32,
+        lines_received: u32,
+    },
+    /// Garbage sent to the opponent, numbered consecutively starting from `0`.
+    Attack { seq: u32, lines: u32, hole: usize },
+    /// The sender's game ended.
+    GameOver,
+    /// The sender gave up.
+    Forfeit,
+}
+
+/// The rules that both sides must agree on, i.e. everything except personal handling settings.
+pub fn rules(config: &GameConfig, mode: &GameMode) -> serde_json::Value {
+    let mut config = config.clone();
+    config.delayed_auto_shift = Duration::ZERO;
+    config.auto_repeat_rate = Duration::ZERO;
+    config.no_soft_drop_lock = false;
+    serde_json::json!({
+        "config": config,
+        "mode": mode,
+    })
+}
+
+/// Checks that a game state received from the opponent can be rendered, returning what is wrong
+/// with it otherwise.
+pub fn state_error(state: &GameState) -> Option<&'static str> {
+    if state.board.len() != Game::HEIGHT {
+        return Some("board of the wrong height");
+    }
+    if state
+        .board
+        .iter()
+        .flatten()
+        .flatten()
+        .any(|tile| !matches!(tile.get(), 1..=7 | 253..=255))
+    {
+        return Some("unknown tile on the board");
+    }
+    if let Some((piece, _)) = state.active_piece_data {
+        let (x, y) = piece.position;
+        if x >= Game::WIDTH || y >= Game::HEIGHT || !piece.fits(&state.board) {
+            return Some("active piece out of place");
+        }
+    }
+    None
+}
+
+/// Lists which rules differ between the two sides, including rules only one side knows of.
+fn rule_mismatches(ours: &serde_json::Value, theirs: &serde_json::Value) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    for section in ["config", "mode"] {
+        match (ours.get(section), theirs.get(section)) {
+            (Some(serde_json::Value::Object(ours)), Some(serde_json::Value::Object(theirs))) => {
+                let keys = ours.keys().chain(theirs.keys()).collect::<BTreeSet<_>>();
+                for key in keys {
+                    if ours.get(key) != theirs.get(key) {
+                        mismatches.push(key.clone());
+                    }
+                }
+            }
+            (ours, theirs) => {
+                if ours != theirs {
+                    mismatches.push(section.to_string());
+                }
+            }
+        }
+    }
+    mismatches
+}
+
+#[derive(Debug)]
+pub struct NetplayConnection {
+    stream: TcpStream,
+    message_receiver: Receiver<Result<NetMessage, String>>,
+    _handle: JoinHandle<()>,
+}
+
+impl NetplayConnection {
+    /// Waits for a single opponent to connect, calling `keep_waiting` regularly to allow cancelling.
+    pub fn host(
+        addr: &str,
+        rules: &serde_json::Value,
+        mut keep_waiting: impl FnMut() -> io::Result<bool>,
+    ) -> io::Result<Option<Self>> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let stream = loop {
+            match listener.accept() {
+                Ok((stream, _)) => break stream,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    if !keep_waiting()? {
+                        return Ok(None);
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        stream.set_nonblocking(false)?;
+        let (mut stream, mut reader) = Self::start_handshake(stream)?;
+        match recv_handshake(&mut reader)? {
+            NetMessage::Hello {
+                version,
+                rules: their_rules,
+            } => {
+                let reason = if version != PROTOCOL_VERSION {
+                    Some(format!(
+                        "protocol version mismatch (host: {PROTOCOL_VERSION}, joined: {version})"
+                    ))
+                } else {
+                    let mismatches = rule_mismatches(rules, &their_rules);
+                    (!mismatches.is_empty())
+                        .then(|| format!("rule mismatch: {}", mismatches.join(", ")))
+                };
+                if let Some(reason) = reason {
+                    write_message(&mut stream, &NetMessage::Reject(reason.clone()))?;
+                    return Err(io::Error::other(reason));
+                }
+                write_message(&mut stream, &NetMessage::Accept)?;
+                Self::new(stream, reader).map(Some)
+            }
+            other => Err(io::Error::other(format!(
+                "unexpected handshake message: {other:?}"
+            ))),
+        }
+    }
+
+    /// Connects to a hosting opponent.
+    pub fn join(addr: &str, rules: &serde_json::Value) -> io::Result<Self> {
+        let mut last_error = None;
+        let mut connected = None;
+        for socket_addr in addr.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&socket_addr, HANDSHAKE_TIMEOUT) {
+                Ok(stream) => {
+                    connected = Some(stream);
+                    break;
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        let Some(stream) = connected else {
+            return Err(last_error.unwrap_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "address could not be resolved")
+            }));
+        };
+        let (mut stream, mut reader) = Self::start_handshake(stream)?;
+        write_message(
+            &mut stream,
+            &NetMessage::Hello {
+                version: PROTOCOL_VERSION,
+                rules: rules.clone(),
+            },
+        )?;
+        match recv_handshake(&mut reader)? {
+            NetMessage::Accept => Self::new(stream, reader),
+            NetMessage::Reject(reason) => Err(io::Error::other(reason)),
+            other => Err(io::Error::other(format!(
+                "unexpected handshake message: {other:?}"
+            ))),
+        }
+    }
+
+    /// Makes the handshake on a fresh stream give up if the opponent stops responding.
+    fn start_handshake(stream: TcpStream) -> io::Result<(TcpStream, BufReader<TcpStream>)> {
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        stream.set_write_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        let reader = BufReader::new(stream.try_clone()?);
+        Ok((stream, reader))
+    }
+
+    /// Starts receiving messages after a successful handshake.
+    fn new(stream: TcpStream, reader: BufReader<TcpStream>) -> io::Result<Self> {
+        // The game itself may go without messages for a while.
+        stream.set_read_timeout(None)?;
+        stream.set_write_timeout(None)?;
+        let (message_sender, message_receiver) = mpsc::channel();
+        let join_handle = thread::spawn(move || {
+            for line in reader.lines() {
+                let message = match line {
+                    Ok(line) => serde_json::from_str::<NetMessage>(&line)
+                        .map_err(|e| format!("malformed message ({e})")),
+                    Err(e) => Err(format!("connection lost ({e})")),
+                };
+                let failed = message.is_err();
+                if message_sender.send(message).is_err() || failed {
+                    return;
+                }
+            }
+            let _ = message_sender.send(Err("connection closed by opponent".to_string()));
+        });
+        Ok(NetplayConnection {
+            stream,
+            message_receiver,
+            _handle: join_handle,
+        })
+    }
+
+    pub fn send(&mut self, message: &NetMessage) -> io::Result<()> {
+        write_message(&mut self.stream, message)
+    }
+
+    /// Returns the next message if one arrived, or an error message if the connection broke.
+    pub fn try_recv(&mut self) -> Result<Option<NetMessage>, String> {
+        match self.message_receiver.try_recv() {
+            Ok(message) => message.map(Some),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err("connection closed".to_string()),
+        }
+    }
+}
+
+impl Drop for NetplayConnection {
+    fn drop(&mut self) {
+        // Also unblocks the reading thread.
+        let _ = self.stream.shutdown(std::net::Shutdown::Both);
+    }
+}
+
+fn write_message(stream: &mut TcpStream, message: &NetMessage) -> io::Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())
+}
+
+/// Reads a handshake message, failing if the opponent does not send one in time.
+fn recv_handshake(reader: &mut BufReader<TcpStream>) -> io::Result<NetMessage> {
+    let mut line = String::new();
+    match reader.read_line(&mut line) {
+        Ok(0) => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "connection closed by opponent",
+        )),
+        Ok(_) => serde_json::from_str(&line)
+            .map_err(|e| io::Error::other(format!("malformed message ({e})"))),
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ) =>
+        {
+            Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "opponent did not respond",
+            ))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hosts on a free local port in the background, returning the address to join.
+    fn host_in_background(
+        rules: serde_json::Value,
+    ) -> (String, JoinHandle<io::Result<Option<NetplayConnection>>>) {
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .unwrap()
+            .to_string();
+        let host_addr = addr.clone();
+        let handle = thread::spawn(move || {
+            NetplayConnection::host(&host_addr, &rules, || {
+                thread::sleep(Duration::from_millis(10));
+                Ok(true)
+            })
+        });
+        // Give the host time to start listening.
+        thread::sleep(Duration::from_millis(100));
+        (addr, handle)
+    }
+
+    #[test]
+    fn rule_mismatches_are_symmetric() {
+        let ours = rules(&GameConfig::default(), &GameMode::marathon());
+        let mut theirs = ours.clone();
+        theirs["config"]["preview_count"] = serde_json::json!(1);
+        theirs["config"]["rule_from_the_future"] = serde_json::json!(true);
+        theirs["mode"]
+            .as_object_mut()
+            .unwrap()
+            .remove("start_level");
+        let expected = ["preview_count", "rule_from_the_future", "start_level"];
+        assert_eq!(rule_mismatches(&ours, &theirs), expected);
+        assert_eq!(rule_mismatches(&theirs, &ours), expected);
+        assert!(rule_mismatches(&ours, &ours.clone()).is_empty());
+    }
+
+    #[test]
+    fn states_which_cannot_be_rendered_are_rejected() {
+        let mut game = Game::with_seed(GameMode::marathon(), GameConfig::default(), 0);
+        assert!(game.update(None, Duration::from_millis(1)).is_ok());
+        let state = game.state().clone();
+        assert_eq!(state_error(&state), None);
+        let mut short_board = state.clone();
+        short_board.board.truncate(4);
+        assert!(state_error(&short_board).is_some());
+        let mut unknown_tile = state.clone();
+        unknown_tile.board[0][0] = std::num::NonZeroU8::new(100);
+        assert!(state_error(&unknown_tile).is_some());
+        let mut misplaced_piece = state.clone();
+        misplaced_piece
+            .active_piece_data
+            .as_mut()
+            .unwrap()
+            .0
+            .position = (usize::MAX, 0);
+        assert!(state_error(&misplaced_piece).is_some());
+        let mut overlapping_piece = state;
+        let (piece, _) = overlapping_piece.active_piece_data.unwrap();
+        let ((x, y), tile) = piece.tiles()[0];
+        overlapping_piece.board[y][x] = Some(tile);
+        assert!(state_error(&overlapping_piece).is_some());
+    }
+
+    #[test]
+    fn handshake_accepts_equal_rules() {
+        let rules = rules(&GameConfig::default(), &GameMode::marathon());
+        let (addr, host) = host_in_background(rules.clone());
+        let mut joined = NetplayConnection::join(&addr, &rules).unwrap();
+        let mut hosted = host.join().unwrap().unwrap().unwrap();
+        hosted.send(&NetMessage::GameOver).unwrap();
+        let received = (0..100).find_map(|_| {
+            thread::sleep(Duration::from_millis(10));
+            joined.try_recv().unwrap()
+        });
+        assert!(matches!(received, Some(NetMessage::GameOver)));
+    }
+
+    #[test]
+    fn handshake_rejects_different_rules() {
+        let (addr, host) = host_in_background(rules(&GameConfig::default(), &GameMode::marathon()));
+        let their_rules = rules(&GameConfig::default(), &GameMode::master());
+        let error = NetplayConnection::join(&addr, &their_rules).unwrap_err();
+        assert!(error.to_string().starts_with("rule mismatch"));
+        assert!(host.join().unwrap().is_err());
+    }
+}
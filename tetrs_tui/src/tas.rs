@@ -0,0 +1,314 @@
+/*!
+This module contains what is needed to play a game 'tool-assisted': with a game clock that can be
+stopped and stepped at will, savestates, and a record of all inputs that can be exported as a replay.
+*/
+
+use std::time::{Duration, Instant};
+
+use tetrs_engine::{
+    Button, ButtonsPressed, Game, GameConfig, GameMode, GameSnapshot, GameState, GameTime,
+};
+
+use crate::terminal_app::RunningGameStats;
+
+/// The virtual clock games are updated by, shared by both players of a versus game.
+///
+/// It only advances while running, so time spent paused is never seen by the game.
+#[derive(Eq, PartialEq, Clone, Copy, Default, Debug)]
+pub struct GameClock {
+    /// Game time accumulated up until the clock was last (re)started.
+    elapsed: GameTime,
+    /// When the clock was last (re)started, if it is running.
+    running_since: Option<Instant>,
+}
+
+impl GameClock {
+    /// The current game time.
+    pub fn now(&self) -> GameTime {
+        self.at(Instant::now())
+    }
+
+    /// The game time at some instant while the clock is running (or has been stopped since).
+    pub fn at(&self, instant: Instant) -> GameTime {
+        match self.running_since {
+            Some(since) => self.elapsed + instant.saturating_duration_since(since),
+            None => self.elapsed,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    /// Starts the clock, if it is not running already.
+    pub fn resume(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    /// Stops the clock, keeping the game time reached.
+    pub fn pause(&mut self) {
+        self.elapsed = self.now();
+        self.running_since = None;
+    }
+
+    /// Moves the clock to some game time, keeping it running if it was.
+    pub fn set(&mut self, time: GameTime) {
+        self.elapsed = time;
+        if self.running_since.is_some() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+}
+
+/// What the player is currently typing a savestate name for.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum TasPrompt {
+    SaveState,
+    LoadState,
+}
+
+/// A saved point of a tool-assisted game to return to.
+#[derive(Clone, Debug)]
+pub struct Savestate {
+    snapshot: GameSnapshot,
+    buttons_pressed: ButtonsPressed,
+    running_game_stats: RunningGameStats,
+    /// How many of the recorded inputs had been made by then.
+    inputs_len: usize,
+}
+
+/// A finished (or abandoned) tool-assisted game, as written to a replay file.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Replay<'a> {
+    pub mode: &'a GameMode,
+    pub config: &'a GameConfig,
+    /// The seed the game's randomness was drawn from, which the inputs only reproduce it with.
+    pub seed: u64,
+    /// Every change of buttons pressed, with the game time it was made at.
+    pub inputs: &'a [(GameTime, ButtonsPressed)],
+    pub last_state: &'a GameState,
+}
+
+/// The state of a game played tool-assisted, next to the game itself.
+#[derive(Clone, Default, Debug)]
+pub struct TasSession {
+    /// The configuration the game was started with, before its piece generator advanced.
+    config: GameConfig,
+    pub buttons_pressed: ButtonsPressed,
+    /// Every change of buttons pressed so far, with the game time it was made at.
+    pub inputs: Vec<(GameTime, ButtonsPressed)>,
+    /// Named savestates, in the order they were first saved.
+    savestates: Vec<(String, Savestate)>,
+    pub prompt: Option<(TasPrompt, String)>,
+    pub message: String,
+}
+
+impl TasSession {
+    /// The length of a frame advance when the game itself does not run in frames.
+    pub const FRAME: Duration = Duration::from_nanos(16_666_667);
+
+    pub const BUTTONS: [Button; 10] = [
+        Button::MoveLeft,
+        Button::MoveRight,
+        Button::RotateLeft,
+        Button::RotateRight,
+        Button::RotateAround,
+        Button::DropSoft,
+        Button::DropHard,
+        Button::DropSonic,
+        Button::Hold,
+        Button::ActivateZone,
+    ];
+
+    /// Starts a session for a game that has not been played yet.
+    pub fn new(game: &Game) -> Self {
+        Self {
+            config: game.config().clone(),
+            ..Default::default()
+        }
+    }
+
+    /// The game time of the start of the frame after the current one.
+    pub fn next_frame(game: &Game) -> GameTime {
+        let time = game.state().time;
+        game.frame_at(time)
+            .and_then(|frame| game.frame_start(frame + 1))
+            .unwrap_or(time + Self::FRAME)
+    }
+
+    /// The game time of the next thing scheduled to happen in the game, if anything is.
+    pub fn next_event(game: &Game) -> Option<GameTime> {
+        game.state().events.values().copied().min()
+    }
+
+    pub fn savestate_names(&self) -> impl Iterator<Item = &str> {
+        self.savestates.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Saves the game under some name, replacing any savestate of the same name.
+    pub fn save(&mut self, name: String, game: &Game, running_game_stats: &RunningGameStats) {
+        let savestate = Savestate {
+            snapshot: game.save_state(),
+            buttons_pressed: self.buttons_pressed,
+            running_game_stats: running_game_stats.clone(),
+            inputs_len: self.inputs.len(),
+        };
+        if let Some((_, existing)) = self
+            .savestates
+            .iter_mut()
+            .find(|(existing_name, _)| *existing_name == name)
+        {
+            *existing = savestate;
+        } else {
+            self.savestates.push((name, savestate));
+        }
+    }
+
+    /// Returns the game to a named savestate, forgetting the inputs made since.
+    ///
+    /// Returns false if there is no savestate of that name.
+    pub fn load(
+        &mut self,
+        name: &str,
+        game: &mut Game,
+        running_game_stats: &mut RunningGameStats,
+    ) -> bool {
+        let Some((_, savestate)) = self
+            .savestates
+            .iter()
+            .find(|(existing_name, _)| existing_name == name)
+        else {
+            return false;
+        };
+        game.load_state(&savestate.snapshot);
+        self.buttons_pressed = savestate.buttons_pressed;
+        running_game_stats.clone_from(&savestate.running_game_stats);
+        self.inputs.truncate(savestate.inputs_len);
+        true
+    }
+
+    /// The savestate name that comes after the one given, for cycling through all of them.
+    pub fn next_savestate_name(&self, name: &str) -> Option<&str> {
+        let next = self
+            .savestates
+            .iter()
+            .position(|(existing_name, _)| existing_name == name)
+            .map_or(0, |i| (i + 1) % self.savestates.len());
+        self.savestates.get(next).map(|(name, _)| name.as_str())
+    }
+
+    pub fn replay<'a>(&'a self, game: &'a Game) -> Replay<'a> {
+        Replay {
+            mode: game.mode(),
+            config: &self.config,
+            seed: game.seed(),
+            inputs: &self.inputs,
+            last_state: game.state(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use super::*;
+
+    fn tas_game(tick_rate: Option<NonZeroU32>) -> Game {
+        let config = GameConfig {
+            tick_rate,
+            ..GameConfig::default()
+        };
+        Game::with_seed(GameMode::marathon(), config, 5)
+    }
+
+    #[test]
+    fn frame_advances_step_to_the_next_frame() {
+        let mut game = tas_game(None);
+        assert!(game.update(None, Duration::from_millis(5)).is_ok());
+        assert_eq!(
+            TasSession::next_frame(&game),
+            Duration::from_millis(5) + TasSession::FRAME
+        );
+        let mut game = tas_game(NonZeroU32::new(60));
+        assert!(game.update(None, Duration::from_millis(5)).is_ok());
+        assert_eq!(TasSession::next_frame(&game), game.frame_start(1).unwrap());
+        assert!(game.update(None, TasSession::next_frame(&game)).is_ok());
+        assert_eq!(TasSession::next_frame(&game), game.frame_start(2).unwrap());
+        // A paused clock stays at the frame it was stepped to.
+        let mut clock = GameClock::default();
+        clock.set(game.state().time);
+        assert!(!clock.is_running());
+        assert_eq!(clock.now(), game.frame_start(1).unwrap());
+    }
+
+    /// Presses a button for one frame, recording the inputs like the TAS controls do.
+    fn press(session: &mut TasSession, game: &mut Game, button: Button) {
+        for pressed in [true, false] {
+            session.buttons_pressed[button] = pressed;
+            let time = TasSession::next_frame(game);
+            session.inputs.push((time, session.buttons_pressed));
+            assert!(game.update(Some(session.buttons_pressed), time).is_ok());
+        }
+    }
+
+    #[test]
+    fn savestates_return_to_the_same_game() {
+        let mut game = tas_game(NonZeroU32::new(60));
+        let mut session = TasSession::default();
+        let mut stats = RunningGameStats::default();
+        press(&mut session, &mut game, Button::MoveLeft);
+        session.save("start".to_string(), &game, &stats);
+        let saved_state = game.state().clone();
+        let saved_inputs = session.inputs.clone();
+        for button in [Button::RotateRight, Button::DropHard, Button::Hold] {
+            press(&mut session, &mut game, button);
+        }
+        stats.1.push(1);
+        let played_state = game.state().clone();
+        assert_ne!(played_state, saved_state);
+        assert!(!session.load("unknown", &mut game, &mut stats));
+        assert!(session.load("start", &mut game, &mut stats));
+        assert_eq!(*game.state(), saved_state);
+        assert_eq!(session.inputs, saved_inputs);
+        assert_eq!(stats, RunningGameStats::default());
+        // Playing the same inputs again gives the same game.
+        for button in [Button::RotateRight, Button::DropHard, Button::Hold] {
+            press(&mut session, &mut game, button);
+        }
+        assert_eq!(*game.state(), played_state);
+    }
+
+    #[test]
+    fn replays_reproduce_the_game_they_were_saved_from() {
+        #[derive(serde::Deserialize)]
+        struct LoadedReplay {
+            mode: GameMode,
+            config: GameConfig,
+            seed: u64,
+            inputs: Vec<(GameTime, ButtonsPressed)>,
+            last_state: GameState,
+        }
+        let mut game = tas_game(NonZeroU32::new(60));
+        let mut session = TasSession::new(&game);
+        for _ in 0..4 {
+            for button in [Button::RotateRight, Button::MoveLeft, Button::DropHard] {
+                press(&mut session, &mut game, button);
+            }
+        }
+        let saved = serde_json::to_string(&session.replay(&game)).unwrap();
+        let replay: LoadedReplay = serde_json::from_str(&saved).unwrap();
+        let mut replayed_game = Game::with_seed(replay.mode, replay.config, replay.seed);
+        for (time, buttons_pressed) in replay.inputs {
+            assert!(replayed_game.update(Some(buttons_pressed), time).is_ok());
+        }
+        assert_eq!(replayed_game.state().board, replay.last_state.board);
+        assert_eq!(
+            replayed_game.state().pieces_played,
+            replay.last_state.pieces_played
+        );
+        assert_eq!(replayed_game.state().board, game.state().board);
+    }
+}
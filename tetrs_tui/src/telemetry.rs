@@ -0,0 +1,223 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, BufWriter, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use tetrs_engine::{Feedback, GameConfig, GameMode, GameState, GameTime};
+
+/// How often (in game time) a full snapshot of the game state is streamed.
+const SNAPSHOT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long to wait for a TCP target to accept the connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Where the telemetry stream is sent to.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+enum Target<'a> {
+    Tcp(&'a str),
+    Unix(&'a str),
+    File(&'a str),
+}
+
+impl<'a> Target<'a> {
+    fn parse(target: &'a str) -> Self {
+        if let Some(addr) = target.strip_prefix("tcp:") {
+            Target::Tcp(addr)
+        } else if let Some(path) = target.strip_prefix("unix:") {
+            Target::Unix(path)
+        } else {
+            Target::File(target)
+        }
+    }
+}
+
+/// One line of the telemetry stream.
+#[derive(serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum TelemetryLine<'a> {
+    Start {
+        mode: &'a GameMode,
+        config: &'a GameConfig,
+    },
+    Feedback {
+        time: GameTime,
+        feedback: &'a Feedback,
+    },
+    Snapshot {
+        state: &'a GameState,
+    },
+    End {
+        state: &'a GameState,
+    },
+}
+
+/// Streams the feedback and state of a running game as JSON lines to some output.
+///
+/// The target is either `unix:PATH` (Unix socket), `tcp:ADDR` or a path to a file to append to.
+pub struct Telemetry {
+    writer: Box<dyn Write + Send>,
+    last_snapshot: Option<GameTime>,
+}
+
+impl std::fmt::Debug for Telemetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Telemetry")
+            .field("last_snapshot", &self.last_snapshot)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Telemetry {
+    pub fn open(target: &str) -> io::Result<Self> {
+        let writer: Box<dyn Write + Send> = match Target::parse(target) {
+            Target::Tcp(addr) => Box::new(Self::connect(addr)?),
+            Target::Unix(path) => {
+                #[cfg(unix)]
+                {
+                    Box::new(std::os::unix::net::UnixStream::connect(path)?)
+                }
+                #[cfg(not(unix))]
+                {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        format!("unix sockets are unavailable on this platform ({path})"),
+                    ));
+                }
+            }
+            Target::File(path) => {
+                Box::new(OpenOptions::new().create(true).append(true).open(path)?)
+            }
+        };
+        Ok(Telemetry {
+            writer: Box::new(BufWriter::new(writer)),
+            last_snapshot: None,
+        })
+    }
+
+    /// Connects to the first address a TCP target resolves to that accepts in time.
+    fn connect(addr: &str) -> io::Result<TcpStream> {
+        let mut last_error = None;
+        for socket_addr in addr.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&socket_addr, CONNECT_TIMEOUT) {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "address could not be resolved")
+        }))
+    }
+
+    pub fn start(&mut self, mode: &GameMode, config: &GameConfig) -> io::Result<()> {
+        self.write_line(&TelemetryLine::Start { mode, config })
+    }
+
+    /// Streams new feedback, plus a state snapshot if the last one is old enough.
+    pub fn update(
+        &mut self,
+        state: &GameState,
+        feedback_events: &[(GameTime, Feedback)],
+    ) -> io::Result<()> {
+        for (time, feedback) in feedback_events {
+            self.write_line(&TelemetryLine::Feedback {
+                time: *time,
+                feedback,
+            })?;
+        }
+        if self.last_snapshot.is_none_or(|last_snapshot| {
+            state.time.saturating_sub(last_snapshot) >= SNAPSHOT_INTERVAL
+        }) {
+            self.last_snapshot = Some(state.time);
+            self.write_line(&TelemetryLine::Snapshot {
+                state: &Self::snapshot(state),
+            })?;
+        }
+        self.writer.flush()
+    }
+
+    pub fn end(&mut self, state: &GameState) -> io::Result<()> {
+        self.write_line(&TelemetryLine::End {
+            state: &Self::snapshot(state),
+        })?;
+        self.writer.flush()
+    }
+
+    // NOTE: Scheduled events are left out, as their keys cannot all be represented in JSON.
+    fn snapshot(state: &GameState) -> GameState {
+        let mut state = state.clone();
+        state.events.clear();
+        state
+    }
+
+    fn write_line(&mut self, line: &TelemetryLine) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, line)?;
+        self.writer.write_all(b"\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetrs_engine::Game;
+
+    use super::*;
+
+    #[test]
+    fn targets_are_told_apart_by_their_prefix() {
+        assert_eq!(
+            Target::parse("tcp:127.0.0.1:9000"),
+            Target::Tcp("127.0.0.1:9000")
+        );
+        assert_eq!(
+            Target::parse("unix:/tmp/tetrs.sock"),
+            Target::Unix("/tmp/tetrs.sock")
+        );
+        assert_eq!(
+            Target::parse("telemetry.jsonl"),
+            Target::File("telemetry.jsonl")
+        );
+        assert_eq!(
+            Target::parse("udp:1.2.3.4:5"),
+            Target::File("udp:1.2.3.4:5")
+        );
+        assert!(Telemetry::open("tcp:not an address").is_err());
+    }
+
+    #[test]
+    fn games_are_streamed_as_json_lines() {
+        let path =
+            std::env::temp_dir().join(format!("tetrs_telemetry_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut game = Game::with_seed(GameMode::marathon(), GameConfig::default(), 0);
+        let mut telemetry = Telemetry::open(path.to_str().unwrap()).unwrap();
+        telemetry.start(game.mode(), game.config()).unwrap();
+        let Ok(feedback_events) = game.update(None, Duration::from_millis(100)) else {
+            panic!("game could not be updated");
+        };
+        telemetry.update(game.state(), &feedback_events).unwrap();
+        // Too soon after the last snapshot for another one.
+        telemetry.update(game.state(), &[]).unwrap();
+        telemetry.end(game.state()).unwrap();
+        drop(telemetry);
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines = written
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        let types = lines
+            .iter()
+            .map(|line| line["type"].as_str().unwrap())
+            .filter(|line_type| *line_type != "feedback")
+            .collect::<Vec<_>>();
+        assert_eq!(types, ["start", "snapshot", "end"]);
+        assert_eq!(lines[0]["mode"]["name"], "Marathon");
+        assert_eq!(lines.len(), 3 + feedback_events.len());
+        // Scheduled events are left out of snapshots.
+        assert_eq!(
+            lines.last().unwrap()["state"]["events"],
+            serde_json::json!({})
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    cell::RefCell,
     collections::HashMap,
     env,
     fmt::Debug,
@@ -6,10 +7,12 @@ use std::{
     io::{self, Read, Write},
     num::{NonZeroU32, NonZeroUsize},
     path::PathBuf,
+    rc::Rc,
     sync::mpsc,
     time::{Duration, Instant},
 };
 
+use chrono::NaiveDate;
 use crossterm::{
     cursor::{self, MoveTo},
     event::{
@@ -21,15 +24,42 @@ use crossterm::{
     terminal::{self, Clear, ClearType},
     ExecutableCommand, QueueableCommand,
 };
+use rand::Rng;
 use tetrs_engine::{
-    piece_generation::TetrominoSource, piece_rotation::RotationSystem, Button, ButtonsPressed,
-    FeedbackEvents, Game, GameConfig, GameMode, GameState, Limits,
+    fumen::{self, FumenPage},
+    piece_generation::TetrominoSource,
+    piece_rotation::RotationSystem,
+    Button, ButtonsPressed, FeedbackEvents, FnGameMod, Game, GameConfig, GameMode, GameState,
+    Gravity, InternalEvent, LevelProgression, Limits, ModifierPoint, Ruleset, Scoring,
+    SpinDetection,
 };
 
 use crate::{
-    game_input_handlers::{combo_bot::ComboBotHandler, crossterm::CrosstermHandler, Interrupt},
-    game_mods,
-    game_renderers::{cached_renderer::CachedRenderer, Renderer},
+    game_input_handlers::{
+        combo_bot::ComboBotHandler,
+        crossterm::CrosstermHandler,
+        socket::{SocketHandler, SocketSession},
+        tbp_bot::TbpBotHandler,
+        versus_bot::{BotDifficulty, VersusBotHandler},
+        InputOrInterrupt, Interrupt,
+    },
+    game_mods::{
+        self,
+        board_layouts::BoardLayout,
+        mode_files::ModeFile,
+        puzzle_mode::{Puzzle, PuzzleGoal, PuzzlePack},
+        utils::{self, parse_tetromino},
+        versus_mode::{GarbageExchange, SharedGarbage},
+    },
+    game_renderers::{
+        cached_renderer::{get_color, CachedRenderer},
+        versus_renderer::VersusRenderer,
+        Renderer,
+    },
+    mode_registry::{ModeEntry, ModeRegistry},
+    netplay::{self, NetMessage, NetplayConnection, NetplayRole},
+    tas::{GameClock, TasPrompt, TasSession},
+    telemetry::Telemetry,
 };
 
 // NOTE: This could be more general and less ad-hoc. Count number of I-Spins, J-Spins, etc..
@@ -45,6 +75,16 @@ pub struct FinishedGameStats {
 }
 
 impl FinishedGameStats {
+    fn new(game: &Game, running_game_stats: &RunningGameStats) -> Self {
+        FinishedGameStats {
+            timestamp: chrono::Utc::now().format("%Y-%m-%d %H:%M").to_string(),
+            actions: running_game_stats.0,
+            score_bonuses: running_game_stats.1.clone(),
+            gamemode: game.mode().clone(),
+            last_state: game.state().clone(),
+        }
+    }
+
     fn was_successful(&self) -> bool {
         self.last_state.end.is_some_and(|fin| fin.is_ok())
     }
@@ -56,18 +96,38 @@ enum Menu {
     NewGame,
     Game {
         game: Box<Game>,
-        time_started: Instant,
-        last_paused: Instant,
-        total_duration_paused: Duration,
+        clock: GameClock,
         running_game_stats: RunningGameStats,
         game_renderer: Box<CachedRenderer>,
+        telemetry: Option<Box<Telemetry>>,
+        /// Set if the game is played tool-assisted.
+        tas: Option<Box<TasSession>>,
     },
     GameOver(Box<FinishedGameStats>),
     GameComplete(Box<FinishedGameStats>),
+    VersusGame {
+        games: Box<[Game; 2]>,
+        garbage: [SharedGarbage; 2],
+        cpu_difficulty: Option<BotDifficulty>,
+        clock: GameClock,
+        game_renderer: Box<VersusRenderer>,
+    },
+    NetplayLobby,
+    NetplayGame {
+        game: Box<Game>,
+        garbage: [SharedGarbage; 2],
+        connection: Box<NetplayConnection>,
+        time_started: Instant,
+        game_renderer: Box<VersusRenderer>,
+    },
+    VersusOver(String),
     Pause,
     Settings,
     ChangeControls,
     ConfigureGame,
+    PuzzleEditor,
+    BoardEditor,
+    ExportFumen(Vec<(String, Vec<FumenPage>)>),
     Scores,
     About,
     Quit(String),
@@ -81,10 +141,17 @@ impl std::fmt::Display for Menu {
             Menu::Game { game, .. } => &format!("Game: {}", game.mode().name),
             Menu::GameOver(_) => "Game Over",
             Menu::GameComplete(_) => "Game Completed",
+            Menu::VersusGame { .. } => "Versus Game",
+            Menu::NetplayLobby => "Netplay Lobby",
+            Menu::NetplayGame { .. } => "Netplay Game",
+            Menu::VersusOver(_) => "Versus Over",
             Menu::Pause => "Pause",
             Menu::Settings => "Settings",
             Menu::ChangeControls => "Change Controls",
             Menu::ConfigureGame => "Configure Game",
+            Menu::PuzzleEditor => "Puzzle Editor",
+            Menu::BoardEditor => "Board Editor",
+            Menu::ExportFumen(_) => "Export Fumen",
             Menu::Scores => "Scoreboard",
             Menu::About => "About",
             Menu::Quit(_) => "Quit",
@@ -99,6 +166,44 @@ enum MenuUpdate {
     Push(Menu),
 }
 
+/// What an entry of the new game menu starts.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+enum NewGameEntry {
+    /// A standard mode of the registry, by its index among those.
+    Normal(usize),
+    Versus,
+    VersusCpu,
+    /// Only listed when hosting or joining an online game.
+    VersusOnline,
+    /// A special mode of the registry, by its index among those.
+    Special(usize),
+    Custom,
+}
+
+impl NewGameEntry {
+    /// The entry at some position of the menu, which lists the standard modes, the versus modes,
+    /// the special modes and finally the custom game.
+    fn at(selected: usize, n_normal: usize, online: bool, n_special: usize) -> Self {
+        let versus: &[Self] = if online {
+            &[Self::Versus, Self::VersusCpu, Self::VersusOnline]
+        } else {
+            &[Self::Versus, Self::VersusCpu]
+        };
+        let Some(i) = selected.checked_sub(n_normal) else {
+            return Self::Normal(selected);
+        };
+        if let Some(entry) = versus.get(i) {
+            return *entry;
+        }
+        let i = i - versus.len();
+        if i < n_special {
+            Self::Special(i)
+        } else {
+            Self::Custom
+        }
+    }
+}
+
 #[derive(
     Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, Debug, serde::Serialize, serde::Deserialize,
 )]
@@ -124,6 +229,9 @@ pub enum GraphicsColor {
 pub struct Settings {
     #[serde_as(as = "HashMap<serde_with::json::JsonString, _>")]
     pub keybinds: HashMap<KeyCode, Button>,
+    #[serde_as(as = "[HashMap<serde_with::json::JsonString, _>; 2]")]
+    #[serde(default = "CrosstermHandler::default_versus_keybinds")]
+    pub versus_keybinds: [HashMap<KeyCode, Button>; 2],
     pub game_fps: f64,
     pub show_fps: bool,
     pub graphics_style: GraphicsStyle,
@@ -132,6 +240,25 @@ pub struct Settings {
     pub save_data_on_exit: bool,
 }
 
+impl Settings {
+    // Profile 0 are the single player keybinds, profiles 1 and 2 those of the versus players.
+    pub fn keybinds_profile(&self, profile: usize) -> &HashMap<KeyCode, Button> {
+        if profile == 0 {
+            &self.keybinds
+        } else {
+            &self.versus_keybinds[profile - 1]
+        }
+    }
+
+    pub fn keybinds_profile_mut(&mut self, profile: usize) -> &mut HashMap<KeyCode, Button> {
+        if profile == 0 {
+            &mut self.keybinds
+        } else {
+            &mut self.versus_keybinds[profile - 1]
+        }
+    }
+}
+
 // For the "New Game" menu.
 #[derive(
     Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, Debug, serde::Serialize, serde::Deserialize,
@@ -148,14 +275,306 @@ pub enum Stat {
     Eq, PartialEq, Ord, PartialOrd, Clone, Hash, Debug, serde::Serialize, serde::Deserialize,
 )]
 pub struct GameModeStore {
-    name: String,
-    start_level: NonZeroU32,
-    increment_level: bool,
+    pub(crate) name: String,
+    pub(crate) start_level: NonZeroU32,
+    pub(crate) increment_level: bool,
+    /// How the level of custom games is incremented, if it is.
+    #[serde(default)]
+    pub(crate) level_progression: LevelProgression,
     custom_mode_limit: Option<Stat>,
-    cheese_mode_limit: Option<NonZeroUsize>,
-    cheese_mode_gap_size: usize,
-    combo_starting_layout: u16,
-    descent_mode: bool,
+    /// Name of the board layout custom games start with.
+    #[serde(default)]
+    custom_board: Option<String>,
+    pub(crate) cheese_mode_limit: Option<NonZeroUsize>,
+    pub(crate) cheese_mode_gap_size: usize,
+    pub(crate) combo_starting_layout: u16,
+    pub(crate) descent_mode: bool,
+    /// Whether Master games end with an invisible credit roll.
+    #[serde(default)]
+    pub(crate) master_credit_roll: bool,
+    /// Which of the variants of Invisible mode is played.
+    #[serde(default)]
+    pub(crate) invisible_mode_variant: usize,
+    /// How likely (in percent) the hole of a Dig Race garbage line is to move.
+    #[serde(default = "default_dig_race_hole_change")]
+    pub(crate) dig_race_hole_change: u8,
+    /// On how many consecutive days up to `daily_last_played` a daily challenge was played.
+    #[serde(default)]
+    pub(crate) daily_streak: u32,
+    /// The day of the last daily challenge played.
+    #[serde(default)]
+    pub(crate) daily_last_played: Option<NaiveDate>,
+    #[serde(default)]
+    versus_cpu_difficulty: BotDifficulty,
+}
+
+fn default_dig_race_hole_change() -> u8 {
+    30
+}
+
+impl Default for GameModeStore {
+    fn default() -> Self {
+        Self {
+            name: "Custom Mode".to_string(),
+            start_level: NonZeroU32::MIN,
+            increment_level: false,
+            level_progression: LevelProgression::default(),
+            custom_mode_limit: None,
+            custom_board: None,
+            cheese_mode_limit: Some(NonZeroUsize::try_from(20).unwrap()),
+            cheese_mode_gap_size: 1,
+            combo_starting_layout: game_mods::combo_mode::LAYOUTS[0],
+            descent_mode: false,
+            master_credit_roll: true,
+            invisible_mode_variant: 0,
+            dig_race_hole_change: default_dig_race_hole_change(),
+            daily_streak: 0,
+            daily_last_played: None,
+            versus_cpu_difficulty: BotDifficulty::default(),
+        }
+    }
+}
+
+impl GameModeStore {
+    /// The current streak of daily challenges, which is broken once a day is missed.
+    pub(crate) fn daily_streak(&self, today: NaiveDate) -> u32 {
+        match self.daily_last_played {
+            Some(last_played) if last_played == today || last_played.succ_opt() == Some(today) => {
+                self.daily_streak
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// What the puzzle editor is currently typing text into.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+enum PuzzleEditorInput {
+    StageName,
+    PackName,
+}
+
+/// The puzzle pack being worked on in the puzzle editor, kept while test-playing stages.
+#[derive(Clone, Debug)]
+pub struct PuzzleEditor {
+    pack: PuzzlePack,
+    stage: usize,
+    /// Cursor position on the board, with row 0 at the top.
+    cursor: (usize, usize),
+    /// The tile painted by the editor (a piece letter, or `X` for grey).
+    brush: char,
+    text_input: Option<PuzzleEditorInput>,
+    message: String,
+    /// Which pack was last opened with Ctrl+O.
+    opened_pack: usize,
+    test_playing: bool,
+}
+
+impl PuzzleEditor {
+    /// The number of (lowest) board rows that can be edited.
+    const ROWS: usize = Game::SKYLINE;
+    const TEST_PACK_NAME: &'static str = "Puzzle Test";
+
+    fn new() -> Self {
+        Self {
+            pack: PuzzlePack {
+                name: "My Puzzles".to_string(),
+                description: String::new(),
+                puzzles: vec![Self::empty_puzzle(1)],
+            },
+            stage: 0,
+            cursor: (0, Self::ROWS - 1),
+            brush: 'X',
+            text_input: None,
+            message: String::new(),
+            opened_pack: 0,
+            test_playing: false,
+        }
+    }
+
+    fn empty_puzzle(number: usize) -> Puzzle {
+        Puzzle {
+            name: format!("Stage {number}"),
+            board: vec![".".repeat(Game::WIDTH); Self::ROWS],
+            pieces: String::new(),
+            attempts: 5,
+            goal: PuzzleGoal::PerfectClear,
+        }
+    }
+
+    fn open(&mut self, mut pack: PuzzlePack) {
+        for puzzle in pack.puzzles.iter_mut() {
+            pad_board(&mut puzzle.board, Self::ROWS);
+        }
+        if pack.puzzles.is_empty() {
+            pack.puzzles.push(Self::empty_puzzle(1));
+        }
+        self.pack = pack;
+        self.stage = 0;
+    }
+
+    /// The pack as it would be saved, with empty rows above the boards removed.
+    fn exported(&self) -> PuzzlePack {
+        let mut pack = self.pack.clone();
+        for puzzle in pack.puzzles.iter_mut() {
+            trim_board(&mut puzzle.board);
+        }
+        pack
+    }
+
+    fn puzzle(&self) -> &Puzzle {
+        &self.pack.puzzles[self.stage]
+    }
+
+    fn puzzle_mut(&mut self) -> &mut Puzzle {
+        &mut self.pack.puzzles[self.stage]
+    }
+
+    /// The editable (lowest) rows of the current board.
+    fn rows(&self) -> &[String] {
+        let board = &self.puzzle().board;
+        &board[board.len() - Self::ROWS..]
+    }
+
+    /// Paints the cell under the cursor with the brush, or erases it if it was painted already.
+    fn paint_cell(&mut self) {
+        let ((x, y), brush) = (self.cursor, self.brush);
+        let board = &mut self.puzzle_mut().board;
+        let rows = board.len() - Self::ROWS;
+        let cell = board[rows + y].chars().nth(x).unwrap_or('.');
+        let new_cell = if cell == brush { '.' } else { brush };
+        set_board_cell(&mut board[rows..], (x, y), new_cell);
+    }
+}
+
+/// What the board editor is currently typing text into.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+enum BoardEditorInput {
+    Name,
+    Queue,
+    Fumen,
+}
+
+/// The layout being worked on in the board editor.
+#[derive(Clone, Debug)]
+pub struct BoardEditor {
+    layout: BoardLayout,
+    /// Cursor position on the board, with row 0 at the top.
+    cursor: (usize, usize),
+    /// The tile painted by the editor (a piece letter, or `X` for grey).
+    brush: char,
+    text_input: Option<BoardEditorInput>,
+    /// A fumen being pasted, to be imported.
+    fumen_input: String,
+    message: String,
+    /// Which saved layout was last opened with Ctrl+O.
+    opened_layout: usize,
+}
+
+impl BoardEditor {
+    const ROWS: usize = Game::SKYLINE;
+
+    fn new() -> Self {
+        Self {
+            layout: BoardLayout {
+                name: "My Board".to_string(),
+                board: vec![".".repeat(Game::WIDTH); Self::ROWS],
+                hold: None,
+                queue: String::new(),
+            },
+            cursor: (0, Self::ROWS - 1),
+            brush: 'X',
+            text_input: None,
+            fumen_input: String::new(),
+            message: String::new(),
+            opened_layout: 0,
+        }
+    }
+
+    fn open(&mut self, mut layout: BoardLayout) {
+        pad_board(&mut layout.board, Self::ROWS);
+        self.layout = layout;
+    }
+
+    /// The layout as it would be saved, with empty rows above the board removed.
+    fn exported(&self) -> BoardLayout {
+        let mut layout = self.layout.clone();
+        trim_board(&mut layout.board);
+        layout
+    }
+}
+
+fn is_empty_cell(ch: char) -> bool {
+    matches!(ch, ' ' | '.' | '_')
+}
+
+/// Pads board rows to full width and (at least) the given height, as used by the editors.
+fn pad_board(board: &mut Vec<String>, rows: usize) {
+    for row in board.iter_mut() {
+        let padding = Game::WIDTH.saturating_sub(row.chars().count());
+        row.push_str(&".".repeat(padding));
+    }
+    let missing_rows = rows.saturating_sub(board.len());
+    board.splice(0..0, vec![".".repeat(Game::WIDTH); missing_rows]);
+}
+
+/// Removes empty rows above a board.
+fn trim_board(board: &mut Vec<String>) {
+    let first_filled = board
+        .iter()
+        .position(|row| row.chars().any(|ch| !is_empty_cell(ch)))
+        .unwrap_or(board.len());
+    board.drain(..first_filled);
+}
+
+/// Replaces a cell of a (padded) board.
+fn set_board_cell(board: &mut [String], (x, y): (usize, usize), new_cell: char) {
+    board[y] = board[y]
+        .chars()
+        .enumerate()
+        .map(|(i, ch)| if i == x { new_cell } else { ch })
+        .collect();
+}
+
+/// The pieces locked in a single player game, kept to be exported as fumen.
+///
+/// Big games are not recorded, as their pieces do not match the tiles they leave on the board.
+#[derive(Clone, Default, Debug)]
+struct FumenRecording {
+    /// A page for every locked piece, showing the board before it was locked.
+    pages: Rc<RefCell<Vec<FumenPage>>>,
+    /// A page showing the game as it was last left.
+    current: FumenPage,
+}
+
+impl FumenRecording {
+    /// A game mod recording the board right before every lock, after any other game mods ran.
+    fn modifier(&self) -> FnGameMod {
+        let pages = Rc::clone(&self.pages);
+        Box::new(move |_, _, state, _, modifier_point| {
+            if let (ModifierPoint::BeforeEvent(InternalEvent::Lock), Some((piece, _))) =
+                (modifier_point, state.active_piece_data)
+            {
+                pages.borrow_mut().push(FumenPage {
+                    board: state.board.clone(),
+                    piece: Some(piece),
+                    ..FumenPage::default()
+                });
+            }
+        })
+    }
+
+    fn exports(&self) -> Vec<(String, Vec<FumenPage>)> {
+        let mut game_pages = self.pages.borrow().clone();
+        game_pages.push(self.current.clone());
+        vec![
+            ("current board".to_string(), vec![self.current.clone()]),
+            (
+                format!("whole game ({} pages)", game_pages.len()),
+                game_pages,
+            ),
+        ]
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -167,32 +586,50 @@ pub struct TerminalApp<T: Write> {
     game_mode_store: GameModeStore,
     past_games: Vec<FinishedGameStats>,
     custom_starting_board: Option<u128>,
-    combo_bot_enabled: bool,
+    netplay_role: Option<NetplayRole>,
+    telemetry_target: Option<String>,
+    socket_session: Option<SocketSession>,
+    bot_command: Option<String>,
+    modes: ModeRegistry,
+    mode_file_errors: Vec<String>,
+    puzzle_editor: PuzzleEditor,
+    board_layouts: Vec<BoardLayout>,
+    board_editor: BoardEditor,
+    /// The last single player game, if it was not followed by a versus game.
+    fumen_recording: Option<FumenRecording>,
+    /// Whether a savefile exists that could not be read, which is then left untouched.
+    savefile_unreadable: bool,
 }
 
 impl<T: Write> Drop for TerminalApp<T> {
     fn drop(&mut self) {
         // FIXME: Handle errors?
         let savefile_path = Self::savefile_path();
-        // If the user wants their data stored, try to do so.
-        if self.settings.save_data_on_exit {
-            if let Err(_e) = self.store_local(savefile_path) {
-                // FIXME: Make this debuggable.
-                //eprintln!("Could not save settings this time: {e} ");
-                //std::thread::sleep(Duration::from_secs(4));
-            }
-        // Otherwise check if savefile exists.
-        } else if let Ok(exists) = savefile_path.try_exists() {
-            // Delete it for them if it does.
-            if exists {
-                let _ = std::fs::remove_file(savefile_path);
+        // Remote players don't get to touch the local savefile, and nobody gets to lose a savefile
+        // that could not be read (e.g. one written by a different version).
+        if self.socket_session.is_none() && !self.savefile_unreadable {
+            // If the user wants their data stored, try to do so.
+            if self.settings.save_data_on_exit {
+                if let Err(_e) = self.store_local(savefile_path) {
+                    // FIXME: Make this debuggable.
+                    //eprintln!("Could not save settings this time: {e} ");
+                    //std::thread::sleep(Duration::from_secs(4));
+                }
+            // Otherwise check if savefile exists.
+            } else if let Ok(exists) = savefile_path.try_exists() {
+                // Delete it for them if it does.
+                if exists {
+                    let _ = std::fs::remove_file(savefile_path);
+                }
             }
         }
         // Console epilogue: De-initialization.
         if self.kitty_enabled {
             let _ = self.term.execute(event::PopKeyboardEnhancementFlags);
         }
-        let _ = terminal::disable_raw_mode();
+        if self.socket_session.is_none() {
+            let _ = terminal::disable_raw_mode();
+        }
         let _ = self.term.execute(style::ResetColor);
         let _ = self.term.execute(cursor::Show);
         let _ = self.term.execute(terminal::LeaveAlternateScreen);
@@ -204,20 +641,35 @@ impl<T: Write> TerminalApp<T> {
     pub const H_MAIN: u16 = 24;
 
     pub const SAVEFILE_NAME: &'static str = ".tetrs_tui_savefile.json";
+    pub const MODE_FILES_DIRNAME: &'static str = "tetrs_tui_modes";
+    pub const PUZZLE_PACKS_DIRNAME: &'static str = "tetrs_tui_puzzles";
+    pub const BOARD_LAYOUTS_DIRNAME: &'static str = "tetrs_tui_boards";
+    pub const FUMEN_EXPORTS_DIRNAME: &'static str = "tetrs_tui_fumen";
+    pub const REPLAYS_DIRNAME: &'static str = "tetrs_tui_replays";
+    /// The frame rates offered for frame-stepped play in the game configuration menu.
+    const TICK_RATES: [u32; 5] = [30, 50, 60, 120, 240];
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         mut terminal: T,
         initial_combo_layout: Option<u16>,
         experimental_custom_layout: Option<u128>,
         combo_bot_enabled: bool,
+        netplay_role: Option<NetplayRole>,
+        telemetry_target: Option<String>,
+        socket_session: Option<SocketSession>,
+        bot_command: Option<String>,
     ) -> Self {
         // Console prologue: Initialization.
         // FIXME: Handle errors?
         let _ = terminal.execute(terminal::EnterAlternateScreen);
         let _ = terminal.execute(terminal::SetTitle("tetrs - Terminal User Interface"));
         let _ = terminal.execute(cursor::Hide);
-        let _ = terminal::enable_raw_mode();
-        let kitty_enabled = terminal::supports_keyboard_enhancement().unwrap_or(false);
+        // A remote terminal is already put into the right mode by its client.
+        let kitty_enabled = socket_session.is_none() && {
+            let _ = terminal::enable_raw_mode();
+            terminal::supports_keyboard_enhancement().unwrap_or(false)
+        };
         if kitty_enabled {
             // FIXME: Kinda iffy. Do we need all flags? What undesirable effects might there be?
             let _ = terminal.execute(event::PushKeyboardEnhancementFlags(
@@ -229,6 +681,7 @@ impl<T: Write> TerminalApp<T> {
             kitty_enabled,
             settings: Settings {
                 keybinds: CrosstermHandler::default_keybinds(),
+                versus_keybinds: CrosstermHandler::default_versus_keybinds(),
                 game_fps: 30.0,
                 show_fps: false,
                 graphics_style: GraphicsStyle::Unicode,
@@ -237,24 +690,46 @@ impl<T: Write> TerminalApp<T> {
                 save_data_on_exit: false,
             },
             game_config: GameConfig::default(),
-            game_mode_store: GameModeStore {
-                name: "Custom Mode".to_string(),
-                start_level: NonZeroU32::MIN,
-                increment_level: false,
-                custom_mode_limit: None,
-                cheese_mode_limit: Some(NonZeroUsize::try_from(20).unwrap()),
-                cheese_mode_gap_size: 1,
-                combo_starting_layout: game_mods::combo_mode::LAYOUTS[0],
-                descent_mode: false,
-            },
+            game_mode_store: GameModeStore::default(),
             past_games: vec![],
             custom_starting_board: experimental_custom_layout,
-            combo_bot_enabled,
+            netplay_role,
+            telemetry_target,
+            socket_session,
+            bot_command,
+            modes: ModeRegistry::builtin(combo_bot_enabled),
+            mode_file_errors: Vec::new(),
+            puzzle_editor: PuzzleEditor::new(),
+            board_layouts: Vec::new(),
+            board_editor: BoardEditor::new(),
+            fumen_recording: None,
+            savefile_unreadable: false,
         };
-        if let Err(_e) = app.load_local() {
-            // FIXME: Make this debuggable.
-            //eprintln!("Could not loading settings: {e}");
-            //std::thread::sleep(Duration::from_secs(5));
+        // Mode files and puzzle packs may not take each other's names.
+        let mut taken_names = Vec::new();
+        let (mode_files, mode_file_errors) =
+            ModeFile::load_dir(&Self::mode_files_dir(), &mut taken_names);
+        for mode_file in mode_files {
+            app.modes.register(mode_file);
+        }
+        app.mode_file_errors = mode_file_errors;
+        let (puzzle_packs, puzzle_pack_errors) =
+            PuzzlePack::load_dir(&Self::puzzle_packs_dir(), &mut taken_names);
+        for puzzle_pack in puzzle_packs {
+            app.modes.register(puzzle_pack);
+        }
+        app.mode_file_errors.extend(puzzle_pack_errors);
+        let (board_layouts, board_layout_errors) =
+            BoardLayout::load_dir(&Self::board_layouts_dir());
+        app.board_layouts = board_layouts;
+        app.mode_file_errors.extend(board_layout_errors);
+        // Remote players start with default settings.
+        if app.socket_session.is_none() {
+            if let Err(_e) = app.load_local() {
+                // FIXME: Make this debuggable.
+                //eprintln!("Could not loading settings: {e}");
+                //std::thread::sleep(Duration::from_secs(5));
+            }
         }
         if let Some(initial_combo_layout) = initial_combo_layout {
             app.game_mode_store.combo_starting_layout = initial_combo_layout;
@@ -264,6 +739,49 @@ impl<T: Write> TerminalApp<T> {
     }
 
     fn savefile_path() -> PathBuf {
+        Self::config_dir().join(Self::SAVEFILE_NAME)
+    }
+
+    /// Directory searched for mode files (see [`ModeFile`]).
+    pub fn mode_files_dir() -> PathBuf {
+        Self::config_dir().join(Self::MODE_FILES_DIRNAME)
+    }
+
+    /// Directory searched for puzzle packs (see [`PuzzlePack`]).
+    pub fn puzzle_packs_dir() -> PathBuf {
+        Self::config_dir().join(Self::PUZZLE_PACKS_DIRNAME)
+    }
+
+    /// Directory of the layouts saved in the board editor (see [`BoardLayout`]).
+    pub fn board_layouts_dir() -> PathBuf {
+        Self::config_dir().join(Self::BOARD_LAYOUTS_DIRNAME)
+    }
+
+    /// Directory into which fumen are exported.
+    pub fn fumen_exports_dir() -> PathBuf {
+        Self::config_dir().join(Self::FUMEN_EXPORTS_DIRNAME)
+    }
+
+    /// Directory into which replays of tool-assisted games are exported.
+    pub fn replays_dir() -> PathBuf {
+        Self::config_dir().join(Self::REPLAYS_DIRNAME)
+    }
+
+    /// Makes a layout available in the board editor and selects it for custom games.
+    pub fn import_board_layout(&mut self, layout: BoardLayout) {
+        self.game_mode_store.custom_board = Some(layout.name.clone());
+        if let Some(existing) = self
+            .board_layouts
+            .iter_mut()
+            .find(|existing| existing.name == layout.name)
+        {
+            *existing = layout;
+        } else {
+            self.board_layouts.push(layout);
+        }
+    }
+
+    fn config_dir() -> PathBuf {
         let home_var = env::var("HOME");
         #[allow(clippy::collapsible_else_if)]
         if cfg!(target_os = "windows") {
@@ -291,7 +809,6 @@ impl<T: Write> TerminalApp<T> {
                 PathBuf::from(".")
             }
         }
-        .join(Self::SAVEFILE_NAME)
     }
 
     fn store_local(&mut self, path: PathBuf) -> io::Result<()> {
@@ -299,15 +816,9 @@ impl<T: Write> TerminalApp<T> {
             .past_games
             .iter()
             .filter(|finished_game_stats| {
-                finished_game_stats.was_successful()
-                    || finished_game_stats.last_state.lines_cleared
-                        > if finished_game_stats.gamemode.name == "Combo"
-                            || finished_game_stats.gamemode.name == "Combo (Bot)"
-                        {
-                            9
-                        } else {
-                            0
-                        }
+                self.modes
+                    .get(&finished_game_stats.gamemode.name)
+                    .keep_record(&finished_game_stats.last_state)
             })
             .cloned()
             .collect::<Vec<_>>();
@@ -328,19 +839,31 @@ impl<T: Write> TerminalApp<T> {
         let mut file = File::open(Self::savefile_path())?;
         let mut save_str = String::new();
         file.read_to_string(&mut save_str)?;
-        (
-            self.settings,
-            self.game_mode_store,
-            self.game_config,
-            self.past_games,
-        ) = serde_json::from_str(&save_str)?;
-        Ok(())
+        match serde_json::from_str(&save_str) {
+            Ok(save_state) => {
+                (
+                    self.settings,
+                    self.game_mode_store,
+                    self.game_config,
+                    self.past_games,
+                ) = save_state;
+                Ok(())
+            }
+            Err(e) => {
+                self.savefile_unreadable = true;
+                Err(e.into())
+            }
+        }
     }
 
     pub fn settings(&self) -> &Settings {
         &self.settings
     }
 
+    pub(crate) fn modes(&self) -> &ModeRegistry {
+        &self.modes
+    }
+
     pub fn run(&mut self) -> io::Result<String> {
         let mut menu_stack = vec![Menu::Title];
         // Preparing main application loop.
@@ -355,19 +878,61 @@ impl<T: Write> TerminalApp<T> {
                 Menu::NewGame => self.newgame(),
                 Menu::Game {
                     game,
-                    time_started,
-                    total_duration_paused,
-                    last_paused,
+                    clock,
+                    running_game_stats,
+                    game_renderer,
+                    telemetry,
+                    tas: Some(tas),
+                } => self.tas_game(
+                    game,
+                    clock,
+                    running_game_stats,
+                    game_renderer.as_mut(),
+                    telemetry,
+                    tas,
+                ),
+                Menu::Game {
+                    game,
+                    clock,
                     running_game_stats,
                     game_renderer,
+                    telemetry,
+                    tas: None,
                 } => self.game(
                     game,
-                    time_started,
-                    last_paused,
-                    total_duration_paused,
+                    clock,
                     running_game_stats,
                     game_renderer.as_mut(),
+                    telemetry,
+                ),
+                Menu::VersusGame {
+                    games,
+                    garbage,
+                    cpu_difficulty,
+                    clock,
+                    game_renderer,
+                } => self.versus_game(
+                    games,
+                    garbage,
+                    *cpu_difficulty,
+                    clock,
+                    game_renderer.as_mut(),
+                ),
+                Menu::NetplayLobby => self.netplay_lobby(),
+                Menu::NetplayGame {
+                    game,
+                    garbage,
+                    connection,
+                    time_started,
+                    game_renderer,
+                } => self.netplay_game(
+                    game,
+                    garbage,
+                    connection,
+                    *time_started,
+                    game_renderer.as_mut(),
                 ),
+                Menu::VersusOver(result) => self.versus_over_menu(result),
                 Menu::Pause => self.pause_menu(),
                 Menu::GameOver(finished_stats) => self.game_over_menu(finished_stats),
                 Menu::GameComplete(finished_stats) => self.game_complete_menu(finished_stats),
@@ -376,6 +941,9 @@ impl<T: Write> TerminalApp<T> {
                 Menu::Settings => self.settings_menu(),
                 Menu::ChangeControls => self.change_controls_menu(),
                 Menu::ConfigureGame => self.configure_game_menu(),
+                Menu::PuzzleEditor => self.puzzle_editor_menu(),
+                Menu::BoardEditor => self.board_editor_menu(),
+                Menu::ExportFumen(exports) => self.export_fumen_menu(exports),
                 Menu::Quit(string) => break string.clone(),
             }?;
             // Change screen session depending on what response screen gave.
@@ -388,7 +956,13 @@ impl<T: Write> TerminalApp<T> {
                 MenuUpdate::Push(menu) => {
                     if matches!(
                         menu,
-                        Menu::Title | Menu::Game { .. } | Menu::GameOver(_) | Menu::GameComplete(_)
+                        Menu::Title
+                            | Menu::Game { .. }
+                            | Menu::GameOver(_)
+                            | Menu::GameComplete(_)
+                            | Menu::VersusGame { .. }
+                            | Menu::NetplayGame { .. }
+                            | Menu::VersusOver(_)
                     ) {
                         menu_stack.clear();
                     }
@@ -399,8 +973,52 @@ impl<T: Write> TerminalApp<T> {
         Ok(msg)
     }
 
-    pub(crate) fn fetch_main_xy() -> (u16, u16) {
-        let (w_console, h_console) = terminal::size().unwrap_or((0, 0));
+    fn terminal_size(&self) -> io::Result<(u16, u16)> {
+        if let Some(session) = &self.socket_session {
+            Ok(session.size())
+        } else {
+            terminal::size()
+        }
+    }
+
+    /// Reads the next key or resize event from wherever the player's terminal is.
+    fn read_event(&self) -> io::Result<Event> {
+        if let Some(session) = &self.socket_session {
+            session.read()
+        } else {
+            event::read()
+        }
+    }
+
+    fn read_event_timeout(&self, timeout: Duration) -> io::Result<Option<Event>> {
+        if let Some(session) = &self.socket_session {
+            session.read_timeout(timeout)
+        } else if event::poll(timeout)? {
+            event::read().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Spawns the handler turning key presses into game inputs.
+    fn input_handler<B: Copy + Send + 'static>(
+        &self,
+        button_sender: &mpsc::Sender<InputOrInterrupt<B>>,
+        keybinds: &HashMap<KeyCode, B>,
+    ) -> Box<dyn Debug> {
+        if let Some(session) = &self.socket_session {
+            Box::new(SocketHandler::new(button_sender, keybinds, session))
+        } else {
+            Box::new(CrosstermHandler::new(
+                button_sender,
+                keybinds,
+                self.kitty_enabled,
+            ))
+        }
+    }
+
+    pub(crate) fn fetch_main_xy(&self) -> (u16, u16) {
+        let (w_console, h_console) = self.terminal_size().unwrap_or((0, 0));
         (
             w_console.saturating_sub(Self::W_MAIN) / 2,
             h_console.saturating_sub(Self::H_MAIN) / 2,
@@ -416,7 +1034,7 @@ impl<T: Write> TerminalApp<T> {
         let mut selected = 0usize;
         loop {
             let w_main = Self::W_MAIN.into();
-            let (x_main, y_main) = Self::fetch_main_xy();
+            let (x_main, y_main) = self.fetch_main_xy();
             let y_selection = Self::H_MAIN / 5;
             if current_menu_name.is_empty() {
                 self.term
@@ -481,7 +1099,7 @@ impl<T: Write> TerminalApp<T> {
             }
             self.term.flush()?;
             // Wait for new input.
-            match event::read()? {
+            match self.read_event()? {
                 // Quit menu.
                 Event::Key(KeyEvent {
                     code: KeyCode::Char('c'),
@@ -543,6 +1161,8 @@ impl<T: Write> TerminalApp<T> {
     fn title(&mut self) -> io::Result<MenuUpdate> {
         let selection = vec![
             Menu::NewGame,
+            Menu::PuzzleEditor,
+            Menu::BoardEditor,
             Menu::Settings,
             Menu::Scores,
             Menu::About,
@@ -552,101 +1172,89 @@ impl<T: Write> TerminalApp<T> {
     }
 
     fn newgame(&mut self) -> io::Result<MenuUpdate> {
-        let normal_gamemodes: [(_, _, Box<dyn Fn() -> Game>); 4] = [
-            (
-                "40-Lines",
-                "how fast can you clear?".to_string(),
-                Box::new(|| Game::new(GameMode::sprint(NonZeroU32::try_from(3).unwrap()))),
-            ),
-            (
-                "Marathon",
-                "can you reach speed level 15?".to_string(),
-                Box::new(|| Game::new(GameMode::marathon())),
-            ),
-            (
-                "Time Trial",
-                "get a highscore in 3 minutes!".to_string(),
-                Box::new(|| Game::new(GameMode::ultra(NonZeroU32::MIN))),
-            ),
-            (
-                "Master",
-                "clear 100 lines starting at instant gravity.".to_string(),
-                Box::new(|| Game::new(GameMode::master())),
-            ),
-        ];
+        self.puzzle_editor.test_playing = false;
         let mut selected = 0usize;
         let mut customization_selected = 0usize;
         let (d_time, d_score, d_pieces, d_lines, d_level) = (Duration::from_secs(5), 200, 10, 5, 1);
+        // Shown once in place of the usual hint.
+        let mut notice = None;
         loop {
             // First part: rendering the menu.
             let w_main = Self::W_MAIN.into();
-            let (x_main, y_main) = Self::fetch_main_xy();
+            let (x_main, y_main) = self.fetch_main_xy();
             let y_selection = Self::H_MAIN / 5;
-            let cheese_mode_limit = self.game_mode_store.cheese_mode_limit;
-            let cheese_mode_gap_size = self.game_mode_store.cheese_mode_gap_size;
-            let combo_starting_layout = self.game_mode_store.combo_starting_layout;
-            let mut special_gamemodes: Vec<(_, _, Box<dyn Fn() -> Game>)> = vec![
-                (
-                    "Puzzle",
-                    "24 stages of perfect clears!".to_string(),
-                    Box::new(game_mods::puzzle_mode::new_game),
-                ),
-                (
-                    "Cheese",
-                    format!(
-                        "eat your way through! (limit: {:?})",
-                        self.game_mode_store.cheese_mode_limit
-                    ),
-                    Box::new(|| {
-                        game_mods::cheese_mode::new_game(cheese_mode_limit, cheese_mode_gap_size)
-                    }),
-                ),
+            // Modes from the registry, with the standard ones listed before versus modes.
+            let (normal_gamemodes, special_gamemodes): (Vec<_>, Vec<_>) = self
+                .modes
+                .iter()
+                .filter(|mode| mode.is_unlocked(&self.game_mode_store))
+                .partition(|mode| mode.is_standard());
+            let mut versus_gamemodes = vec![
+                ("Versus", "local split-screen battle!".to_string()),
                 (
-                    "Combo",
+                    "Versus CPU",
                     format!(
-                        "how long can you chain? (start: {:b})",
-                        self.game_mode_store.combo_starting_layout
+                        "battle the computer! (difficulty: {:?})",
+                        self.game_mode_store.versus_cpu_difficulty
                     ),
-                    Box::new(|| {
-                        let mut combo_game = game_mods::combo_mode::new_game(combo_starting_layout);
-                        if self.combo_bot_enabled {
-                            // SAFETY: We only add the information that this will be botted.
-                            unsafe {
-                                combo_game.mode_mut().name.push_str(" (Bot)");
-                            }
-                        }
-                        combo_game
-                    }),
                 ),
             ];
-            if self.game_mode_store.descent_mode {
-                special_gamemodes.insert(
-                    1,
-                    (
-                        "Descent",
-                        "spin the piece and collect gems by touching them.".to_string(),
-                        Box::new(game_mods::descent_mode::new_game),
-                    ),
-                )
+            if let Some(netplay_role) = &self.netplay_role {
+                versus_gamemodes.push(("Versus Online", format!("{netplay_role}.")));
             }
-            // There are the normal, special, + the custom gamemode.
-            let selection_size = normal_gamemodes.len() + special_gamemodes.len() + 1;
+            // There are the normal, versus, special, + the custom gamemode.
+            let selection_size =
+                normal_gamemodes.len() + versus_gamemodes.len() + special_gamemodes.len() + 1;
+            let n_unspecial_gamemodes = normal_gamemodes.len() + versus_gamemodes.len();
             // There are four columns for the custom stat selection.
-            let customization_selection_size = 4;
+            let customization_selection_size = 5;
             selected = selected.rem_euclid(selection_size);
             customization_selected =
                 customization_selected.rem_euclid(customization_selection_size);
+            let entry = NewGameEntry::at(
+                selected,
+                normal_gamemodes.len(),
+                self.netplay_role.is_some(),
+                special_gamemodes.len(),
+            );
+            let selected_mode = match entry {
+                NewGameEntry::Normal(i) => Some(normal_gamemodes[i]),
+                NewGameEntry::Special(i) => Some(special_gamemodes[i]),
+                _ => None,
+            };
             // Render menu title.
             self.term
                 .queue(Clear(ClearType::All))?
                 .queue(MoveTo(x_main, y_main + y_selection))?
                 .queue(Print(format!("{:^w_main$}", "* Start New Game *")))?
                 .queue(MoveTo(x_main, y_main + y_selection + 2))?
-                .queue(Print(format!("{:^w_main$}", "──────────────────────────")))?;
-            // Render normal and special gamemodes.
-            for (i, (name, details, _)) in normal_gamemodes
+                .queue(Print(format!("{:^w_main$}", "──────────────────────────")))?
+                .queue(MoveTo(x_main, y_main + y_selection + 3))?
+                .queue(PrintStyledContent(
+                    format!(
+                        "{:^w_main$}",
+                        notice.take().unwrap_or_else(|| {
+                            "([Enter] to play, [t] to play tool-assisted)".to_string()
+                        })
+                    )
+                    .italic(),
+                ))?;
+            // Render normal, versus and special gamemodes.
+            let mode_details = |mode: &&dyn ModeEntry| {
+                (
+                    mode.name().to_string(),
+                    mode.description(&self.game_mode_store),
+                )
+            };
+            for (i, (name, details)) in normal_gamemodes
                 .iter()
-                .chain(special_gamemodes.iter())
+                .map(mode_details)
+                .chain(
+                    versus_gamemodes
+                        .iter()
+                        .map(|(name, details)| (name.to_string(), details.clone())),
+                )
+                .chain(special_gamemodes.iter().map(mode_details))
                 .enumerate()
             {
                 self.term
@@ -655,7 +1263,7 @@ impl<T: Write> TerminalApp<T> {
                         y_main
                             + y_selection
                             + 4
-                            + u16::try_from(i + if normal_gamemodes.len() <= i { 1 } else { 0 })
+                            + u16::try_from(i + if n_unspecial_gamemodes <= i { 1 } else { 0 })
                                 .unwrap(),
                     ))?
                     .queue(Print(format!(
@@ -674,7 +1282,7 @@ impl<T: Write> TerminalApp<T> {
                     y_main
                         + y_selection
                         + 4
-                        + u16::try_from(normal_gamemodes.len() + 1 + special_gamemodes.len() + 1)
+                        + u16::try_from(n_unspecial_gamemodes + 1 + special_gamemodes.len() + 1)
                             .unwrap(),
                 ))?
                 .queue(Print(format!(
@@ -695,7 +1303,19 @@ impl<T: Write> TerminalApp<T> {
                     format!("| level start: {}", self.game_mode_store.start_level),
                     format!(
                         "| level increment: {}",
-                        self.game_mode_store.increment_level
+                        if self.game_mode_store.increment_level {
+                            fmt_level_progression(&self.game_mode_store.level_progression)
+                        } else {
+                            "off".to_string()
+                        }
+                    ),
+                    format!(
+                        "| board: {}",
+                        match &self.game_mode_store.custom_board {
+                            Some(name) => name,
+                            None if self.custom_starting_board.is_some() => "--custom-start",
+                            None => "empty",
+                        }
                     ),
                     format!("| limit: {:?}", self.game_mode_store.custom_mode_limit),
                 ];
@@ -715,9 +1335,19 @@ impl<T: Write> TerminalApp<T> {
                         }))?;
                 }
             }
+            // Render mode files that could not be loaded.
+            if let Some(error) = self.mode_file_errors.first() {
+                let error_str = format!(
+                    "(could not load {} file(s): {error})",
+                    self.mode_file_errors.len()
+                );
+                self.term
+                    .queue(MoveTo(x_main, y_main + Self::H_MAIN - 1))?
+                    .queue(Print(format!("{:^w_main$.w_main$}", error_str)))?;
+            }
             self.term.flush()?;
             // Wait for new input.
-            match event::read()? {
+            match self.read_event()? {
                 // Quit app.
                 Event::Key(KeyEvent {
                     code: KeyCode::Char('c'),
@@ -735,26 +1365,70 @@ impl<T: Write> TerminalApp<T> {
                     kind: Press,
                     ..
                 }) => break Ok(MenuUpdate::Pop),
-                // Try select mode.
+                // Try select mode, possibly to be played tool-assisted.
                 Event::Key(KeyEvent {
-                    code: KeyCode::Enter,
+                    code: code @ (KeyCode::Enter | KeyCode::Char('t')),
                     kind: Press,
                     ..
                 }) => {
-                    let mut game = if selected < normal_gamemodes.len() {
-                        normal_gamemodes[selected].2()
-                    } else if selected < normal_gamemodes.len() + special_gamemodes.len() {
-                        special_gamemodes[selected - normal_gamemodes.len()].2()
+                    let tool_assisted = code == KeyCode::Char('t');
+                    let versus = matches!(
+                        entry,
+                        NewGameEntry::Versus | NewGameEntry::VersusCpu | NewGameEntry::VersusOnline
+                    );
+                    if tool_assisted && versus {
+                        continue;
+                    }
+                    if entry == NewGameEntry::VersusOnline {
+                        break Ok(MenuUpdate::Push(Menu::NetplayLobby));
+                    }
+                    if versus {
+                        let garbage = [SharedGarbage::default(), SharedGarbage::default()];
+                        let mut games = [0, 1].map(|player| {
+                            game_mods::versus_mode::new_game(
+                                garbage[player].clone(),
+                                garbage[1 - player].clone(),
+                            )
+                        });
+                        for game in games.iter_mut() {
+                            game.config_mut().clone_from(&self.game_config);
+                        }
+                        let cpu_difficulty = (entry == NewGameEntry::VersusCpu)
+                            .then_some(self.game_mode_store.versus_cpu_difficulty);
+                        self.fumen_recording = None;
+                        break Ok(MenuUpdate::Push(Menu::VersusGame {
+                            games: Box::new(games),
+                            garbage,
+                            cpu_difficulty,
+                            clock: GameClock::default(),
+                            game_renderer: Default::default(),
+                        }));
+                    }
+                    let seed = rand::thread_rng().gen();
+                    let game = if let Some(mode) = selected_mode {
+                        if tool_assisted {
+                            mode.new_tas_game(&self.game_mode_store, seed)
+                        } else {
+                            Some(mode.new_game(&self.game_mode_store))
+                        }
                     } else {
                         let GameModeStore {
                             name,
                             start_level,
                             increment_level,
+                            level_progression,
                             custom_mode_limit,
+                            custom_board,
                             cheese_mode_limit: _,
                             cheese_mode_gap_size: _,
                             combo_starting_layout: _,
                             descent_mode: _,
+                            master_credit_roll: _,
+                            invisible_mode_variant: _,
+                            dig_race_hole_change: _,
+                            daily_streak: _,
+                            daily_last_played: _,
+                            versus_cpu_difficulty: _,
                         } = self.game_mode_store.clone();
                         let limits = match custom_mode_limit {
                             Some(Stat::Time(max_dur)) => Limits {
@@ -779,31 +1453,79 @@ impl<T: Write> TerminalApp<T> {
                             },
                             None => Limits::default(),
                         };
-                        let mut custom_game = Game::new(GameMode {
+                        let custom_mode = GameMode {
                             name,
                             start_level,
                             increment_level,
+                            level_progression,
+                            big: false,
+                            cascade: false,
+                            zone: false,
                             limits,
-                        });
-                        if let Some(layout_bits) = self.custom_starting_board {
-                            unsafe {
-                                custom_game.add_modifier(game_mods::utils::custom_starting_board(
-                                    layout_bits,
-                                ));
+                        };
+                        let mut custom_game =
+                            Game::with_seed(custom_mode, GameConfig::default(), seed);
+                        let board_layout = self
+                            .board_layouts
+                            .iter()
+                            .find(|layout| Some(&layout.name) == custom_board.as_ref());
+                        let starting_board = if let Some(board_layout) = board_layout {
+                            Some(board_layout.modifier())
+                        } else {
+                            self.custom_starting_board
+                                .map(game_mods::utils::custom_starting_board)
+                        };
+                        match starting_board {
+                            // Starting boards are set up by a game mod, which savestates miss.
+                            Some(_) if tool_assisted => None,
+                            Some(starting_board) => {
+                                unsafe { custom_game.add_modifier(starting_board) };
+                                Some(custom_game)
                             }
+                            None => Some(custom_game),
                         }
-                        custom_game
+                    };
+                    let Some(mut game) = game else {
+                        notice = Some(
+                            "(only modes without game mods can be played tool-assisted)"
+                                .to_string(),
+                        );
+                        continue;
                     };
                     // Set config.
                     game.config_mut().clone_from(&self.game_config);
-                    let now = Instant::now();
+                    if let Some(mode) = selected_mode {
+                        mode.configure(game.config_mut());
+                    }
+                    let telemetry = self.telemetry_target.as_deref().map(|target| {
+                        let mut telemetry = Telemetry::open(target)?;
+                        telemetry.start(game.mode(), game.config())?;
+                        io::Result::Ok(Box::new(telemetry))
+                    });
+                    let telemetry = match telemetry {
+                        Some(Ok(telemetry)) => Some(telemetry),
+                        // Play on without telemetry once the player was told.
+                        Some(Err(e)) => {
+                            self.telemetry_target = None;
+                            notice = Some(format!("(telemetry turned off: {e})"));
+                            continue;
+                        }
+                        None => None,
+                    };
+                    // Savestates would make a mess of the recorded pages.
+                    self.fumen_recording =
+                        (!tool_assisted && !game.mode().big).then(FumenRecording::default);
+                    if let Some(recording) = &self.fumen_recording {
+                        unsafe { game.add_modifier(recording.modifier()) };
+                    }
+                    let tas = tool_assisted.then(|| Box::new(TasSession::new(&game)));
                     break Ok(MenuUpdate::Push(Menu::Game {
                         game: Box::new(game),
-                        time_started: now,
-                        last_paused: now,
-                        total_duration_paused: Duration::ZERO,
+                        clock: GameClock::default(),
                         running_game_stats: RunningGameStats::default(),
                         game_renderer: Default::default(),
+                        telemetry,
+                        tas,
                     }));
                 }
                 // Move selector up or increase stat.
@@ -818,11 +1540,9 @@ impl<T: Write> TerminalApp<T> {
                                 self.game_mode_store.start_level =
                                     self.game_mode_store.start_level.saturating_add(d_level);
                             }
-                            2 => {
-                                self.game_mode_store.increment_level =
-                                    !self.game_mode_store.increment_level;
-                            }
-                            3 => {
+                            2 => self.cycle_level_progression(true),
+                            3 => self.cycle_custom_board(true),
+                            4 => {
                                 match self.game_mode_store.custom_mode_limit {
                                     Some(Stat::Time(ref mut dur)) => {
                                         *dur += d_time;
@@ -863,11 +1583,9 @@ impl<T: Write> TerminalApp<T> {
                                 )
                                 .unwrap_or(NonZeroU32::MIN);
                             }
-                            2 => {
-                                self.game_mode_store.increment_level =
-                                    !self.game_mode_store.increment_level;
-                            }
-                            3 => {
+                            2 => self.cycle_level_progression(false),
+                            3 => self.cycle_custom_board(false),
+                            4 => {
                                 match self.game_mode_store.custom_mode_limit {
                                     Some(Stat::Time(ref mut dur)) => {
                                         *dur = dur.saturating_sub(d_time);
@@ -903,23 +1621,16 @@ impl<T: Write> TerminalApp<T> {
                 }) => {
                     if selected == selection_size - 1 && customization_selected > 0 {
                         customization_selected += customization_selection_size - 1
-                    } else if selected == selection_size - 2 {
-                        let new_layout_idx = if let Some(i) = game_mods::combo_mode::LAYOUTS
-                            .iter()
-                            .position(|lay| *lay == self.game_mode_store.combo_starting_layout)
-                        {
-                            let layout_cnt = game_mods::combo_mode::LAYOUTS.len();
-                            (i + layout_cnt - 1) % layout_cnt
-                        } else {
-                            0
-                        };
-                        self.game_mode_store.combo_starting_layout =
-                            game_mods::combo_mode::LAYOUTS[new_layout_idx];
-                    } else if selected == selection_size - 3 {
-                        if let Some(limit) = self.game_mode_store.cheese_mode_limit {
-                            self.game_mode_store.cheese_mode_limit =
-                                NonZeroUsize::try_from(limit.get() - 1).ok();
-                        }
+                    } else if entry == NewGameEntry::VersusCpu {
+                        let difficulty_cnt = BotDifficulty::ALL.len();
+                        self.game_mode_store.versus_cpu_difficulty =
+                            BotDifficulty::ALL[(self.game_mode_store.versus_cpu_difficulty
+                                as usize
+                                + difficulty_cnt
+                                - 1)
+                                % difficulty_cnt];
+                    } else if let Some(mode) = selected_mode {
+                        mode.adjust(&mut self.game_mode_store, false);
                     }
                 }
                 // Move selector right (select stat).
@@ -946,25 +1657,14 @@ impl<T: Write> TerminalApp<T> {
                         } else {
                             customization_selected += 1
                         }
-                    } else if selected == selection_size - 2 {
-                        let new_layout_idx = if let Some(i) = crate::game_mods::combo_mode::LAYOUTS
-                            .iter()
-                            .position(|lay| *lay == self.game_mode_store.combo_starting_layout)
-                        {
-                            let layout_cnt = crate::game_mods::combo_mode::LAYOUTS.len();
-                            (i + 1) % layout_cnt
-                        } else {
-                            0
-                        };
-                        self.game_mode_store.combo_starting_layout =
-                            crate::game_mods::combo_mode::LAYOUTS[new_layout_idx];
-                    } else if selected == selection_size - 3 {
-                        self.game_mode_store.cheese_mode_limit =
-                            if let Some(limit) = self.game_mode_store.cheese_mode_limit {
-                                limit.checked_add(1)
-                            } else {
-                                Some(NonZeroUsize::MIN)
-                            };
+                    } else if entry == NewGameEntry::VersusCpu {
+                        self.game_mode_store.versus_cpu_difficulty =
+                            BotDifficulty::ALL[(self.game_mode_store.versus_cpu_difficulty
+                                as usize
+                                + 1)
+                                % BotDifficulty::ALL.len()];
+                    } else if let Some(mode) = selected_mode {
+                        mode.adjust(&mut self.game_mode_store, true);
                     }
                 }
                 // Other event: don't care.
@@ -973,21 +1673,66 @@ impl<T: Write> TerminalApp<T> {
         }
     }
 
+    /// Selects the next (or previous) saved board layout for custom games, or none.
+    fn cycle_custom_board(&mut self, forward: bool) {
+        let names = std::iter::once(None)
+            .chain(self.board_layouts.iter().map(|layout| Some(&layout.name)))
+            .collect::<Vec<_>>();
+        let current = names
+            .iter()
+            .position(|name| *name == self.game_mode_store.custom_board.as_ref())
+            .unwrap_or(0);
+        let new = if forward {
+            (current + 1) % names.len()
+        } else {
+            (current + names.len() - 1) % names.len()
+        };
+        self.game_mode_store.custom_board = names[new].cloned();
+    }
+
+    /// Cycles custom games through no level increment and the different level progressions.
+    fn cycle_level_progression(&mut self, forward: bool) {
+        let options = std::iter::once(None)
+            .chain(
+                [
+                    LevelProgression::default(),
+                    LevelProgression::VariableGoal,
+                    LevelProgression::Sections(NonZeroU32::try_from(100).unwrap()),
+                    LevelProgression::Time(Duration::from_secs(60)),
+                ]
+                .map(Some),
+            )
+            .collect::<Vec<_>>();
+        let store = &mut self.game_mode_store;
+        let current = options
+            .iter()
+            .position(|option| *option == store.increment_level.then_some(store.level_progression))
+            .unwrap_or(0);
+        let new = if forward {
+            (current + 1) % options.len()
+        } else {
+            (current + options.len() - 1) % options.len()
+        };
+        store.increment_level = options[new].is_some();
+        if let Some(level_progression) = options[new] {
+            store.level_progression = level_progression;
+        }
+    }
+
     fn game(
         &mut self,
         game: &mut Game,
-        time_started: &mut Instant,
-        last_paused: &mut Instant,
-        total_duration_paused: &mut Duration,
+        clock: &mut GameClock,
         running_game_stats: &mut RunningGameStats,
         game_renderer: &mut impl Renderer,
+        telemetry: &mut Option<Box<Telemetry>>,
     ) -> io::Result<MenuUpdate> {
         // Prepare channel with which to communicate `Button` inputs / game interrupt.
         let mut buttons_pressed = ButtonsPressed::default();
         let (button_sender, button_receiver) = mpsc::channel();
-        let _input_handler =
-            CrosstermHandler::new(&button_sender, &self.settings.keybinds, self.kitty_enabled);
-        let mut combo_bot_handler = (game.mode().name == "Combo (Bot)")
+        let _input_handler = self.input_handler(&button_sender, &self.settings.keybinds);
+        let played_by_combo_bot = self.modes.get(&game.mode().name).played_by_combo_bot();
+        let mut combo_bot_handler = played_by_combo_bot
             .then(|| ComboBotHandler::new(&button_sender, Duration::from_millis(100)));
         let mut inform_combo_bot = |game: &Game, evts: &FeedbackEvents| {
             if let Some((_, state_sender)) = &mut combo_bot_handler {
@@ -1001,9 +1746,41 @@ impl<T: Write> TerminalApp<T> {
                 }
             }
         };
+        let mut tbp_bot_handler = match &self.bot_command {
+            Some(command) if !played_by_combo_bot => {
+                match TbpBotHandler::new(&button_sender, command, Duration::from_millis(50)) {
+                    Ok(handler) => Some(handler),
+                    Err(e) => {
+                        return Ok(MenuUpdate::Push(Menu::Quit(format!(
+                            "failed to launch bot `{command}`: {e}"
+                        ))))
+                    }
+                }
+            }
+            _ => None,
+        };
+        // Tell a resumed bot where it left off.
+        if let Some((_, state_sender)) = &tbp_bot_handler {
+            if let Some(tbp_state) = TbpBotHandler::encode(game) {
+                let _ = state_sender.send(tbp_state);
+            }
+        }
+        let mut inform_tbp_bot = |game: &Game, evts: &FeedbackEvents| {
+            if let Some((_, state_sender)) = &mut tbp_bot_handler {
+                if evts.iter().any(|(_, feedback)| {
+                    matches!(feedback, tetrs_engine::Feedback::PieceSpawned(_))
+                }) {
+                    if let Some(tbp_state) = TbpBotHandler::encode(game) {
+                        if state_sender.send(tbp_state).is_err() {
+                            tbp_bot_handler = None;
+                        }
+                    }
+                }
+            }
+        };
         // Game Loop
         let session_resumed = Instant::now();
-        *total_duration_paused += session_resumed.saturating_duration_since(*last_paused);
+        clock.resume();
         let mut clean_screen = true;
         let mut f = 0u32;
         let mut fps_counter = 0;
@@ -1049,7 +1826,7 @@ impl<T: Write> TerminalApp<T> {
                         )));
                     }
                     Ok(Err(Interrupt::Pause)) => {
-                        *last_paused = Instant::now();
+                        clock.pause();
                         break 'render MenuUpdate::Push(Menu::Pause);
                     }
                     Ok(Err(Interrupt::WindowResize)) => {
@@ -1058,31 +1835,35 @@ impl<T: Write> TerminalApp<T> {
                     }
                     Ok(Ok((instant, button, button_state))) => {
                         buttons_pressed[button] = button_state;
-                        let game_time_userinput = instant.saturating_duration_since(*time_started)
-                            - *total_duration_paused;
-                        let game_now = std::cmp::max(game_time_userinput, game.state().time);
+                        let game_now = std::cmp::max(clock.at(instant), game.state().time);
                         // FIXME: Handle/ensure no Err.
                         if let Ok(evts) = game.update(Some(buttons_pressed), game_now) {
                             inform_combo_bot(game, &evts);
+                            inform_tbp_bot(game, &evts);
                             new_feedback_events.extend(evts);
                         }
                     }
                     Err(mpsc::RecvTimeoutError::Timeout) => {
-                        let game_time_now = Instant::now().saturating_duration_since(*time_started)
-                            - *total_duration_paused;
                         // FIXME: Handle/ensure no Err.
-                        if let Ok(evts) = game.update(None, game_time_now) {
+                        if let Ok(evts) = game.update(None, clock.now()) {
                             inform_combo_bot(game, &evts);
+                            inform_tbp_bot(game, &evts);
                             new_feedback_events.extend(evts);
                         }
                         break 'frame_idle;
                     }
                     Err(mpsc::RecvTimeoutError::Disconnected) => {
                         // NOTE: We kind of rely on this not happening too often.
+                        clock.pause();
                         break 'render MenuUpdate::Push(Menu::Pause);
                     }
                 };
             }
+            if let Some(stream) = telemetry {
+                if stream.update(game.state(), &new_feedback_events).is_err() {
+                    *telemetry = None;
+                }
+            }
             game_renderer.render(
                 self,
                 running_game_stats,
@@ -1103,8 +1884,14 @@ impl<T: Write> TerminalApp<T> {
                 }
             }
         };
+        if let Some(recording) = &mut self.fumen_recording {
+            recording.current = FumenPage::from_state(game.state());
+        }
         if let Some(finished_state) = game.state().end {
-            let h_console = terminal::size()?.1;
+            if let Some(stream) = telemetry {
+                let _ = stream.end(game.state());
+            }
+            let h_console = self.terminal_size()?.1;
             if finished_state.is_ok() {
                 for i in 0..h_console {
                     self.term
@@ -1124,147 +1911,1528 @@ impl<T: Write> TerminalApp<T> {
         Ok(menu_update)
     }
 
-    fn generic_game_ended(
+    /// Runs a single player game tool-assisted: The game clock is stopped unless started on
+    /// purpose, the game can be stepped through frame by frame or event by event, and the
+    /// keybinds toggle whether their button is held instead of pressing it for as long as the key.
+    fn tas_game(
         &mut self,
-        selection: Vec<Menu>,
-        success: bool,
-        finished_game_stats: &FinishedGameStats,
+        game: &mut Game,
+        clock: &mut GameClock,
+        running_game_stats: &mut RunningGameStats,
+        game_renderer: &mut impl Renderer,
+        telemetry: &mut Option<Box<Telemetry>>,
+        tas: &mut TasSession,
     ) -> io::Result<MenuUpdate> {
-        let FinishedGameStats {
-            timestamp: _,
-            actions,
-            score_bonuses,
-            gamemode,
-            last_state,
-        } = finished_game_stats;
-        let GameState {
-            time: game_time,
-            end: _,
-            events: _,
-            buttons_pressed: _,
-            board: _,
-            active_piece_data: _,
-            hold_piece: _,
-            next_pieces: _,
-            pieces_played,
-            lines_cleared,
-            level,
-            score,
-            consecutive_line_clears: _,
-            back_to_back_special_clears: _,
-        } = last_state;
-        if gamemode.name == "Puzzle" && success {
-            self.game_mode_store.descent_mode = true;
-        }
-        let actions_str = [
-            format!(
-                "{} Single{}",
-                actions[1],
-                if actions[1] != 1 { "s" } else { "" }
-            ),
-            format!(
-                "{} Double{}",
-                actions[2],
-                if actions[2] != 1 { "s" } else { "" }
-            ),
-            format!(
-                "{} Triple{}",
-                actions[3],
-                if actions[3] != 1 { "s" } else { "" }
-            ),
-            format!(
-                "{} Quadruple{}",
-                actions[4],
-                if actions[4] != 1 { "s" } else { "" }
-            ),
-            format!(
-                "{} Spin{}",
-                actions[0],
-                if actions[0] != 1 { "s" } else { "" }
-            ),
-        ]
-        .join(", ");
-        let mut selected = 0usize;
+        let mut clean_screen = true;
+        let mut new_feedback_events = Vec::new();
         loop {
-            let w_main = Self::W_MAIN.into();
-            let (x_main, y_main) = Self::fetch_main_xy();
-            let y_selection = Self::H_MAIN / 5;
-            self.term
-                .queue(Clear(ClearType::All))?
-                .queue(MoveTo(x_main, y_main + y_selection))?
-                .queue(Print(format!(
-                    "{:^w_main$}",
-                    if success {
-                        format!(
-                            "+ Game Completed! [{}] +",
-                            gamemode.name.to_ascii_uppercase()
-                        )
+            if let Some(stream) = telemetry {
+                if stream.update(game.state(), &new_feedback_events).is_err() {
+                    *telemetry = None;
+                }
+            }
+            game_renderer.render(
+                self,
+                running_game_stats,
+                game,
+                std::mem::take(&mut new_feedback_events),
+                clean_screen,
+            )?;
+            clean_screen = false;
+            self.render_tas_panel(game, clock, tas)?;
+            // Only a running clock makes the game advance by itself.
+            let event = if clock.is_running() && !game.ended() {
+                let frame_duration = Duration::from_secs_f64(1.0 / self.settings.game_fps);
+                match self.read_event_timeout(frame_duration)? {
+                    Some(event) => event,
+                    None => {
+                        // FIXME: Handle/ensure no Err.
+                        if let Ok(evts) = game.update(None, clock.now()) {
+                            new_feedback_events.extend(evts);
+                        }
+                        continue;
+                    }
+                }
+            } else {
+                self.read_event()?
+            };
+            let (code, modifiers) = match event {
+                Event::Key(KeyEvent {
+                    code,
+                    modifiers,
+                    kind: Press | Repeat,
+                    state: _,
+                }) => (code, modifiers),
+                Event::Resize(..) => {
+                    clean_screen = true;
+                    continue;
+                }
+                _ => continue,
+            };
+            if code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL) {
+                break Ok(MenuUpdate::Push(Menu::Quit(
+                    "exited with ctrl-c".to_string(),
+                )));
+            }
+            // Typing the name of a savestate.
+            if let Some((prompt, mut name)) = tas.prompt.take() {
+                match code {
+                    KeyCode::Enter => match prompt {
+                        TasPrompt::SaveState => {
+                            tas.message = format!("saved {name:?}");
+                            tas.save(name, game, running_game_stats);
+                        }
+                        TasPrompt::LoadState => {
+                            if tas.load(&name, game, running_game_stats) {
+                                clock.set(game.state().time);
+                                clean_screen = true;
+                                tas.message = format!("loaded {name:?}");
+                            } else {
+                                tas.message = format!("no savestate {name:?}");
+                            }
+                        }
+                    },
+                    KeyCode::Esc => {}
+                    KeyCode::Tab => {
+                        if let Some(next_name) = tas.next_savestate_name(&name) {
+                            name = next_name.to_string();
+                        }
+                        tas.prompt = Some((prompt, name));
+                    }
+                    KeyCode::Backspace => {
+                        name.pop();
+                        tas.prompt = Some((prompt, name));
+                    }
+                    KeyCode::Char(ch) => {
+                        name.push(ch);
+                        tas.prompt = Some((prompt, name));
+                    }
+                    _ => tas.prompt = Some((prompt, name)),
+                }
+                continue;
+            }
+            let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+            match code {
+                KeyCode::Char('d') if ctrl => {
+                    game.forfeit();
+                    let finished_game_stats = FinishedGameStats::new(game, running_game_stats);
+                    break Ok(MenuUpdate::Push(Menu::GameOver(Box::new(
+                        finished_game_stats,
+                    ))));
+                }
+                // Leave the game; Tool-assisted games are not kept as records.
+                KeyCode::Esc => {
+                    clock.pause();
+                    if !game.ended() {
+                        break Ok(MenuUpdate::Push(Menu::Pause));
+                    }
+                    let finished_game_stats = FinishedGameStats::new(game, running_game_stats);
+                    let menu = if finished_game_stats.was_successful() {
+                        Menu::GameComplete
                     } else {
-                        format!(
-                            "- Game Over ({:?}). [{}] -",
-                            last_state.end.unwrap().unwrap_err(),
-                            gamemode.name
-                        )
+                        Menu::GameOver
+                    }(Box::new(finished_game_stats));
+                    break Ok(MenuUpdate::Push(menu));
+                }
+                KeyCode::Char('r') if ctrl => {
+                    if clock.is_running() {
+                        clock.pause();
+                    } else {
+                        clock.set(game.state().time);
+                        clock.resume();
                     }
-                )))?
-                /*.queue(MoveTo(0, y_main + y_selection + 2))?
-                .queue(Print(Self::produce_header()?))?*/
-                .queue(MoveTo(x_main, y_main + y_selection + 2))?
-                .queue(Print(format!("{:^w_main$}", "──────────────────────────")))?
-                .queue(MoveTo(x_main, y_main + y_selection + 4))?
-                .queue(Print(format!("{:^w_main$}", format!("Score: {score}"))))?
-                .queue(MoveTo(x_main, y_main + y_selection + 5))?
-                .queue(Print(format!("{:^w_main$}", format!("Level: {level}",))))?
-                .queue(MoveTo(x_main, y_main + y_selection + 6))?
-                .queue(Print(format!(
-                    "{:^w_main$}",
-                    format!("Lines: {}", lines_cleared)
-                )))?
-                .queue(MoveTo(x_main, y_main + y_selection + 7))?
-                .queue(Print(format!(
-                    "{:^w_main$}",
-                    format!("Tetrominos: {}", pieces_played.iter().sum::<u32>())
-                )))?
-                .queue(MoveTo(x_main, y_main + y_selection + 8))?
-                .queue(Print(format!(
-                    "{:^w_main$}",
-                    format!("Time: {}", fmt_duration(*game_time))
-                )))?
-                .queue(MoveTo(x_main, y_main + y_selection + 10))?
-                .queue(Print(format!("{:^w_main$}", actions_str)))?
-                .queue(MoveTo(x_main, y_main + y_selection + 11))?
+                }
+                KeyCode::Char('s') if ctrl => {
+                    let name = format!("state {}", tas.savestate_names().count() + 1);
+                    tas.prompt = Some((TasPrompt::SaveState, name));
+                }
+                KeyCode::Char('l') if ctrl => {
+                    let name = tas.savestate_names().last().unwrap_or_default().to_string();
+                    tas.prompt = Some((TasPrompt::LoadState, name));
+                }
+                KeyCode::Char('e') if ctrl => {
+                    tas.message = if self.socket_session.is_some() {
+                        "(saving is disabled for remote players)".to_string()
+                    } else {
+                        let name = format!("replay {}", chrono::Utc::now().format("%Y%m%d %H%M%S"));
+                        let saved = serde_json::to_string(&tas.replay(game))
+                            .map_err(io::Error::from)
+                            .and_then(|replay| {
+                                utils::save_text_file(&Self::replays_dir(), &name, "json", &replay)
+                            });
+                        match saved {
+                            Ok(path) => format!("saved to {path:?}"),
+                            Err(e) => format!("could not save: {e}"),
+                        }
+                    };
+                }
+                _ if game.ended() => {}
+                // Advance by a single frame, or up to whatever is scheduled to happen next.
+                KeyCode::Char(ch @ ('.' | '>')) => {
+                    let next_frame = TasSession::next_frame(game);
+                    let update_time = if ch == '>' {
+                        TasSession::next_event(game).unwrap_or(next_frame)
+                    } else {
+                        next_frame
+                    };
+                    // FIXME: Handle/ensure no Err.
+                    if let Ok(evts) = game.update(None, update_time.max(game.state().time)) {
+                        new_feedback_events.extend(evts);
+                    }
+                    clock.set(game.state().time);
+                }
+                // Hold or release a button.
+                code => {
+                    let Some(&button) = self.settings.keybinds.get(&code) else {
+                        continue;
+                    };
+                    tas.buttons_pressed[button] = !tas.buttons_pressed[button];
+                    let update_time = clock.now().max(game.state().time);
+                    // FIXME: Handle/ensure no Err.
+                    if let Ok(evts) = game.update(Some(tas.buttons_pressed), update_time) {
+                        new_feedback_events.extend(evts);
+                        tas.inputs.push((game.state().time, tas.buttons_pressed));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Shows the state of a tool-assisted game next to it.
+    fn render_tas_panel(
+        &mut self,
+        game: &Game,
+        clock: &GameClock,
+        tas: &TasSession,
+    ) -> io::Result<()> {
+        const W_PANEL: usize = 19;
+        let (x_main, y_main) = self.fetch_main_xy();
+        let time = game.state().time;
+        let mut lines = vec![
+            "TOOL-ASSISTED".to_string(),
+            if game.ended() {
+                "game ended"
+            } else if clock.is_running() {
+                "clock running"
+            } else {
+                "clock stopped"
+            }
+            .to_string(),
+            format!("time {:.3}s", time.as_secs_f64()),
+            game.frame_at(time)
+                .map_or(String::new(), |frame| format!("frame {frame}")),
+        ];
+        for button in TasSession::BUTTONS {
+            let held = if tas.buttons_pressed[button] {
+                'x'
+            } else {
+                ' '
+            };
+            lines.push(format!("[{held}] {button:?}"));
+        }
+        lines.push(format!("inputs: {}", tas.inputs.len()));
+        lines.push(format!("states: {}", tas.savestate_names().count()));
+        lines.push(tas.message.clone());
+        lines.push(match &tas.prompt {
+            Some((TasPrompt::SaveState, name)) => format!("save: {name}_"),
+            Some((TasPrompt::LoadState, name)) => format!("load: {name}_"),
+            None => String::new(),
+        });
+        lines.push("[.] frame [>] event".to_string());
+        lines.push("^R clock ^E export".to_string());
+        lines.push("^S save ^L load".to_string());
+        for (i, line) in lines.into_iter().enumerate() {
+            // Keep the end of long lines (such as file paths) visible.
+            let skip = line.chars().count().saturating_sub(W_PANEL);
+            self.term
+                .queue(MoveTo(x_main + 61, y_main + 1 + u16::try_from(i).unwrap()))?
                 .queue(Print(format!(
-                    "{:^w_main$}",
-                    format!(
-                        "Average score bonus: {:.1}",
-                        score_bonuses.iter().copied().map(u64::from).sum::<u64>() as f64
-                            / (score_bonuses.len() as f64/*I give up*/)
-                    )
-                )))?
-                .queue(MoveTo(x_main, y_main + y_selection + 13))?
+                    "{:<W_PANEL$}",
+                    line.chars().skip(skip).collect::<String>()
+                )))?;
+        }
+        self.term.flush()
+    }
+
+    fn versus_game(
+        &mut self,
+        games: &mut [Game; 2],
+        garbage: &[SharedGarbage; 2],
+        cpu_difficulty: Option<BotDifficulty>,
+        clock: &mut GameClock,
+        game_renderer: &mut VersusRenderer,
+    ) -> io::Result<MenuUpdate> {
+        // Prepare channel with which to communicate `(player, Button)` inputs / game interrupt.
+        let mut buttons_pressed = [ButtonsPressed::default(); 2];
+        let (button_sender, button_receiver) = mpsc::channel();
+        // Against the CPU the player uses the single player controls.
+        let versus_keybinds = if cpu_difficulty.is_some() {
+            self.settings
+                .keybinds
+                .iter()
+                .map(|(&code, &button)| (code, (0, button)))
+                .collect::<HashMap<_, _>>()
+        } else {
+            self.settings
+                .versus_keybinds
+                .iter()
+                .enumerate()
+                .flat_map(|(player, keybinds)| {
+                    keybinds
+                        .iter()
+                        .map(move |(&code, &button)| (code, (player, button)))
+                })
+                .collect::<HashMap<_, _>>()
+        };
+        let _input_handler = self.input_handler(&button_sender, &versus_keybinds);
+        let mut versus_bot_handler =
+            cpu_difficulty.map(|difficulty| VersusBotHandler::new(&button_sender, 1, difficulty));
+        // A piece may already be in play when resuming, tell the bot about it.
+        if let Some((_, state_sender)) = &versus_bot_handler {
+            if let Some(bot_state) = VersusBotHandler::encode(&games[1]) {
+                let _ = state_sender.send(bot_state);
+            }
+        }
+        let mut inform_versus_bot = |game: &Game, evts: &FeedbackEvents| {
+            if let Some((_, state_sender)) = &mut versus_bot_handler {
+                if evts.iter().any(|(_, feedback)| {
+                    matches!(feedback, tetrs_engine::Feedback::PieceSpawned(_))
+                }) {
+                    if let Some(bot_state) = VersusBotHandler::encode(game) {
+                        if state_sender.send(bot_state).is_err() {
+                            versus_bot_handler = None;
+                        }
+                    }
+                }
+            }
+        };
+        let player_names = [
+            "Player 1".to_string(),
+            if let Some(difficulty) = cpu_difficulty {
+                format!("CPU ({difficulty:?})")
+            } else {
+                "Player 2".to_string()
+            },
+        ];
+        // Game Loop
+        let session_resumed = Instant::now();
+        clock.resume();
+        let mut clean_screen = true;
+        let mut f = 0u32;
+        let menu_update = 'render: loop {
+            // Exit if either game ended.
+            if games.iter().any(Game::ended) {
+                let result = match (games[0].ended(), games[1].ended()) {
+                    (true, true) => "Draw!".to_string(),
+                    (false, _) => format!("{} wins!", player_names[0]),
+                    (true, false) => format!("{} wins!", player_names[1]),
+                };
+                break 'render MenuUpdate::Push(Menu::VersusOver(result));
+            }
+            // Start next frame
+            f += 1;
+            let next_frame_at = loop {
+                let frame_at = session_resumed
+                    + Duration::from_secs_f64(f64::from(f) / self.settings.game_fps);
+                if frame_at < Instant::now() {
+                    f += 1;
+                } else {
+                    break frame_at;
+                }
+            };
+            let mut new_feedback_events = [Vec::new(), Vec::new()];
+            'frame_idle: loop {
+                let frame_idle_remaining = next_frame_at - Instant::now();
+                match button_receiver.recv_timeout(frame_idle_remaining) {
+                    Ok(Err(Interrupt::ExitProgram)) => {
+                        break 'render MenuUpdate::Push(Menu::Quit(
+                            "exited with ctrl-c".to_string(),
+                        ));
+                    }
+                    Ok(Err(Interrupt::ForfeitGame)) => {
+                        for game in games.iter_mut() {
+                            game.forfeit();
+                        }
+                        break 'render MenuUpdate::Push(Menu::VersusOver(
+                            "Match forfeited.".to_string(),
+                        ));
+                    }
+                    Ok(Err(Interrupt::Pause)) => {
+                        clock.pause();
+                        break 'render MenuUpdate::Push(Menu::Pause);
+                    }
+                    Ok(Err(Interrupt::WindowResize)) => {
+                        clean_screen = true;
+                        continue 'frame_idle;
+                    }
+                    Ok(Ok((instant, (player, button), button_state))) => {
+                        buttons_pressed[player][button] = button_state;
+                        let game = &mut games[player];
+                        let game_now = std::cmp::max(clock.at(instant), game.state().time);
+                        // FIXME: Handle/ensure no Err.
+                        if let Ok(evts) = game.update(Some(buttons_pressed[player]), game_now) {
+                            if player == 1 {
+                                inform_versus_bot(game, &evts);
+                            }
+                            new_feedback_events[player].extend(evts);
+                        }
+                    }
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        let game_time_now = clock.now();
+                        for (player, (game, feedback_events)) in games
+                            .iter_mut()
+                            .zip(new_feedback_events.iter_mut())
+                            .enumerate()
+                        {
+                            // FIXME: Handle/ensure no Err.
+                            if let Ok(evts) = game.update(None, game_time_now) {
+                                if player == 1 {
+                                    inform_versus_bot(game, &evts);
+                                }
+                                feedback_events.extend(evts);
+                            }
+                        }
+                        break 'frame_idle;
+                    }
+                    Err(mpsc::RecvTimeoutError::Disconnected) => {
+                        // NOTE: We kind of rely on this not happening too often.
+                        clock.pause();
+                        break 'render MenuUpdate::Push(Menu::Pause);
+                    }
+                };
+            }
+            game_renderer.render(
+                self,
+                [
+                    (&player_names[0], games[0].state(), &garbage[0].borrow()),
+                    (&player_names[1], games[1].state(), &garbage[1].borrow()),
+                ],
+                new_feedback_events,
+                clean_screen,
+            )?;
+            clean_screen = false;
+        };
+        Ok(menu_update)
+    }
+
+    fn netplay_lobby(&mut self) -> io::Result<MenuUpdate> {
+        let Some(netplay_role) = self.netplay_role.clone() else {
+            return Ok(MenuUpdate::Pop);
+        };
+        let garbage = [SharedGarbage::default(), SharedGarbage::default()];
+        // The second exchange only collects garbage to be sent over the network.
+        let mut game = game_mods::versus_mode::new_game(garbage[0].clone(), garbage[1].clone());
+        game.config_mut().clone_from(&self.game_config);
+        let rules = netplay::rules(game.config(), game.mode());
+        let w_main = Self::W_MAIN.into();
+        let (x_main, y_main) = self.fetch_main_xy();
+        let y_selection = Self::H_MAIN / 5;
+        self.term
+            .queue(Clear(ClearType::All))?
+            .queue(MoveTo(x_main, y_main + y_selection))?
+            .queue(Print(format!("{:^w_main$}", "* Versus Online *")))?
+            .queue(MoveTo(x_main, y_main + y_selection + 2))?
+            .queue(Print(format!("{:^w_main$}", "──────────────────────────")))?
+            .queue(MoveTo(x_main, y_main + y_selection + 4))?
+            .queue(Print(format!(
+                "{:^w_main$}",
+                format!("Connecting ({netplay_role})...")
+            )))?
+            .queue(MoveTo(x_main, y_main + y_selection + 6))?
+            .queue(PrintStyledContent(
+                format!("{:^w_main$}", "Press [Esc] to cancel.").italic(),
+            ))?;
+        self.term.flush()?;
+        let connection = match &netplay_role {
+            NetplayRole::Host(addr) => NetplayConnection::host(addr, &rules, || {
+                Ok(!matches!(
+                    self.read_event_timeout(Duration::from_millis(100))?,
+                    Some(Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: Press,
+                        ..
+                    }))
+                ))
+            }),
+            NetplayRole::Join(addr) => NetplayConnection::join(addr, &rules).map(Some),
+        };
+        match connection {
+            Ok(Some(connection)) => Ok(MenuUpdate::Push(Menu::NetplayGame {
+                game: Box::new(game),
+                garbage,
+                connection: Box::new(connection),
+                time_started: Instant::now(),
+                game_renderer: Default::default(),
+            })),
+            Ok(None) => Ok(MenuUpdate::Pop),
+            Err(e) => {
+                self.term
+                    .queue(MoveTo(x_main, y_main + y_selection + 4))?
+                    .queue(Clear(ClearType::CurrentLine))?
+                    .queue(Print(format!(
+                        "{:^w_main$}",
+                        format!("Could not connect: {e}")
+                    )))?
+                    .queue(MoveTo(x_main, y_main + y_selection + 6))?
+                    .queue(Clear(ClearType::CurrentLine))?
+                    .queue(PrintStyledContent(
+                        format!("{:^w_main$}", "Press any key to return.").italic(),
+                    ))?;
+                self.term.flush()?;
+                while !matches!(self.read_event()?, Event::Key(KeyEvent { kind: Press, .. })) {}
+                Ok(MenuUpdate::Pop)
+            }
+        }
+    }
+
+    fn netplay_game(
+        &mut self,
+        game: &mut Game,
+        garbage: &[SharedGarbage; 2],
+        connection: &mut NetplayConnection,
+        time_started: Instant,
+        game_renderer: &mut VersusRenderer,
+    ) -> io::Result<MenuUpdate> {
+        // How often to send a snapshot of the game to the opponent.
+        const STATE_INTERVAL: Duration = Duration::from_millis(50);
+        // Prepare channel with which to communicate `Button` inputs / game interrupt.
+        let mut buttons_pressed = ButtonsPressed::default();
+        let (button_sender, button_receiver) = mpsc::channel();
+        let _input_handler = self.input_handler(&button_sender, &self.settings.keybinds);
+        let player_names = ["You", "Opponent"];
+        let mut opponent_state = Game::new(game.mode().clone()).state().clone();
+        let mut opponent_garbage = GarbageExchange::default();
+        let mut opponent_ended = false;
+        let (mut attacks_sent, mut attacks_received, mut lines_received) = (0u32, 0u32, 0u32);
+        let mut last_state_sent: Option<Instant> = None;
+        // Game Loop
+        let mut clean_screen = true;
+        let mut f = 0u32;
+        let menu_update = 'render: loop {
+            // Exit if either game ended.
+            if game.ended() || opponent_ended {
+                if game.ended() {
+                    let _ = connection.send(&NetMessage::GameOver);
+                }
+                let result = if game.ended() && opponent_ended {
+                    "Draw!"
+                } else if game.ended() {
+                    "Opponent wins!"
+                } else {
+                    "You win!"
+                };
+                break 'render MenuUpdate::Push(Menu::VersusOver(result.to_string()));
+            }
+            // Start next frame
+            f += 1;
+            let next_frame_at = loop {
+                let frame_at =
+                    time_started + Duration::from_secs_f64(f64::from(f) / self.settings.game_fps);
+                if frame_at < Instant::now() {
+                    f += 1;
+                } else {
+                    break frame_at;
+                }
+            };
+            let mut new_feedback_events = Vec::new();
+            'frame_idle: loop {
+                let frame_idle_remaining = next_frame_at - Instant::now();
+                match button_receiver.recv_timeout(frame_idle_remaining) {
+                    Ok(Err(Interrupt::ExitProgram)) => {
+                        let _ = connection.send(&NetMessage::Forfeit);
+                        break 'render MenuUpdate::Push(Menu::Quit(
+                            "exited with ctrl-c".to_string(),
+                        ));
+                    }
+                    Ok(Err(Interrupt::ForfeitGame)) => {
+                        game.forfeit();
+                        let _ = connection.send(&NetMessage::Forfeit);
+                        break 'render MenuUpdate::Push(Menu::VersusOver(
+                            "Match forfeited.".to_string(),
+                        ));
+                    }
+                    // A match over the network cannot be paused.
+                    Ok(Err(Interrupt::Pause)) => continue 'frame_idle,
+                    Ok(Err(Interrupt::WindowResize)) => {
+                        clean_screen = true;
+                        continue 'frame_idle;
+                    }
+                    Ok(Ok((instant, button, button_state))) => {
+                        buttons_pressed[button] = button_state;
+                        let game_time_userinput = instant.saturating_duration_since(time_started);
+                        let game_now = std::cmp::max(game_time_userinput, game.state().time);
+                        // FIXME: Handle/ensure no Err.
+                        if let Ok(evts) = game.update(Some(buttons_pressed), game_now) {
+                            new_feedback_events.extend(evts);
+                        }
+                    }
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        let game_time_now = Instant::now().saturating_duration_since(time_started);
+                        // FIXME: Handle/ensure no Err.
+                        if let Ok(evts) = game.update(None, game_time_now) {
+                            new_feedback_events.extend(evts);
+                        }
+                        break 'frame_idle;
+                    }
+                    Err(mpsc::RecvTimeoutError::Disconnected) => {
+                        // NOTE: We kind of rely on this not happening too often.
+                        continue 'frame_idle;
+                    }
+                };
+            }
+            // Send out attacks and snapshots of our game.
+            let mut outgoing = garbage[1]
+                .borrow_mut()
+                .incoming
+                .drain(..)
+                .map(|(lines, hole)| {
+                    attacks_sent += 1;
+                    NetMessage::Attack {
+                        seq: attacks_sent - 1,
+                        lines,
+                        hole,
+                    }
+                })
+                .collect::<Vec<_>>();
+            let now = Instant::now();
+            if last_state_sent.is_none_or(|sent| now.duration_since(sent) >= STATE_INTERVAL) {
+                last_state_sent = Some(now);
+                let mut state = game.state().clone();
+                // The opponent only needs to render our game.
+                state.events.clear();
+                let garbage = garbage[0].borrow();
+                outgoing.push(NetMessage::State {
+                    state: Box::new(state),
+                    garbage_pending: garbage.pending(),
+                    lines_sent: garbage.lines_sent,
+                    lines_received,
+                });
+            }
+            for message in outgoing {
+                if let Err(e) = connection.send(&message) {
+                    break 'render MenuUpdate::Push(Menu::VersusOver(format!(
+                        "Connection lost: {e}"
+                    )));
+                }
+            }
+            // Receive whatever the opponent sent.
+            loop {
+                let desync = match connection.try_recv() {
+                    Ok(None) => break,
+                    Ok(Some(NetMessage::State {
+                        state,
+                        garbage_pending,
+                        lines_sent,
+                        lines_received: opponent_lines_received,
+                    })) => {
+                        if let Some(error) = netplay::state_error(&state) {
+                            Some(error)
+                        } else {
+                            opponent_state = *state;
+                            opponent_garbage.incoming = [(garbage_pending, 0)].into();
+                            opponent_garbage.lines_sent = lines_sent;
+                            // Neither side can have received more garbage than the other one sent.
+                            (opponent_lines_received > garbage[0].borrow().lines_sent
+                                || lines_received > lines_sent)
+                                .then_some("garbage totals differ")
+                        }
+                    }
+                    Ok(Some(NetMessage::Attack { seq, lines, hole })) => {
+                        let lines_total = lines_received.checked_add(lines);
+                        if seq != attacks_received {
+                            Some("attack out of order")
+                        } else if !usize::try_from(lines).is_ok_and(|lines| lines <= Game::HEIGHT) {
+                            // No single piece clears its way to an attack this large.
+                            Some("attack too large")
+                        } else if let Some(lines_total) = lines_total {
+                            attacks_received += 1;
+                            lines_received = lines_total;
+                            garbage[0].borrow_mut().incoming.push_back((lines, hole));
+                            None
+                        } else {
+                            Some("garbage totals differ")
+                        }
+                    }
+                    Ok(Some(NetMessage::GameOver)) => {
+                        // Nothing else will arrive, the opponent is leaving the match.
+                        opponent_ended = true;
+                        break;
+                    }
+                    Ok(Some(NetMessage::Forfeit)) => {
+                        break 'render MenuUpdate::Push(Menu::VersusOver(
+                            "Opponent forfeited, you win!".to_string(),
+                        ));
+                    }
+                    Ok(Some(_)) => Some("unexpected message"),
+                    Err(e) => {
+                        break 'render MenuUpdate::Push(Menu::VersusOver(format!(
+                            "Opponent disconnected: {e}"
+                        )));
+                    }
+                };
+                if let Some(desync) = desync {
+                    break 'render MenuUpdate::Push(Menu::VersusOver(format!(
+                        "Desync detected ({desync}), match aborted."
+                    )));
+                }
+            }
+            game_renderer.render(
+                self,
+                [
+                    (player_names[0], game.state(), &garbage[0].borrow()),
+                    (player_names[1], &opponent_state, &opponent_garbage),
+                ],
+                [new_feedback_events, Vec::new()],
+                clean_screen,
+            )?;
+            clean_screen = false;
+        };
+        Ok(menu_update)
+    }
+
+    fn versus_over_menu(&mut self, result: &str) -> io::Result<MenuUpdate> {
+        let selection = vec![
+            Menu::NewGame,
+            Menu::Settings,
+            Menu::Scores,
+            Menu::Quit("quit after versus match".to_string()),
+        ];
+        self.generic_placeholder_widget(result, selection)
+    }
+
+    fn generic_game_ended(
+        &mut self,
+        selection: Vec<Menu>,
+        success: bool,
+        finished_game_stats: &FinishedGameStats,
+    ) -> io::Result<MenuUpdate> {
+        let FinishedGameStats {
+            timestamp: _,
+            actions,
+            score_bonuses,
+            gamemode,
+            last_state,
+        } = finished_game_stats;
+        let GameState {
+            time: game_time,
+            end: _,
+            events: _,
+            buttons_pressed: _,
+            board: _,
+            active_piece_data: _,
+            hold_piece: _,
+            next_pieces: _,
+            pieces_played,
+            lines_cleared,
+            level,
+            score,
+            consecutive_line_clears: _,
+            back_to_back_special_clears: _,
+            mod_stats: _,
+            pieces_since_line_clear: _,
+            piece_spawn_time: _,
+            garbage_lines_cleared: _,
+            level_progress: _,
+            gravity_suspended: _,
+            zone_meter: _,
+            zone_end: _,
+            zone_lines: _,
+            last_rotation: _,
+            board_lock_times: _,
+        } = last_state;
+        self.modes.get(&gamemode.name).on_game_ended(
+            &mut self.game_mode_store,
+            gamemode,
+            last_state,
+        );
+        let actions_str = [
+            format!(
+                "{} Single{}",
+                actions[1],
+                if actions[1] != 1 { "s" } else { "" }
+            ),
+            format!(
+                "{} Double{}",
+                actions[2],
+                if actions[2] != 1 { "s" } else { "" }
+            ),
+            format!(
+                "{} Triple{}",
+                actions[3],
+                if actions[3] != 1 { "s" } else { "" }
+            ),
+            format!(
+                "{} Quadruple{}",
+                actions[4],
+                if actions[4] != 1 { "s" } else { "" }
+            ),
+            format!(
+                "{} Spin{}",
+                actions[0],
+                if actions[0] != 1 { "s" } else { "" }
+            ),
+        ]
+        .join(", ");
+        let mut selected = 0usize;
+        loop {
+            let w_main = Self::W_MAIN.into();
+            let (x_main, y_main) = self.fetch_main_xy();
+            let y_selection = Self::H_MAIN / 5;
+            self.term
+                .queue(Clear(ClearType::All))?
+                .queue(MoveTo(x_main, y_main + y_selection))?
+                .queue(Print(format!(
+                    "{:^w_main$}",
+                    if success {
+                        format!(
+                            "+ Game Completed! [{}] +",
+                            gamemode.name.to_ascii_uppercase()
+                        )
+                    } else {
+                        format!(
+                            "- Game Over ({:?}). [{}] -",
+                            last_state.end.unwrap().unwrap_err(),
+                            gamemode.name
+                        )
+                    }
+                )))?
+                /*.queue(MoveTo(0, y_main + y_selection + 2))?
+                .queue(Print(Self::produce_header()?))?*/
+                .queue(MoveTo(x_main, y_main + y_selection + 2))?
+                .queue(Print(format!("{:^w_main$}", "──────────────────────────")))?
+                .queue(MoveTo(x_main, y_main + y_selection + 4))?
+                .queue(Print(format!("{:^w_main$}", format!("Score: {score}"))))?
+                .queue(MoveTo(x_main, y_main + y_selection + 5))?
+                .queue(Print(format!("{:^w_main$}", format!("Level: {level}",))))?
+                .queue(MoveTo(x_main, y_main + y_selection + 6))?
+                .queue(Print(format!(
+                    "{:^w_main$}",
+                    format!("Lines: {}", lines_cleared)
+                )))?
+                .queue(MoveTo(x_main, y_main + y_selection + 7))?
+                .queue(Print(format!(
+                    "{:^w_main$}",
+                    format!("Tetrominos: {}", pieces_played.iter().sum::<u32>())
+                )))?
+                .queue(MoveTo(x_main, y_main + y_selection + 8))?
+                .queue(Print(format!(
+                    "{:^w_main$}",
+                    format!("Time: {}", fmt_duration(*game_time))
+                )))?
+                .queue(MoveTo(x_main, y_main + y_selection + 9))?
+                .queue(Print(format!(
+                    "{:^w_main$}",
+                    self.modes
+                        .get(&gamemode.name)
+                        .result(gamemode, last_state)
+                        .unwrap_or_default()
+                )))?
+                .queue(MoveTo(x_main, y_main + y_selection + 10))?
+                .queue(Print(format!("{:^w_main$}", actions_str)))?
+                .queue(MoveTo(x_main, y_main + y_selection + 11))?
+                .queue(Print(format!(
+                    "{:^w_main$}",
+                    format!(
+                        "Average score bonus: {:.1}",
+                        score_bonuses.iter().copied().map(u64::from).sum::<u64>() as f64
+                            / (score_bonuses.len() as f64/*I give up*/)
+                    )
+                )))?
+                .queue(MoveTo(x_main, y_main + y_selection + 13))?
+                .queue(Print(format!("{:^w_main$}", "──────────────────────────")))?;
+            let names = selection
+                .iter()
+                .map(|menu| menu.to_string())
+                .collect::<Vec<_>>();
+            for (i, name) in names.into_iter().enumerate() {
+                self.term
+                    .queue(MoveTo(
+                        x_main,
+                        y_main + y_selection + 14 + u16::try_from(i).unwrap(),
+                    ))?
+                    .queue(Print(format!(
+                        "{:^w_main$}",
+                        if i == selected {
+                            format!(">>> {name} <<<")
+                        } else {
+                            name
+                        }
+                    )))?;
+            }
+            self.term.flush()?;
+            // Wait for new input.
+            match self.read_event()? {
+                // Quit menu.
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char('c'),
+                    modifiers: KeyModifiers::CONTROL,
+                    kind: Press | Repeat,
+                    state: _,
+                }) => {
+                    break Ok(MenuUpdate::Push(Menu::Quit(
+                        "exited with ctrl-c".to_string(),
+                    )))
+                }
+                Event::Key(KeyEvent {
+                    code: KeyCode::Esc,
+                    kind: Press,
+                    ..
+                }) => break Ok(MenuUpdate::Pop),
+                // Select next menu.
+                Event::Key(KeyEvent {
+                    code: KeyCode::Enter,
+                    kind: Press,
+                    ..
+                }) => {
+                    if !selection.is_empty() {
+                        let menu = selection.into_iter().nth(selected).unwrap();
+                        break Ok(MenuUpdate::Push(menu));
+                    }
+                }
+                // Move selector up.
+                Event::Key(KeyEvent {
+                    code: KeyCode::Up,
+                    kind: Press | Repeat,
+                    ..
+                }) => {
+                    if !selection.is_empty() {
+                        selected += selection.len() - 1;
+                    }
+                }
+                // Move selector down.
+                Event::Key(KeyEvent {
+                    code: KeyCode::Down,
+                    kind: Press | Repeat,
+                    ..
+                }) => {
+                    if !selection.is_empty() {
+                        selected += 1;
+                    }
+                }
+                // Other event: don't care.
+                _ => {}
+            }
+            if !selection.is_empty() {
+                selected = selected.rem_euclid(selection.len());
+            }
+        }
+    }
+
+    fn game_over_menu(
+        &mut self,
+        finished_game_stats: &FinishedGameStats,
+    ) -> io::Result<MenuUpdate> {
+        let mut selection = vec![
+            Menu::NewGame,
+            Menu::Settings,
+            Menu::Scores,
+            Menu::Quit("quit after game over".to_string()),
+        ];
+        if let Some(recording) = &self.fumen_recording {
+            selection.insert(3, Menu::ExportFumen(recording.exports()));
+        }
+        if self.puzzle_editor.test_playing {
+            selection.insert(0, Menu::PuzzleEditor);
+        }
+        self.generic_game_ended(selection, false, finished_game_stats)
+    }
+
+    fn game_complete_menu(
+        &mut self,
+        finished_game_stats: &FinishedGameStats,
+    ) -> io::Result<MenuUpdate> {
+        let mut selection = vec![
+            Menu::NewGame,
+            Menu::Settings,
+            Menu::Scores,
+            Menu::Quit("quit after game complete".to_string()),
+        ];
+        if let Some(recording) = &self.fumen_recording {
+            selection.insert(3, Menu::ExportFumen(recording.exports()));
+        }
+        if self.puzzle_editor.test_playing {
+            selection.insert(0, Menu::PuzzleEditor);
+        }
+        self.generic_game_ended(selection, true, finished_game_stats)
+    }
+
+    fn pause_menu(&mut self) -> io::Result<MenuUpdate> {
+        let mut selection = vec![
+            Menu::NewGame,
+            Menu::Settings,
+            Menu::Scores,
+            Menu::About,
+            Menu::Quit("quit from pause".to_string()),
+        ];
+        if let Some(recording) = &self.fumen_recording {
+            selection.insert(3, Menu::ExportFumen(recording.exports()));
+        }
+        if self.puzzle_editor.test_playing {
+            selection.insert(0, Menu::PuzzleEditor);
+        }
+        self.generic_placeholder_widget("GAME PAUSED", selection)
+    }
+
+    fn puzzle_editor_menu(&mut self) -> io::Result<MenuUpdate> {
+        self.puzzle_editor.test_playing = false;
+        loop {
+            let w_main = Self::W_MAIN.into();
+            let (x_main, y_main) = self.fetch_main_xy();
+            let editor = &self.puzzle_editor.clone();
+            let puzzle = editor.puzzle();
+            self.term
+                .queue(Clear(ClearType::All))?
+                .queue(MoveTo(x_main, y_main))?
+                .queue(Print(format!(
+                    "{:^w_main$}",
+                    format!(
+                        "[ Puzzle Editor: {} (stage {}/{}) ]",
+                        editor.pack.name,
+                        editor.stage + 1,
+                        editor.pack.puzzles.len()
+                    )
+                )))?;
+            // Render board.
+            let (x_board, y_board) = (x_main + 4, y_main + 2);
+            let cursor = editor.text_input.is_none().then_some(editor.cursor);
+            self.draw_editor_board(editor.rows(), cursor, (x_board, y_board))?;
+            // Render stage details and help.
+            let cursor_if = |input| {
+                if editor.text_input == Some(input) {
+                    "_"
+                } else {
+                    ""
+                }
+            };
+            let details = [
+                format!(
+                    "pack name  : {}{}",
+                    editor.pack.name,
+                    cursor_if(PuzzleEditorInput::PackName)
+                ),
+                format!(
+                    "stage name : {}{}",
+                    puzzle.name,
+                    cursor_if(PuzzleEditorInput::StageName)
+                ),
+                format!("pieces     : {}", puzzle.pieces),
+                format!("attempts   : {}", puzzle.attempts),
+                format!("brush      : {}", editor.brush),
+                match puzzle.goal {
+                    PuzzleGoal::PerfectClear => "goal       : perfect clear".to_string(),
+                    PuzzleGoal::Lines(lines) => format!("goal       : clear {lines} line(s)"),
+                },
+            ];
+            let help = if editor.text_input.is_some() {
+                vec!["Type the name, then press [Enter]."]
+            } else {
+                vec![
+                    "[←↑↓→] move cursor",
+                    "[Space] paint/erase with brush",
+                    "[b] cycle brush (X: grey)",
+                    "[I/O/T/S/Z/L/J] add piece  [Bksp] undo",
+                    "[n] stage name  [p] pack name",
+                    "[a] attempts  [g] goal",
+                    "[c] clear board",
+                    "[PgUp/PgDn] stage  [+] new stage",
+                    "[Del] delete stage  [Enter] test-play",
+                    "[Ctrl+S] save  [Ctrl+O] open next pack",
+                    "[Esc] back",
+                ]
+            };
+            for (i, line) in details
+                .into_iter()
+                .chain(help.into_iter().map(String::from))
+                .enumerate()
+            {
+                self.term
+                    .queue(MoveTo(x_main + 30, y_board + 1 + u16::try_from(i).unwrap()))?
+                    .queue(Print(line))?;
+            }
+            self.term
+                .queue(MoveTo(x_main + 30, y_board + 19))?
+                .queue(PrintStyledContent(editor.message.clone().italic()))?;
+            self.term.flush()?;
+            // Wait for new input.
+            let Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: Press | Repeat,
+                ..
+            }) = self.read_event()?
+            else {
+                continue;
+            };
+            let editor = &mut self.puzzle_editor;
+            if code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL) {
+                break Ok(MenuUpdate::Push(Menu::Quit(
+                    "exited with ctrl-c".to_string(),
+                )));
+            }
+            // Typing a name.
+            if let Some(input) = editor.text_input {
+                let name = match input {
+                    PuzzleEditorInput::StageName => &mut editor.pack.puzzles[editor.stage].name,
+                    PuzzleEditorInput::PackName => &mut editor.pack.name,
+                };
+                match code {
+                    KeyCode::Enter | KeyCode::Esc => editor.text_input = None,
+                    KeyCode::Backspace => {
+                        name.pop();
+                    }
+                    KeyCode::Char(ch) if name.chars().count() < 24 => name.push(ch),
+                    _ => {}
+                }
+                continue;
+            }
+            editor.message.clear();
+            match code {
+                KeyCode::Esc => break Ok(MenuUpdate::Pop),
+                KeyCode::Char('s') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    let pack = editor.exported();
+                    // Saving again replaces a pack, but not any other mode of the same name.
+                    let taken_names = self
+                        .modes
+                        .user_modes()
+                        .filter(|mode| mode.puzzle_pack().is_none())
+                        .map(|mode| mode.name().to_string())
+                        .collect::<Vec<_>>();
+                    editor.message = if self.socket_session.is_some() {
+                        "(saving is disabled for remote players)".to_string()
+                    } else if let Err(e) = pack.check(&taken_names) {
+                        format!("cannot save: {e}")
+                    } else {
+                        match utils::save_data_file(&Self::puzzle_packs_dir(), &pack.name, &pack) {
+                            Ok(path) => {
+                                self.modes.register(pack);
+                                format!("saved to {path:?}")
+                            }
+                            Err(e) => format!("could not save: {e}"),
+                        }
+                    };
+                }
+                KeyCode::Char('o') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    // The built-in pack serves as the first template.
+                    let mut builtin = PuzzlePack::builtin();
+                    builtin.name.push_str(" (edited)");
+                    let mut packs = vec![builtin];
+                    packs
+                        .extend(PuzzlePack::load_dir(&Self::puzzle_packs_dir(), &mut Vec::new()).0);
+                    editor.opened_pack = (editor.opened_pack + 1) % packs.len();
+                    let pack = packs.swap_remove(editor.opened_pack);
+                    editor.message = format!("opened {:?}", pack.name);
+                    editor.open(pack);
+                }
+                KeyCode::Enter => {
+                    let mut pack = editor.exported();
+                    pack.name = PuzzleEditor::TEST_PACK_NAME.to_string();
+                    pack.puzzles = vec![pack.puzzles.swap_remove(editor.stage)];
+                    if let Err(e) = pack.check(&[]) {
+                        editor.message = format!("cannot play: {e}");
+                        continue;
+                    }
+                    editor.test_playing = true;
+                    let mut game = game_mods::puzzle_mode::new_pack_game(&pack);
+                    game.config_mut().clone_from(&self.game_config);
+                    let recording = FumenRecording::default();
+                    unsafe { game.add_modifier(recording.modifier()) };
+                    self.fumen_recording = Some(recording);
+                    break Ok(MenuUpdate::Push(Menu::Game {
+                        game: Box::new(game),
+                        clock: GameClock::default(),
+                        running_game_stats: RunningGameStats::default(),
+                        game_renderer: Default::default(),
+                        telemetry: None,
+                        tas: None,
+                    }));
+                }
+                KeyCode::Up => editor.cursor.1 = editor.cursor.1.saturating_sub(1),
+                KeyCode::Down => {
+                    editor.cursor.1 = (editor.cursor.1 + 1).min(PuzzleEditor::ROWS - 1)
+                }
+                KeyCode::Left => editor.cursor.0 = editor.cursor.0.saturating_sub(1),
+                KeyCode::Right => editor.cursor.0 = (editor.cursor.0 + 1).min(Game::WIDTH - 1),
+                KeyCode::Char(' ') => editor.paint_cell(),
+                KeyCode::Char('b') => {
+                    let brushes = ['X', 'I', 'O', 'T', 'S', 'Z', 'L', 'J'];
+                    let i = brushes.iter().position(|b| *b == editor.brush).unwrap_or(0);
+                    editor.brush = brushes[(i + 1) % brushes.len()];
+                }
+                KeyCode::Backspace => {
+                    editor.puzzle_mut().pieces.pop();
+                }
+                KeyCode::Char('n') => editor.text_input = Some(PuzzleEditorInput::StageName),
+                KeyCode::Char('p') => editor.text_input = Some(PuzzleEditorInput::PackName),
+                KeyCode::Char('a') => {
+                    let puzzle = editor.puzzle_mut();
+                    puzzle.attempts = puzzle.attempts % 9 + 1;
+                }
+                KeyCode::Char('g') => {
+                    let puzzle = editor.puzzle_mut();
+                    puzzle.goal = match puzzle.goal {
+                        PuzzleGoal::PerfectClear => PuzzleGoal::Lines(1),
+                        PuzzleGoal::Lines(lines) if lines < 4 => PuzzleGoal::Lines(lines + 1),
+                        PuzzleGoal::Lines(_) => PuzzleGoal::PerfectClear,
+                    };
+                }
+                KeyCode::Char('c') => {
+                    editor.puzzle_mut().board = vec![".".repeat(Game::WIDTH); PuzzleEditor::ROWS];
+                }
+                KeyCode::Char(ch) if parse_tetromino(ch).is_some() => {
+                    editor.puzzle_mut().pieces.push(ch.to_ascii_uppercase());
+                }
+                KeyCode::PageUp => editor.stage = editor.stage.saturating_sub(1),
+                KeyCode::PageDown => {
+                    editor.stage = (editor.stage + 1).min(editor.pack.puzzles.len() - 1);
+                }
+                KeyCode::Char('+') => {
+                    editor.stage += 1;
+                    let puzzle = PuzzleEditor::empty_puzzle(editor.pack.puzzles.len() + 1);
+                    editor.pack.puzzles.insert(editor.stage, puzzle);
+                }
+                KeyCode::Delete => {
+                    if editor.pack.puzzles.len() > 1 {
+                        editor.pack.puzzles.remove(editor.stage);
+                        editor.stage = editor.stage.min(editor.pack.puzzles.len() - 1);
+                    } else {
+                        editor.pack.puzzles[0] = PuzzleEditor::empty_puzzle(1);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Draws the rows of a board being edited, with the cell under the cursor highlighted.
+    fn draw_editor_board(
+        &mut self,
+        rows: &[String],
+        cursor: Option<(usize, usize)>,
+        (x_board, y_board): (u16, u16),
+    ) -> io::Result<()> {
+        let tile_color = get_color(self.settings.graphics_color_board);
+        for (y, row) in rows.iter().enumerate() {
+            let y_row = y_board + u16::try_from(y).unwrap();
+            self.term.queue(MoveTo(x_board, y_row))?.queue(Print("│"))?;
+            for (x, ch) in row.chars().enumerate() {
+                let tile = parse_tetromino(ch)
+                    .map_or(Game::GARBAGE_TILE, |tetromino| tetromino.tiletypeid());
+                let cell = if is_empty_cell(ch) {
+                    " .".to_string().stylize()
+                } else if let Some(color) = tile_color(tile) {
+                    "██".to_string().with(color)
+                } else if parse_tetromino(ch).is_some() {
+                    // Without colors, tell pieces apart by their letter.
+                    ch.to_string().repeat(2).stylize()
+                } else {
+                    "██".to_string().stylize()
+                };
+                if cursor == Some((x, y)) {
+                    self.term.queue(PrintStyledContent(cell.reverse()))?;
+                } else {
+                    self.term.queue(PrintStyledContent(cell))?;
+                }
+            }
+            self.term.queue(Print("│"))?;
+        }
+        self.term
+            .queue(MoveTo(
+                x_board,
+                y_board + u16::try_from(rows.len()).unwrap(),
+            ))?
+            .queue(Print(format!("└{}┘", "─".repeat(2 * Game::WIDTH))))?;
+        Ok(())
+    }
+
+    fn board_editor_menu(&mut self) -> io::Result<MenuUpdate> {
+        loop {
+            let w_main = Self::W_MAIN.into();
+            let (x_main, y_main) = self.fetch_main_xy();
+            let editor = &self.board_editor.clone();
+            self.term
+                .queue(Clear(ClearType::All))?
+                .queue(MoveTo(x_main, y_main))?
+                .queue(Print(format!(
+                    "{:^w_main$}",
+                    format!("[ Board Editor: {} ]", editor.layout.name)
+                )))?;
+            // Render board.
+            let (x_board, y_board) = (x_main + 4, y_main + 2);
+            let cursor = editor.text_input.is_none().then_some(editor.cursor);
+            self.draw_editor_board(&editor.layout.board, cursor, (x_board, y_board))?;
+            // Render layout details and help.
+            let cursor_if = |input| {
+                if editor.text_input == Some(input) {
+                    "_"
+                } else {
+                    ""
+                }
+            };
+            let details = [
+                format!(
+                    "name  : {}{}",
+                    editor.layout.name,
+                    cursor_if(BoardEditorInput::Name)
+                ),
+                format!(
+                    "hold  : {}",
+                    editor.layout.hold.map_or("-".to_string(), String::from)
+                ),
+                format!(
+                    "queue : {}{}",
+                    editor.layout.queue,
+                    cursor_if(BoardEditorInput::Queue)
+                ),
+                format!("brush : {}", editor.brush),
+                if editor.text_input == Some(BoardEditorInput::Fumen) {
+                    let chars = editor.fumen_input.chars().collect::<Vec<_>>();
+                    let tail = chars[chars.len().saturating_sub(32)..]
+                        .iter()
+                        .collect::<String>();
+                    format!("fumen : {tail}_")
+                } else {
+                    "".to_string()
+                },
+            ];
+            let help = if editor.text_input.is_some() {
+                vec!["Type, then press [Enter]."]
+            } else {
+                vec![
+                    "[←↑↓→] move cursor",
+                    "[Space] paint/erase with brush",
+                    "[I/O/T/S/Z/L/J/X] choose brush (X: grey)",
+                    "[f] fill row, leaving a gap at the cursor",
+                    "[h] cycle hold piece  [q] edit queue",
+                    "[n] rename  [c] clear board",
+                    "[u] import fumen  [e] export fumen",
+                    "[Ctrl+S] save  [Ctrl+O] open next saved",
+                    "[Esc] back",
+                ]
+            };
+            for (i, line) in details
+                .into_iter()
+                .chain(help.into_iter().map(String::from))
+                .enumerate()
+            {
+                self.term
+                    .queue(MoveTo(x_main + 30, y_board + 1 + u16::try_from(i).unwrap()))?
+                    .queue(Print(line))?;
+            }
+            self.term
+                .queue(MoveTo(x_main + 30, y_board + 16))?
+                .queue(PrintStyledContent(editor.message.clone().italic()))?;
+            self.term.flush()?;
+            // Wait for new input.
+            let Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: Press | Repeat,
+                ..
+            }) = self.read_event()?
+            else {
+                continue;
+            };
+            let editor = &mut self.board_editor;
+            if code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL) {
+                break Ok(MenuUpdate::Push(Menu::Quit(
+                    "exited with ctrl-c".to_string(),
+                )));
+            }
+            // Typing a name or pieces.
+            if let Some(input) = editor.text_input {
+                let text = match input {
+                    BoardEditorInput::Name => &mut editor.layout.name,
+                    BoardEditorInput::Queue => &mut editor.layout.queue,
+                    BoardEditorInput::Fumen => &mut editor.fumen_input,
+                };
+                match code {
+                    KeyCode::Enter if input == BoardEditorInput::Fumen => {
+                        editor.text_input = None;
+                        match fumen::decode(&editor.fumen_input) {
+                            Ok(pages) => {
+                                let name = editor.layout.name.clone();
+                                editor.open(BoardLayout::from_fumen(name, &pages[0]));
+                                editor.message = if pages.len() > 1 {
+                                    format!("imported first of {} pages", pages.len())
+                                } else {
+                                    "imported fumen".to_string()
+                                };
+                            }
+                            Err(e) => editor.message = format!("could not import: {e}"),
+                        }
+                    }
+                    KeyCode::Enter | KeyCode::Esc => editor.text_input = None,
+                    KeyCode::Backspace => {
+                        text.pop();
+                    }
+                    KeyCode::Char(ch) if input == BoardEditorInput::Fumen => text.push(ch),
+                    KeyCode::Char(ch) if text.chars().count() < 24 => {
+                        if input == BoardEditorInput::Name {
+                            text.push(ch);
+                        } else if parse_tetromino(ch).is_some() {
+                            text.push(ch.to_ascii_uppercase());
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            editor.message.clear();
+            match code {
+                KeyCode::Esc => break Ok(MenuUpdate::Pop),
+                KeyCode::Char('s') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    let layout = editor.exported();
+                    editor.message = if self.socket_session.is_some() {
+                        "(saving is disabled for remote players)".to_string()
+                    } else {
+                        match utils::save_data_file(
+                            &Self::board_layouts_dir(),
+                            &layout.name,
+                            &layout,
+                        ) {
+                            Ok(path) => {
+                                let message = format!("saved to {path:?}");
+                                if let Some(existing) = self
+                                    .board_layouts
+                                    .iter_mut()
+                                    .find(|existing| existing.name == layout.name)
+                                {
+                                    *existing = layout;
+                                } else {
+                                    self.board_layouts.push(layout);
+                                }
+                                message
+                            }
+                            Err(e) => format!("could not save: {e}"),
+                        }
+                    };
+                }
+                KeyCode::Char('o') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    if self.board_layouts.is_empty() {
+                        editor.message = "(no saved layouts yet)".to_string();
+                    } else {
+                        editor.opened_layout =
+                            (editor.opened_layout + 1) % self.board_layouts.len();
+                        let layout = self.board_layouts[editor.opened_layout].clone();
+                        editor.message = format!("opened {:?}", layout.name);
+                        editor.open(layout);
+                    }
+                }
+                KeyCode::Up => editor.cursor.1 = editor.cursor.1.saturating_sub(1),
+                KeyCode::Down => {
+                    editor.cursor.1 = (editor.cursor.1 + 1).min(BoardEditor::ROWS - 1);
+                }
+                KeyCode::Left => editor.cursor.0 = editor.cursor.0.saturating_sub(1),
+                KeyCode::Right => editor.cursor.0 = (editor.cursor.0 + 1).min(Game::WIDTH - 1),
+                KeyCode::Char(' ') => {
+                    let (x, y) = editor.cursor;
+                    let cell = editor.layout.board[y].chars().nth(x).unwrap_or('.');
+                    let new_cell = if cell == editor.brush {
+                        '.'
+                    } else {
+                        editor.brush
+                    };
+                    set_board_cell(&mut editor.layout.board, editor.cursor, new_cell);
+                }
+                KeyCode::Char('f') => {
+                    let (gap, y) = editor.cursor;
+                    editor.layout.board[y] = (0..Game::WIDTH)
+                        .map(|x| if x == gap { '.' } else { editor.brush })
+                        .collect();
+                }
+                KeyCode::Char('h') => {
+                    let pieces = ['I', 'O', 'T', 'S', 'Z', 'L', 'J'];
+                    editor.layout.hold = match editor.layout.hold {
+                        None => Some(pieces[0]),
+                        Some(piece) => pieces
+                            .iter()
+                            .position(|p| *p == piece)
+                            .and_then(|i| pieces.get(i + 1))
+                            .copied(),
+                    };
+                }
+                KeyCode::Char('q') => editor.text_input = Some(BoardEditorInput::Queue),
+                KeyCode::Char('n') => editor.text_input = Some(BoardEditorInput::Name),
+                KeyCode::Char('u') => {
+                    editor.fumen_input.clear();
+                    editor.text_input = Some(BoardEditorInput::Fumen);
+                }
+                KeyCode::Char('e') => {
+                    let exports = vec![(
+                        editor.layout.name.clone(),
+                        vec![editor.exported().to_fumen()],
+                    )];
+                    break Ok(MenuUpdate::Push(Menu::ExportFumen(exports)));
+                }
+                KeyCode::Char('c') => {
+                    editor.layout.board = vec![".".repeat(Game::WIDTH); BoardEditor::ROWS];
+                }
+                KeyCode::Char(ch) if parse_tetromino(ch).is_some() || ch == 'x' => {
+                    editor.brush = ch.to_ascii_uppercase();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn export_fumen_menu(
+        &mut self,
+        exports: &[(String, Vec<FumenPage>)],
+    ) -> io::Result<MenuUpdate> {
+        let mut selected = 0usize;
+        // The last exported fumen, and where it was saved to.
+        let mut exported: Option<(String, String)> = None;
+        loop {
+            let w_main = Self::W_MAIN.into();
+            let (x_main, y_main) = self.fetch_main_xy();
+            let y_selection = Self::H_MAIN / 5;
+            self.term
+                .queue(Clear(ClearType::All))?
+                .queue(MoveTo(x_main, y_main + y_selection))?
+                .queue(Print(format!("{:^w_main$}", "[ Export Fumen ]")))?
+                .queue(MoveTo(x_main, y_main + y_selection + 2))?
                 .queue(Print(format!("{:^w_main$}", "──────────────────────────")))?;
-            let names = selection
-                .iter()
-                .map(|menu| menu.to_string())
-                .collect::<Vec<_>>();
-            for (i, name) in names.into_iter().enumerate() {
+            for (i, (label, _)) in exports.iter().enumerate() {
                 self.term
                     .queue(MoveTo(
                         x_main,
-                        y_main + y_selection + 14 + u16::try_from(i).unwrap(),
+                        y_main + y_selection + 4 + u16::try_from(i).unwrap(),
                     ))?
                     .queue(Print(format!(
                         "{:^w_main$}",
                         if i == selected {
-                            format!(">>> {name} <<<")
+                            format!(">>> {label} <<<")
                         } else {
-                            name
+                            label.clone()
                         }
                     )))?;
             }
+            let mut y_text = y_main + y_selection + 4 + u16::try_from(exports.len()).unwrap() + 1;
+            if let Some((fumen, message)) = &exported {
+                self.term
+                    .queue(MoveTo(x_main, y_text))?
+                    .queue(PrintStyledContent(format!("{message:^w_main$}").italic()))?;
+                // Show as much of the fumen as fits.
+                let chars = fumen.chars().collect::<Vec<_>>();
+                let lines = chars.chunks(w_main - 4).collect::<Vec<_>>();
+                for (i, line) in lines.iter().take(6).enumerate() {
+                    let mut line = line.iter().collect::<String>();
+                    if i == 5 && lines.len() > 6 {
+                        line.replace_range(line.len() - 3.., "...");
+                    }
+                    y_text += 1;
+                    self.term
+                        .queue(MoveTo(x_main + 2, y_text))?
+                        .queue(Print(line))?;
+                }
+            }
+            self.term
+                .queue(MoveTo(x_main, y_text + 2))?
+                .queue(PrintStyledContent(
+                    format!("{:^w_main$}", "Use [↑] [↓] [Esc] [Enter].",).italic(),
+                ))?;
             self.term.flush()?;
-            // Wait for new input.
-            match event::read()? {
+            match self.read_event()? {
                 // Quit menu.
                 Event::Key(KeyEvent {
                     code: KeyCode::Char('c'),
@@ -1281,16 +3449,28 @@ impl<T: Write> TerminalApp<T> {
                     kind: Press,
                     ..
                 }) => break Ok(MenuUpdate::Pop),
-                // Select next menu.
+                // Export selected pages.
                 Event::Key(KeyEvent {
                     code: KeyCode::Enter,
                     kind: Press,
                     ..
                 }) => {
-                    if !selection.is_empty() {
-                        let menu = selection.into_iter().nth(selected).unwrap();
-                        break Ok(MenuUpdate::Push(menu));
-                    }
+                    let fumen = fumen::encode(&exports[selected].1);
+                    let message = if self.socket_session.is_some() {
+                        "(saving is disabled for remote players)".to_string()
+                    } else {
+                        let name = format!("fumen {}", chrono::Utc::now().format("%Y%m%d %H%M%S"));
+                        match utils::save_text_file(
+                            &Self::fumen_exports_dir(),
+                            &name,
+                            "txt",
+                            &fumen,
+                        ) {
+                            Ok(path) => format!("saved to {path:?}"),
+                            Err(e) => format!("could not save: {e}"),
+                        }
+                    };
+                    exported = Some((fumen, message));
                 }
                 // Move selector up.
                 Event::Key(KeyEvent {
@@ -1298,9 +3478,7 @@ impl<T: Write> TerminalApp<T> {
                     kind: Press | Repeat,
                     ..
                 }) => {
-                    if !selection.is_empty() {
-                        selected += selection.len() - 1;
-                    }
+                    selected += exports.len() - 1;
                 }
                 // Move selector down.
                 Event::Key(KeyEvent {
@@ -1308,62 +3486,21 @@ impl<T: Write> TerminalApp<T> {
                     kind: Press | Repeat,
                     ..
                 }) => {
-                    if !selection.is_empty() {
-                        selected += 1;
-                    }
+                    selected += 1;
                 }
                 // Other event: don't care.
                 _ => {}
             }
-            if !selection.is_empty() {
-                selected = selected.rem_euclid(selection.len());
-            }
+            selected = selected.rem_euclid(exports.len());
         }
     }
 
-    fn game_over_menu(
-        &mut self,
-        finished_game_stats: &FinishedGameStats,
-    ) -> io::Result<MenuUpdate> {
-        let selection = vec![
-            Menu::NewGame,
-            Menu::Settings,
-            Menu::Scores,
-            Menu::Quit("quit after game over".to_string()),
-        ];
-        self.generic_game_ended(selection, false, finished_game_stats)
-    }
-
-    fn game_complete_menu(
-        &mut self,
-        finished_game_stats: &FinishedGameStats,
-    ) -> io::Result<MenuUpdate> {
-        let selection = vec![
-            Menu::NewGame,
-            Menu::Settings,
-            Menu::Scores,
-            Menu::Quit("quit after game complete".to_string()),
-        ];
-        self.generic_game_ended(selection, true, finished_game_stats)
-    }
-
-    fn pause_menu(&mut self) -> io::Result<MenuUpdate> {
-        let selection = vec![
-            Menu::NewGame,
-            Menu::Settings,
-            Menu::Scores,
-            Menu::About,
-            Menu::Quit("quit from pause".to_string()),
-        ];
-        self.generic_placeholder_widget("GAME PAUSED", selection)
-    }
-
     fn settings_menu(&mut self) -> io::Result<MenuUpdate> {
         let selection_len = 7;
         let mut selected = 0usize;
         loop {
             let w_main = Self::W_MAIN.into();
-            let (x_main, y_main) = Self::fetch_main_xy();
+            let (x_main, y_main) = self.fetch_main_xy();
             let y_selection = Self::H_MAIN / 5;
             self.term
                 .queue(Clear(ClearType::All))?
@@ -1416,7 +3553,7 @@ impl<T: Write> TerminalApp<T> {
                 ))?;
             self.term.flush()?;
             // Wait for new input.
-            match event::read()? {
+            match self.read_event()? {
                 // Quit menu.
                 Event::Key(KeyEvent {
                     code: KeyCode::Char('c'),
@@ -1543,17 +3680,24 @@ impl<T: Write> TerminalApp<T> {
             Button::DropHard,
             Button::DropSonic,
             Button::Hold,
+            Button::ActivateZone,
         ];
         let selection_len = button_selection.len() + 1;
         let mut selected = 0usize;
+        // Which set of keybinds is being edited: single player, versus player 1 or 2.
+        let profile_names = ["Single Player", "Versus: Player 1", "Versus: Player 2"];
+        let mut profile = 0usize;
         loop {
             let w_main = Self::W_MAIN.into();
-            let (x_main, y_main) = Self::fetch_main_xy();
+            let (x_main, y_main) = self.fetch_main_xy();
             let y_selection = Self::H_MAIN / 5;
             self.term
                 .queue(Clear(ClearType::All))?
                 .queue(MoveTo(x_main, y_main + y_selection))?
-                .queue(Print(format!("{:^w_main$}", "| Change Controls |")))?
+                .queue(Print(format!(
+                    "{:^w_main$}",
+                    format!("| Change Controls: < {} > |", profile_names[profile])
+                )))?
                 .queue(MoveTo(x_main, y_main + y_selection + 2))?
                 .queue(Print(format!("{:^w_main$}", "──────────────────────────")))?;
             let button_names = button_selection
@@ -1561,7 +3705,7 @@ impl<T: Write> TerminalApp<T> {
                 .map(|&button| {
                     format!(
                         "{button:?} : {}",
-                        fmt_keybinds(button, &self.settings.keybinds)
+                        fmt_keybinds(button, self.settings.keybinds_profile(profile))
                     )
                 })
                 .collect::<Vec<_>>();
@@ -1606,10 +3750,17 @@ impl<T: Write> TerminalApp<T> {
                 ))?
                 .queue(PrintStyledContent(
                     format!("{:^w_main$}", "Press [Delete] to remove keybinds.",).italic(),
+                ))?
+                .queue(MoveTo(
+                    x_main,
+                    y_main + y_selection + 4 + u16::try_from(selection_len).unwrap() + 5,
+                ))?
+                .queue(PrintStyledContent(
+                    format!("{:^w_main$}", "Press [←] [→] to switch between players.",).italic(),
                 ))?;
             self.term.flush()?;
             // Wait for new input.
-            match event::read()? {
+            match self.read_event()? {
                 // Quit menu.
                 Event::Key(KeyEvent {
                     code: KeyCode::Char('c'),
@@ -1633,7 +3784,11 @@ impl<T: Write> TerminalApp<T> {
                     ..
                 }) => {
                     if selected == selection_len - 1 {
-                        self.settings.keybinds = CrosstermHandler::default_keybinds();
+                        *self.settings.keybinds_profile_mut(profile) = if profile == 0 {
+                            CrosstermHandler::default_keybinds()
+                        } else {
+                            CrosstermHandler::default_versus_keybinds()[profile - 1].clone()
+                        };
                     } else {
                         let current_button = button_selection[selected];
                         self.term
@@ -1657,9 +3812,11 @@ impl<T: Write> TerminalApp<T> {
                         loop {
                             if let Event::Key(KeyEvent {
                                 code, kind: Press, ..
-                            }) = event::read()?
+                            }) = self.read_event()?
                             {
-                                self.settings.keybinds.insert(code, current_button);
+                                self.settings
+                                    .keybinds_profile_mut(profile)
+                                    .insert(code, current_button);
                                 break;
                             }
                         }
@@ -1672,11 +3829,11 @@ impl<T: Write> TerminalApp<T> {
                     ..
                 }) => {
                     if selected == selection_len - 1 {
-                        self.settings.keybinds.clear();
+                        self.settings.keybinds_profile_mut(profile).clear();
                     } else {
                         let current_button = button_selection[selected];
                         self.settings
-                            .keybinds
+                            .keybinds_profile_mut(profile)
                             .retain(|_code, button| *button != current_button);
                     }
                 }
@@ -1696,19 +3853,41 @@ impl<T: Write> TerminalApp<T> {
                 }) => {
                     selected += 1;
                 }
+                // Switch keybind profile.
+                Event::Key(KeyEvent {
+                    code: KeyCode::Left,
+                    kind: Press | Repeat,
+                    ..
+                }) => {
+                    profile += profile_names.len() - 1;
+                }
+                Event::Key(KeyEvent {
+                    code: KeyCode::Right,
+                    kind: Press | Repeat,
+                    ..
+                }) => {
+                    profile += 1;
+                }
                 // Other event: don't care.
                 _ => {}
             }
             selected = selected.rem_euclid(selection_len);
+            profile = profile.rem_euclid(profile_names.len());
         }
     }
 
     fn configure_game_menu(&mut self) -> io::Result<MenuUpdate> {
-        let selection_len = 12;
+        let selection_len = 22;
+        // All settings but 'Restore Defaults' do not fit on screen at once.
+        let max_visible = 12;
         let mut selected = 0usize;
+        let mut scroll = 0usize;
+        let mut ruleset = 0usize;
+        // The ruleset last applied, with the configuration from before to return to.
+        let mut applied_ruleset: Option<(Ruleset, GameConfig)> = None;
         loop {
             let w_main = Self::W_MAIN.into();
-            let (x_main, y_main) = Self::fetch_main_xy();
+            let (x_main, y_main) = self.fetch_main_xy();
             let y_selection = Self::H_MAIN / 5;
             self.term
                 .queue(Clear(ClearType::All))?
@@ -1729,6 +3908,7 @@ impl<T: Write> TerminalApp<T> {
                         TetrominoSource::Recency { .. } => "Recency-based".to_string(),
                         TetrominoSource::BalanceRelative { .. } =>
                             "Balance Relative Counts".to_string(),
+                        TetrominoSource::Classic { .. } => "Classic (NES)".to_string(),
                         TetrominoSource::Cycle { pattern, index: _ } =>
                             format!("Cycle Pattern {pattern:?}"),
                     }
@@ -1747,16 +3927,65 @@ impl<T: Write> TerminalApp<T> {
                 format!("ground time max : {:?}", self.game_config.ground_time_max),
                 format!("line clear delay : {:?}", self.game_config.line_clear_delay),
                 format!("appearance delay : {:?}", self.game_config.appearance_delay),
+                format!(
+                    "appearance delay per height : {:?}",
+                    self.game_config.appearance_delay_per_height
+                ),
+                format!("gravity : {:?}", self.game_config.gravity),
+                format!("scoring : {:?}", self.game_config.scoring),
+                format!("spin detection : {:?}", self.game_config.spin_detection),
+                format!(
+                    "lock resets max : {}",
+                    match self.game_config.lock_resets_max {
+                        Some(resets) => resets.to_string(),
+                        None => "unlimited".to_string(),
+                    }
+                ),
+                format!("no hold : {}", self.game_config.no_hold),
+                format!("no hard drop : {}", self.game_config.no_hard_drop),
+                format!("no ghost piece : {}", self.game_config.no_ghost_piece),
+                format!(
+                    "frame-stepped : {}",
+                    match self.game_config.tick_rate {
+                        Some(tick_rate) => format!("{tick_rate} fps"),
+                        None => "off".to_string(),
+                    }
+                ),
                 format!(
                     "**no soft drop lock : {}",
                     self.game_config.no_soft_drop_lock
                 ),
+                match &applied_ruleset {
+                    Some((applied, _)) => format!(
+                        "apply ruleset : {:?} ({applied:?} applied, [Backspace] to undo)",
+                        Ruleset::ALL[ruleset]
+                    ),
+                    None => format!("apply ruleset : {:?}", Ruleset::ALL[ruleset]),
+                },
             ];
-            for (i, label) in labels.into_iter().enumerate() {
+            let n_labels = labels.len();
+            scroll = scroll.clamp(
+                selected.min(n_labels - 1).saturating_sub(max_visible - 1),
+                selected.min(n_labels - 1),
+            );
+            if scroll > 0 {
+                self.term
+                    .queue(MoveTo(x_main, y_main + y_selection + 3))?
+                    .queue(Print(format!(
+                        "{:^w_main$}",
+                        format!("...  (+{scroll} more)")
+                    )))?;
+            }
+            for (i, label) in labels
+                .into_iter()
+                .enumerate()
+                .skip(scroll)
+                .take(max_visible)
+            {
                 self.term
                     .queue(MoveTo(
                         x_main,
-                        y_main + y_selection + 4 + u16::try_from(i).unwrap(),
+                        y_main + y_selection + 4 + u16::try_from(i - scroll).unwrap(),
                     ))?
                     .queue(Print(format!(
                         "{:^w_main$}",
@@ -1767,10 +3996,22 @@ impl<T: Write> TerminalApp<T> {
                         }
                     )))?;
             }
+            let labels_left = n_labels.saturating_sub(scroll + max_visible);
+            if labels_left > 0 {
+                self.term
+                    .queue(MoveTo(
+                        x_main,
+                        y_main + y_selection + 4 + u16::try_from(max_visible).unwrap(),
+                    ))?
+                    .queue(Print(format!(
+                        "{:^w_main$}",
+                        format!("...  (+{labels_left} more)")
+                    )))?;
+            }
             self.term
                 .queue(MoveTo(
                     x_main,
-                    y_main + y_selection + 4 + u16::try_from(selection_len - 1).unwrap() + 1,
+                    y_main + y_selection + 4 + u16::try_from(max_visible).unwrap() + 1,
                 ))?
                 .queue(Print(format!(
                     "{:^w_main$}",
@@ -1783,7 +4024,7 @@ impl<T: Write> TerminalApp<T> {
             self.term
                 .queue(MoveTo(
                     x_main,
-                    y_main + y_selection + 4 + u16::try_from(selection_len - 1).unwrap() + 4,
+                    y_main + y_selection + 4 + u16::try_from(max_visible).unwrap() + 3,
                 ))?
                 .queue(Print(format!(
                     "{:^w_main$}",
@@ -1796,7 +4037,7 @@ impl<T: Write> TerminalApp<T> {
             self.term
                 .queue(MoveTo(
                     x_main,
-                    y_main + y_selection + 4 + u16::try_from(selection_len - 1).unwrap() + 5,
+                    y_main + y_selection + 4 + u16::try_from(max_visible).unwrap() + 4,
                 ))?
                 .queue(Print(format!(
                     "{:^w_main$}",
@@ -1808,7 +4049,7 @@ impl<T: Write> TerminalApp<T> {
                 )))?;
             self.term.flush()?;
             // Wait for new input.
-            match event::read()? {
+            match self.read_event()? {
                 // Quit menu.
                 Event::Key(KeyEvent {
                     code: KeyCode::Char('c'),
@@ -1831,9 +4072,32 @@ impl<T: Write> TerminalApp<T> {
                     kind: Press,
                     ..
                 }) => {
-                    if selected == selection_len - 1 {
+                    if selected == selection_len - 2 {
+                        let previous_config = std::mem::replace(
+                            &mut self.game_config,
+                            Ruleset::ALL[ruleset].game_config(),
+                        );
+                        self.game_config.no_soft_drop_lock = !self.kitty_enabled;
+                        // Undoing several rulesets in a row returns to the custom configuration.
+                        let previous_config = match applied_ruleset.take() {
+                            Some((_, custom_config)) => custom_config,
+                            None => previous_config,
+                        };
+                        applied_ruleset = Some((Ruleset::ALL[ruleset], previous_config));
+                    } else if selected == selection_len - 1 {
                         self.game_config = GameConfig::default();
                         self.game_config.no_soft_drop_lock = !self.kitty_enabled;
+                        applied_ruleset = None;
+                    }
+                }
+                // Return to the configuration from before the ruleset was applied.
+                Event::Key(KeyEvent {
+                    code: KeyCode::Backspace,
+                    kind: Press,
+                    ..
+                }) if selected == selection_len - 2 => {
+                    if let Some((_, previous_config)) = applied_ruleset.take() {
+                        self.game_config = previous_config;
                     }
                 }
                 // Move selector up.
@@ -1872,7 +4136,8 @@ impl<T: Write> TerminalApp<T> {
                             TetrominoSource::Uniform => TetrominoSource::bag(),
                             TetrominoSource::Stock { .. } => TetrominoSource::recency(),
                             TetrominoSource::Recency { .. } => TetrominoSource::balance_relative(),
-                            TetrominoSource::BalanceRelative { .. } => TetrominoSource::uniform(),
+                            TetrominoSource::BalanceRelative { .. } => TetrominoSource::classic(),
+                            TetrominoSource::Classic { .. } => TetrominoSource::uniform(),
                             TetrominoSource::Cycle { .. } => TetrominoSource::uniform(),
                         };
                     }
@@ -1901,8 +4166,58 @@ impl<T: Write> TerminalApp<T> {
                         self.game_config.appearance_delay += Duration::from_millis(10);
                     }
                     10 => {
+                        self.game_config.appearance_delay_per_height += Duration::from_millis(10);
+                    }
+                    11 => {
+                        self.game_config.gravity = match self.game_config.gravity {
+                            Gravity::Standard => Gravity::Classic,
+                            Gravity::Classic => Gravity::Standard,
+                        };
+                    }
+                    12 => {
+                        self.game_config.scoring = match self.game_config.scoring {
+                            Scoring::Standard => Scoring::Classic,
+                            Scoring::Classic => Scoring::Guideline,
+                            Scoring::Guideline => Scoring::Standard,
+                        };
+                    }
+                    13 => {
+                        self.game_config.spin_detection = match self.game_config.spin_detection {
+                            SpinDetection::Immobile => SpinDetection::TCorners,
+                            SpinDetection::TCorners => SpinDetection::Immobile,
+                        };
+                    }
+                    14 => {
+                        self.game_config.lock_resets_max = Some(
+                            self.game_config
+                                .lock_resets_max
+                                .map_or(0, |resets| resets + 1),
+                        );
+                    }
+                    15 => {
+                        self.game_config.no_hold = !self.game_config.no_hold;
+                    }
+                    16 => {
+                        self.game_config.no_hard_drop = !self.game_config.no_hard_drop;
+                    }
+                    17 => {
+                        self.game_config.no_ghost_piece = !self.game_config.no_ghost_piece;
+                    }
+                    18 => {
+                        if let Some(tick_rate) = Self::TICK_RATES.into_iter().find(|tick_rate| {
+                            self.game_config
+                                .tick_rate
+                                .is_none_or(|current| *tick_rate > current.get())
+                        }) {
+                            self.game_config.tick_rate = NonZeroU32::new(tick_rate);
+                        }
+                    }
+                    19 => {
                         self.game_config.no_soft_drop_lock = !self.game_config.no_soft_drop_lock;
                     }
+                    20 => {
+                        ruleset += 1;
+                    }
                     _ => {}
                 },
                 Event::Key(KeyEvent {
@@ -1922,10 +4237,11 @@ impl<T: Write> TerminalApp<T> {
                             .game_config
                             .tetromino_generator
                         {
-                            TetrominoSource::Uniform => TetrominoSource::balance_relative(),
+                            TetrominoSource::Uniform => TetrominoSource::classic(),
                             TetrominoSource::Stock { .. } => TetrominoSource::uniform(),
                             TetrominoSource::Recency { .. } => TetrominoSource::bag(),
                             TetrominoSource::BalanceRelative { .. } => TetrominoSource::recency(),
+                            TetrominoSource::Classic { .. } => TetrominoSource::balance_relative(),
                             TetrominoSource::Cycle { .. } => TetrominoSource::uniform(),
                         };
                     }
@@ -1977,14 +4293,70 @@ impl<T: Write> TerminalApp<T> {
                             .saturating_sub(Duration::from_millis(10));
                     }
                     10 => {
+                        self.game_config.appearance_delay_per_height = self
+                            .game_config
+                            .appearance_delay_per_height
+                            .saturating_sub(Duration::from_millis(10));
+                    }
+                    11 => {
+                        self.game_config.gravity = match self.game_config.gravity {
+                            Gravity::Standard => Gravity::Classic,
+                            Gravity::Classic => Gravity::Standard,
+                        };
+                    }
+                    12 => {
+                        self.game_config.scoring = match self.game_config.scoring {
+                            Scoring::Standard => Scoring::Guideline,
+                            Scoring::Classic => Scoring::Standard,
+                            Scoring::Guideline => Scoring::Classic,
+                        };
+                    }
+                    13 => {
+                        self.game_config.spin_detection = match self.game_config.spin_detection {
+                            SpinDetection::Immobile => SpinDetection::TCorners,
+                            SpinDetection::TCorners => SpinDetection::Immobile,
+                        };
+                    }
+                    14 => {
+                        // Below no resets at all lie unlimited resets.
+                        self.game_config.lock_resets_max = self
+                            .game_config
+                            .lock_resets_max
+                            .and_then(|resets| resets.checked_sub(1));
+                    }
+                    15 => {
+                        self.game_config.no_hold = !self.game_config.no_hold;
+                    }
+                    16 => {
+                        self.game_config.no_hard_drop = !self.game_config.no_hard_drop;
+                    }
+                    17 => {
+                        self.game_config.no_ghost_piece = !self.game_config.no_ghost_piece;
+                    }
+                    18 => {
+                        // Below the lowest frame rate, frame-stepped play is turned off.
+                        self.game_config.tick_rate =
+                            self.game_config.tick_rate.and_then(|current| {
+                                Self::TICK_RATES
+                                    .into_iter()
+                                    .rev()
+                                    .find(|tick_rate| *tick_rate < current.get())
+                                    .and_then(NonZeroU32::new)
+                            });
+                    }
+                    19 => {
                         self.game_config.no_soft_drop_lock = !self.game_config.no_soft_drop_lock;
                     }
+                    20 => {
+                        ruleset += Ruleset::ALL.len() - 1;
+                    }
                     _ => {}
                 },
                 // Other event: don't care.
                 _ => {}
             }
             selected = selected.rem_euclid(selection_len);
+            ruleset = ruleset.rem_euclid(Ruleset::ALL.len());
         }
     }
 
@@ -1993,7 +4365,7 @@ impl<T: Write> TerminalApp<T> {
         let mut scroll = 0usize;
         loop {
             let w_main = Self::W_MAIN.into();
-            let (x_main, y_main) = Self::fetch_main_xy();
+            let (x_main, y_main) = self.fetch_main_xy();
             let y_selection = Self::H_MAIN / 5;
             self.term
                 .queue(Clear(ClearType::All))?
@@ -2014,159 +4386,10 @@ impl<T: Write> TerminalApp<T> {
                          gamemode,
                          last_state,
                      }| {
-                        match gamemode.name.as_str() {
-                            "Marathon" => {
-                                format!(
-                                    "{timestamp} ~ Marathon: {} pts{}",
-                                    last_state.score,
-                                    if last_state.end.is_some_and(|end| end.is_ok()) {
-                                        "".to_string()
-                                    } else {
-                                        let Limits {
-                                            level: Some((_, max_lvl)),
-                                            ..
-                                        } = gamemode.limits
-                                        else {
-                                            panic!()
-                                        };
-                                        format!(" ({}/{} lvl)", last_state.level, max_lvl)
-                                    },
-                                )
-                            }
-                            "40-Lines" => {
-                                format!(
-                                    "{timestamp} ~ 40-Lines: {}{}",
-                                    fmt_duration(last_state.time),
-                                    if last_state.end.is_some_and(|end| end.is_ok()) {
-                                        "".to_string()
-                                    } else {
-                                        let Limits {
-                                            lines: Some((_, max_lns)),
-                                            ..
-                                        } = gamemode.limits
-                                        else {
-                                            panic!()
-                                        };
-                                        format!(" ({}/{} lns)", last_state.lines_cleared, max_lns)
-                                    },
-                                )
-                            }
-                            "Time Trial" => {
-                                format!(
-                                    "{timestamp} ~ Time Trial: {} pts{}",
-                                    last_state.score,
-                                    if last_state.end.is_some_and(|end| end.is_ok()) {
-                                        "".to_string()
-                                    } else {
-                                        let Limits {
-                                            time: Some((_, max_dur)),
-                                            ..
-                                        } = gamemode.limits
-                                        else {
-                                            panic!()
-                                        };
-                                        format!(
-                                            " ({} / {})",
-                                            fmt_duration(last_state.time),
-                                            fmt_duration(max_dur)
-                                        )
-                                    },
-                                )
-                            }
-                            "Master" => {
-                                let Limits {
-                                    lines: Some((_, max_lns)),
-                                    ..
-                                } = gamemode.limits
-                                else {
-                                    panic!()
-                                };
-                                format!(
-                                    "{timestamp} ~ Master: {}/{} lns",
-                                    last_state.lines_cleared, max_lns
-                                )
-                            }
-                            "Puzzle" => {
-                                format!(
-                                    "{timestamp} ~ Puzzle: {}{}",
-                                    fmt_duration(last_state.time),
-                                    if last_state.end.is_some_and(|end| end.is_ok()) {
-                                        "".to_string()
-                                    } else {
-                                        let Limits {
-                                            level: Some((_, max_lvl)),
-                                            ..
-                                        } = gamemode.limits
-                                        else {
-                                            panic!()
-                                        };
-                                        format!(" ({}/{} lvl)", last_state.level, max_lvl)
-                                    },
-                                )
-                            }
-                            "Descent" => {
-                                format!(
-                                    "{timestamp} ~ Descent: {} gems, depth {}",
-                                    last_state.score, last_state.lines_cleared,
-                                )
-                            }
-                            "Cheese" => {
-                                format!(
-                                    "{timestamp} ~ Cheese: {} ({}/{} lns)",
-                                    last_state.pieces_played.iter().sum::<u32>(),
-                                    last_state.lines_cleared,
-                                    gamemode
-                                        .limits
-                                        .lines
-                                        .map_or("∞".to_string(), |(_, max_lns)| max_lns
-                                            .to_string())
-                                )
-                            }
-                            "Combo" => {
-                                format!("{timestamp} ~ Combo: {} lns", last_state.lines_cleared)
-                            }
-                            "Combo (Bot)" => {
-                                format!(
-                                    "{timestamp} ~ Combo (Bot): {} lns",
-                                    last_state.lines_cleared
-                                )
-                            }
-                            _ => {
-                                format!(
-                                    "{timestamp} ~ Custom Mode: {} lns, {} pts, {}{}",
-                                    last_state.lines_cleared,
-                                    last_state.score,
-                                    fmt_duration(last_state.time),
-                                    [
-                                        gamemode.limits.time.map(|(_, max_dur)| format!(
-                                            " ({} / {})",
-                                            fmt_duration(last_state.time),
-                                            fmt_duration(max_dur)
-                                        )),
-                                        gamemode.limits.pieces.map(|(_, max_pcs)| format!(
-                                            " ({}/{} pcs)",
-                                            last_state.pieces_played.iter().sum::<u32>(),
-                                            max_pcs
-                                        )),
-                                        gamemode.limits.lines.map(|(_, max_lns)| format!(
-                                            " ({}/{} lns)",
-                                            last_state.lines_cleared, max_lns
-                                        )),
-                                        gamemode.limits.level.map(|(_, max_lvl)| format!(
-                                            " ({}/{} lvl)",
-                                            last_state.level, max_lvl
-                                        )),
-                                        gamemode.limits.score.map(|(_, max_pts)| format!(
-                                            " ({}/{} pts)",
-                                            last_state.score, max_pts
-                                        )),
-                                    ]
-                                    .into_iter()
-                                    .find_map(|limit_text| limit_text)
-                                    .unwrap_or_default()
-                                )
-                            }
-                        }
+                        format!(
+                            "{timestamp} ~ {}",
+                            self.modes.get(&gamemode.name).summary(gamemode, last_state)
+                        )
                     },
                 )
                 .collect::<Vec<_>>();
@@ -2193,7 +4416,7 @@ impl<T: Write> TerminalApp<T> {
             }
             self.term.flush()?;
             // Wait for new input.
-            match event::read()? {
+            match self.read_event()? {
                 // Quit menu.
                 Event::Key(KeyEvent {
                     code: KeyCode::Char('c'),
@@ -2247,86 +4470,31 @@ impl<T: Write> TerminalApp<T> {
         game: &Game,
         running_game_stats: &mut RunningGameStats,
     ) -> FinishedGameStats {
-        let finished_game_stats = FinishedGameStats {
-            timestamp: chrono::Utc::now().format("%Y-%m-%d %H:%M").to_string(),
-            actions: running_game_stats.0,
-            score_bonuses: running_game_stats.1.clone(),
-            gamemode: game.mode().clone(),
-            last_state: game.state().clone(),
-        };
+        let finished_game_stats = FinishedGameStats::new(game, running_game_stats);
         self.past_games.push(finished_game_stats.clone());
-        self.past_games
-            .sort_by(|stats1, stats2| {
-                // First sort by gamemode.
-                stats1.gamemode.name.cmp(&stats2.gamemode.name).then_with(|| {
+        let modes = &self.modes;
+        self.past_games.sort_by(|stats1, stats2| {
+            // First sort by gamemode.
+            stats1
+                .gamemode
+                .name
+                .cmp(&stats2.gamemode.name)
+                .then_with(|| {
                     // Sort by whether game was finished successfully or not.
                     let end1 = stats1.last_state.end.is_some_and(|end| end.is_ok());
                     let end2 = stats2.last_state.end.is_some_and(|end| end.is_ok());
                     end1.cmp(&end2).reverse().then_with(|| {
                         // Depending on gamemode, sort differently.
-                        match stats1.gamemode.name.as_str() {
-                            "Marathon" => {
-                                // Sort desc by level.
-                                stats1.last_state.level.cmp(&stats2.last_state.level).reverse().then_with(||
-                                    // Sort desc by score.
-
-                                    stats1.last_state.score.cmp(&stats2.last_state.score).reverse()
-                                )
-                            },
-                            "40-Lines" => {
-                                // Sort desc by lines.
-                                stats1.last_state.lines_cleared.cmp(&stats2.last_state.lines_cleared).reverse().then_with(||
-                                    // Sort asc by time.
-                                    stats1.last_state.time.cmp(&stats2.last_state.time)
-                                )
-                            },
-                            "Time Trial" => {
-                                // Sort asc by time.
-                                stats1.last_state.time.cmp(&stats2.last_state.time).then_with(||
-                                    // Sort by desc score.
-                                    stats1.last_state.score.cmp(&stats2.last_state.score).reverse()
-                                )
-                            },
-                            "Master" => {
-                                // Sort desc by lines.
-                                stats1.last_state.lines_cleared.cmp(&stats2.last_state.lines_cleared).reverse()
-                            },
-                            "Puzzle" => {
-                                // Sort desc by level.
-                                stats1.last_state.level.cmp(&stats2.last_state.level).reverse().then_with(||
-                                    // Sort asc by time.
-                                    stats1.last_state.time.cmp(&stats2.last_state.time)
-                                )
-                            },
-                            "Descent" => {
-                                // Sort desc by score.
-                                stats1.last_state.score.cmp(&stats2.last_state.score).reverse().then_with(||
-                                    // Sort desc by depth.
-                                    stats1.last_state.lines_cleared.cmp(&stats2.last_state.lines_cleared).reverse()
-                                )
-                            },
-                            "Cheese" => {
-                                // Sort desc by lines.
-                                stats1.last_state.lines_cleared.cmp(&stats2.last_state.lines_cleared).reverse().then_with(||
-                                    // Sort asc by number of pieces played.
-                                    stats1.last_state.pieces_played.iter().sum::<u32>().cmp(&stats2.last_state.pieces_played.iter().sum::<u32>())
-                                )
-                            },
-                            "Combo" => {
-                                // Sort desc by lines.
-                                stats1.last_state.lines_cleared.cmp(&stats2.last_state.lines_cleared).reverse()
-                            },
-                            _ => {
-                                // Sort desc by lines.
-                                stats1.last_state.lines_cleared.cmp(&stats2.last_state.lines_cleared).reverse()
-                            },
-                        }.then_with(|| {
-                            // Sort asc by timestamp.
-                            stats1.timestamp.cmp(&stats2.timestamp)
-                        })
+                        modes
+                            .get(&stats1.gamemode.name)
+                            .compare(&stats1.gamemode, &stats1.last_state, &stats2.last_state)
+                            .then_with(|| {
+                                // Sort asc by timestamp.
+                                stats1.timestamp.cmp(&stats2.timestamp)
+                            })
                     })
                 })
-            });
+        });
         finished_game_stats
     }
 }
@@ -2342,6 +4510,15 @@ pub fn fmt_duration(dur: Duration) -> String {
     )
 }
 
+pub fn fmt_level_progression(level_progression: &LevelProgression) -> String {
+    match level_progression {
+        LevelProgression::Lines(lines) => format!("every {lines} lines"),
+        LevelProgression::VariableGoal => "variable goal".to_string(),
+        LevelProgression::Sections(points) => format!("{points}-point sections"),
+        LevelProgression::Time(interval) => format!("every {}", fmt_duration(*interval)),
+    }
+}
+
 pub fn fmt_key(key: KeyCode) -> String {
     format!(
         "[{}]",
@@ -2373,3 +4550,69 @@ pub fn fmt_keybinds(button: Button, keybinds: &HashMap<KeyCode, Button>) -> Stri
         .collect::<Vec<String>>()
         .join(" ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fumen_recordings_show_the_board_before_each_lock() {
+        let recording = FumenRecording::default();
+        let mut game = Game::new(GameMode::marathon());
+        unsafe { game.add_modifier(recording.modifier()) };
+        // Several pieces fall and lock within a single update.
+        assert!(game.update(None, Duration::from_secs(60)).is_ok());
+        let pages = recording.pages.borrow();
+        let pieces_played = game.state().pieces_played.iter().sum::<u32>();
+        assert!(pieces_played > 1);
+        assert_eq!(pages.len(), usize::try_from(pieces_played).unwrap());
+        assert!(pages[0]
+            .board
+            .iter()
+            .all(|line| line.iter().all(Option::is_none)));
+        let first_piece = pages[0].piece.unwrap();
+        assert!(first_piece.tiles().iter().all(
+            |((x, y), _)| pages[0].board[*y][*x].is_none() && pages[1].board[*y][*x].is_some()
+        ));
+    }
+
+    #[test]
+    fn new_game_entries_without_netplay() {
+        let entries = (0..7)
+            .map(|selected| NewGameEntry::at(selected, 3, false, 1))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                NewGameEntry::Normal(0),
+                NewGameEntry::Normal(1),
+                NewGameEntry::Normal(2),
+                NewGameEntry::Versus,
+                NewGameEntry::VersusCpu,
+                // Not the online versus mode, which is not listed.
+                NewGameEntry::Special(0),
+                NewGameEntry::Custom,
+            ]
+        );
+    }
+
+    #[test]
+    fn new_game_entries_with_netplay() {
+        let entries = (0..8)
+            .map(|selected| NewGameEntry::at(selected, 3, true, 1))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                NewGameEntry::Normal(0),
+                NewGameEntry::Normal(1),
+                NewGameEntry::Normal(2),
+                NewGameEntry::Versus,
+                NewGameEntry::VersusCpu,
+                NewGameEntry::VersusOnline,
+                NewGameEntry::Special(0),
+                NewGameEntry::Custom,
+            ]
+        );
+    }
+}
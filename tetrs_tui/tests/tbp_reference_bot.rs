@@ -0,0 +1,187 @@
+//! Plays a game of the engine with `tbp_reference_bot`, talking the Tetris Bot Protocol to it the
+//! way `./tetrs_tui --bot` does.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use serde_json::{json, Value};
+use tetrs_engine::{Button, ButtonsPressed, Coord, Game, GameConfig, GameMode, Tetromino};
+
+fn piece_char(piece: Tetromino) -> char {
+    match piece {
+        Tetromino::O => 'O',
+        Tetromino::I => 'I',
+        Tetromino::S => 'S',
+        Tetromino::Z => 'Z',
+        Tetromino::T => 'T',
+        Tetromino::L => 'L',
+        Tetromino::J => 'J',
+    }
+}
+
+/// The cells of a TBP location, as given by the specification (SRS rotation centers).
+fn tbp_cells(location: &Value) -> Vec<Coord> {
+    let minos: [(isize, isize); 4] = match location["type"].as_str().unwrap() {
+        "O" => [(0, 0), (1, 0), (0, 1), (1, 1)],
+        "I" => [(-1, 0), (0, 0), (1, 0), (2, 0)],
+        "S" => [(-1, 0), (0, 0), (0, 1), (1, 1)],
+        "Z" => [(-1, 1), (0, 1), (0, 0), (1, 0)],
+        "T" => [(-1, 0), (0, 0), (1, 0), (0, 1)],
+        "L" => [(-1, 0), (0, 0), (1, 0), (1, 1)],
+        "J" => [(-1, 0), (0, 0), (1, 0), (-1, 1)],
+        piece => panic!("unknown piece {piece:?}"),
+    };
+    let (x, y) = (
+        location["x"].as_i64().unwrap() as isize,
+        location["y"].as_i64().unwrap() as isize,
+    );
+    let mut cells = minos
+        .map(|(dx, dy)| {
+            let (dx, dy) = match location["orientation"].as_str().unwrap() {
+                "north" => (dx, dy),
+                "east" => (dy, -dx),
+                "south" => (-dx, -dy),
+                "west" => (-dy, dx),
+                orientation => panic!("unknown orientation {orientation:?}"),
+            };
+            ((x + dx) as usize, (y + dy) as usize)
+        })
+        .to_vec();
+    cells.sort();
+    cells
+}
+
+fn active_cells(game: &Game) -> Vec<Coord> {
+    let (piece, _) = game.state().active_piece_data.unwrap();
+    let mut cells = piece.tiles().map(|(coord, _)| coord).to_vec();
+    cells.sort();
+    cells
+}
+
+/// Cells moved so that the leftmost, lowest one is at the origin.
+fn shape(cells: &[Coord]) -> Vec<Coord> {
+    let x_min = cells.iter().map(|(x, _)| *x).min().unwrap();
+    let y_min = cells.iter().map(|(_, y)| *y).min().unwrap();
+    cells.iter().map(|(x, y)| (x - x_min, y - y_min)).collect()
+}
+
+struct Player {
+    game: Game,
+    time: Duration,
+}
+
+impl Player {
+    fn press(&mut self, button: Button) {
+        let mut buttons_pressed = ButtonsPressed::default();
+        buttons_pressed[button] = true;
+        for buttons_pressed in [buttons_pressed, ButtonsPressed::default()] {
+            self.time += Duration::from_millis(1);
+            assert!(self.game.update(Some(buttons_pressed), self.time).is_ok());
+        }
+    }
+
+    /// Lets time pass until the next piece has spawned.
+    fn await_piece(&mut self) {
+        while self.game.state().active_piece_data.is_none() {
+            self.time += Duration::from_millis(10);
+            assert!(self.game.update(None, self.time).is_ok());
+        }
+    }
+
+    /// Moves the active piece into the target cells with straight drops, like the bot plans it.
+    fn place(&mut self, target: &[Coord]) {
+        for _ in 0..4 {
+            if shape(&active_cells(&self.game)) == shape(target) {
+                break;
+            }
+            self.press(Button::RotateRight);
+        }
+        let target_x = target.iter().map(|(x, _)| *x).min().unwrap();
+        loop {
+            let x = active_cells(&self.game)
+                .iter()
+                .map(|(x, _)| *x)
+                .min()
+                .unwrap();
+            if x == target_x {
+                break;
+            }
+            self.press(if x < target_x {
+                Button::MoveRight
+            } else {
+                Button::MoveLeft
+            });
+        }
+        self.press(Button::DropSonic);
+        assert_eq!(active_cells(&self.game), target);
+        self.press(Button::DropHard);
+    }
+}
+
+#[test]
+fn reference_bot_plays_the_engine() {
+    let mut bot = Command::new(env!("CARGO_BIN_EXE_tbp_reference_bot"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut to_bot = bot.stdin.take().unwrap();
+    let mut from_bot = BufReader::new(bot.stdout.take().unwrap()).lines();
+    let mut send = |message: Value| writeln!(to_bot, "{message}").unwrap();
+    let mut recv =
+        || -> Value { serde_json::from_str(&from_bot.next().unwrap().unwrap()).unwrap() };
+
+    assert_eq!(recv()["type"], "info");
+    send(json!({ "type": "rules" }));
+    assert_eq!(recv()["type"], "ready");
+
+    let mut player = Player {
+        game: Game::with_seed(GameMode::marathon(), GameConfig::default(), 7),
+        time: Duration::ZERO,
+    };
+    player.await_piece();
+    let state = player.game.state();
+    let queue = std::iter::once(state.active_piece_data.unwrap().0.shape)
+        .chain(state.next_pieces.iter().copied())
+        .map(piece_char)
+        .collect::<Vec<_>>();
+    send(json!({
+        "type": "start",
+        "hold": null,
+        "queue": queue,
+        "combo": 0,
+        "back_to_back": false,
+        "board": vec![vec![Value::Null; Game::WIDTH]; 40],
+    }));
+
+    let piece_count = 50;
+    for _ in 0..piece_count {
+        send(json!({ "type": "suggest" }));
+        let suggestion = recv();
+        assert_eq!(suggestion["type"], "suggestion");
+        let current = piece_char(player.game.state().active_piece_data.unwrap().0.shape);
+        // Placements of the held piece are not played here, to keep the queues simple.
+        let mv = suggestion["moves"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|mv| mv["location"]["type"] == current.to_string())
+            .expect("no placement suggested for the current piece")
+            .clone();
+        player.place(&tbp_cells(&mv["location"]));
+        send(json!({ "type": "play", "move": mv }));
+        player.await_piece();
+        let revealed = *player.game.state().next_pieces.back().unwrap();
+        send(json!({ "type": "new_piece", "piece": piece_char(revealed) }));
+    }
+    send(json!({ "type": "quit" }));
+    assert!(bot.wait().unwrap().success());
+
+    let state = player.game.state();
+    assert!(state.end.is_none());
+    assert_eq!(state.pieces_played.iter().sum::<u32>(), piece_count);
+    assert!(state.lines_cleared > 0);
+}